@@ -25,7 +25,7 @@ pub fn render(c: &mut Criterion) {
         "files", "fd -t f",
     )]);
 
-    let config = Config::new(&ConfigEnv::init().unwrap(), None).unwrap();
+    let config = Config::new(&ConfigEnv::init().unwrap(), None, None).unwrap();
     let backend = TestBackend::new(width, height);
     let mut terminal = Terminal::new(backend).unwrap();
     let (tx, _) = tokio::sync::mpsc::unbounded_channel();
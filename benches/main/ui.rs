@@ -34,7 +34,8 @@ pub fn draw(c: &mut Criterion) {
             // FIXME: this is kind of hacky
             || {
                 let config =
-                    Config::new(&ConfigEnv::init().unwrap(), None).unwrap();
+                    Config::new(&ConfigEnv::init().unwrap(), None, None)
+                        .unwrap();
                 let backend = TestBackend::new(width, height);
                 let terminal = Terminal::new(backend).unwrap();
                 let (tx, _) = tokio::sync::mpsc::unbounded_channel();
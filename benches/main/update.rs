@@ -19,7 +19,7 @@ fn setup_tv() -> Television {
         "files", "fd -t f",
     )]);
 
-    let config = Config::new(&ConfigEnv::init().unwrap(), None).unwrap();
+    let config = Config::new(&ConfigEnv::init().unwrap(), None, None).unwrap();
     let (tx, _) = tokio::sync::mpsc::unbounded_channel();
     let channel_prototype = cable.get_channel("files");
     let layered_config = ConfigLayers::new(
@@ -1,15 +1,36 @@
-use crate::event::Key;
+use crate::{
+    event::Key, notifications::Severity, television::ActionOutputPopup,
+};
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_with::{OneOrMany, serde_as};
+use strum::{EnumIter, IntoEnumIterator};
 
 /// The different actions that can be performed by the application.
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Hash, PartialOrd, Ord)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Deserialize,
+    Hash,
+    PartialOrd,
+    Ord,
+    JsonSchema,
+    EnumIter,
+)]
 #[serde(rename_all = "snake_case")]
 pub enum Action {
     // input actions
     /// Add a character to the input buffer.
     #[serde(skip)]
     AddInputChar(char),
+    /// Insert pasted text into the input buffer in one go.
+    #[serde(skip)]
+    InsertText(String),
+    /// Replace the content of the input buffer with the given text.
+    #[serde(skip)]
+    SetInput(String),
     /// Delete the character before the cursor from the input buffer.
     DeletePrevChar,
     /// Delete the previous word from the input buffer.
@@ -26,6 +47,10 @@ pub enum Action {
     GoToInputStart,
     /// Move the cursor to the end of the input buffer.
     GoToInputEnd,
+    /// Switch the input to insert mode (vim mode only).
+    EnterInsertMode,
+    /// Switch the input to normal mode (vim mode only).
+    EnterNormalMode,
     // rendering actions
     /// Render the terminal user interface screen.
     #[serde(skip)]
@@ -46,6 +71,7 @@ pub enum Action {
     /// Select the entry currently under the cursor and exit the application.
     SelectAndExit,
     /// Confirm selection using one of the `expect` keys.
+    #[strum(disabled)]
     Expect(Key),
     /// Select the next entry in the currently focused list.
     SelectNextEntry,
@@ -55,6 +81,15 @@ pub enum Action {
     SelectNextPage,
     /// Select the previous page of entries in the currently focused list.
     SelectPrevPage,
+    /// Select the first entry in the currently focused list.
+    SelectFirstEntry,
+    /// Select the last entry in the currently focused list.
+    SelectLastEntry,
+    /// Remove the entry under cursor from the list of selected entries.
+    DeselectEntry,
+    /// Start or stop selecting the range of entries between the current
+    /// entry and the cursor (vim mode only).
+    ToggleVisualMode,
     /// Copy the currently selected entry to the clipboard.
     CopyEntryToClipboard,
     // preview actions
@@ -84,15 +119,22 @@ pub enum Action {
     /// Toggle a UI feature.
     ToggleRemoteControl,
     ToggleActionPicker,
+    ToggleCommandPalette,
     ToggleHelp,
     ToggleStatusBar,
     TogglePreview,
+    /// Show the notifications raised during the session.
+    ToggleNotificationHistory,
     /// Switch between the portrait and landscape modes.
     #[serde(rename = "toggle_layout")]
     ToggleOrientation,
     /// Signal an error with the given message.
     #[serde(skip)]
     Error(String),
+    /// Display a notification with the given severity and message.
+    #[serde(skip)]
+    #[strum(disabled)]
+    Notify(Severity, String),
     /// No operation.
     NoOp,
     // Channel actions
@@ -102,16 +144,29 @@ pub enum Action {
     CyclePreviews,
     /// Reload the current source command.
     ReloadSource,
+    /// Cycle between the fuzzy, substring and regex matching modes.
+    CycleMatchMode,
+    /// Cycle between smart case, case sensitive and case insensitive
+    /// matching.
+    ToggleCaseSensitivity,
+    /// Enable or disable sorting the results by score.
+    ToggleSort,
     /// Switch to the specified channel directly via shortcut.
     #[serde(skip)]
     SwitchToChannel(String),
     /// Timer action for watch mode to trigger periodic reloads.
     #[serde(skip)]
     WatchTimer,
+    /// Reload the configuration, themes and cable channels after they
+    /// changed on disk.
+    #[serde(skip)]
+    ReloadConfig,
     /// Navigate to the previous entry in the history.
     SelectPrevHistory,
     /// Navigate to the next entry in the history.
     SelectNextHistory,
+    /// Fuzzy search the history and reuse one of its queries.
+    ToggleHistoryPicker,
     // Mouse and position-aware actions
     /// Select an entry at a specific position (e.g., from mouse click)
     #[serde(skip)]
@@ -119,6 +174,31 @@ pub enum Action {
     /// Handle mouse click event at specific coordinates
     #[serde(skip)]
     MouseClickAt(u16, u16),
+    /// Toggle the selection of the entry at a specific position (e.g., from
+    /// a ctrl-click)
+    #[serde(skip)]
+    ToggleSelectionAtPosition(u16, u16),
+    /// Select every entry between the current one and the one at a specific
+    /// position (e.g., from a shift-click)
+    #[serde(skip)]
+    SelectRangeToPosition(u16, u16),
+    /// Handle the mouse being dragged to specific coordinates
+    #[serde(skip)]
+    MouseDragTo(u16, u16),
+    /// Handle the release of the mouse button
+    #[serde(skip)]
+    MouseRelease,
+    /// Continue an external action with the values the user entered for its
+    /// inputs.
+    #[serde(skip)]
+    SubmitActionInputs(String, Vec<(String, String)>),
+    /// Run an external action that has already been confirmed by the user.
+    #[serde(skip)]
+    RunExternalAction(String, Vec<(String, String)>),
+    /// Show the output of an action that ran in `ExecutionMode::Capture`.
+    #[serde(skip)]
+    #[strum(disabled)]
+    ShowActionOutput(Box<ActionOutputPopup>),
     /// Execute an external action
     #[serde(untagged)]
     ExternalAction(String),
@@ -127,6 +207,20 @@ pub enum Action {
 /// Prefix used to identify custom external actions defined by the user in a channel's prototype.
 pub const CUSTOM_ACTION_PREFIX: &str = "actions:";
 
+/// Built-in actions listed in the command palette: every action that can be
+/// bound to a key, except for the palette toggle itself.
+pub fn command_palette_actions() -> impl Iterator<Item = Action> {
+    Action::iter().filter(|action| {
+        action.config_name().is_some()
+            && !matches!(
+                action,
+                Action::ExternalAction(_)
+                    | Action::ToggleCommandPalette
+                    | Action::NoOp
+            )
+    })
+}
+
 /// Container for one or more actions that can be executed together.
 ///
 /// This enum enables binding single keys to multiple actions, allowing for
@@ -167,7 +261,9 @@ pub const CUSTOM_ACTION_PREFIX: &str = "actions:";
 /// assert_eq!(actions_vec, vec![Action::ReloadSource, Action::Quit]);
 /// ```
 #[serde_as]
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Hash, PartialOrd, Ord)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, Deserialize, Hash, PartialOrd, Ord, JsonSchema,
+)]
 #[serde(transparent)]
 pub struct Actions {
     #[serde_as(as = "OneOrMany<_>")]
@@ -307,6 +403,8 @@ impl Action {
                 | Action::TogglePreview
                 | Action::ToggleStatusBar
                 | Action::ToggleOrientation
+                | Action::EnterInsertMode
+                | Action::EnterNormalMode
                 | Action::CopyEntryToClipboard
                 | Action::OpenEntry
                 | Action::Render
@@ -314,10 +412,97 @@ impl Action {
                 | Action::Suspend
                 | Action::Resume
                 | Action::Error(_)
+                | Action::Notify(_, _)
+                | Action::ToggleNotificationHistory
+                | Action::MouseRelease
                 | Action::NoOp
         )
     }
 
+    /// Returns the name used to refer to this action in configuration files
+    /// (e.g. `toggle_preview`).
+    ///
+    /// Returns `None` for internal actions that can't be bound to a key.
+    pub fn config_name(&self) -> Option<&str> {
+        Some(match self {
+            Action::DeletePrevChar => "delete_prev_char",
+            Action::DeletePrevWord => "delete_prev_word",
+            Action::DeleteNextChar => "delete_next_char",
+            Action::DeleteLine => "delete_line",
+            Action::GoToPrevChar => "go_to_prev_char",
+            Action::GoToNextChar => "go_to_next_char",
+            Action::GoToInputStart => "go_to_input_start",
+            Action::GoToInputEnd => "go_to_input_end",
+            Action::EnterInsertMode => "enter_insert_mode",
+            Action::EnterNormalMode => "enter_normal_mode",
+            Action::ToggleSelectionDown => "toggle_selection_down",
+            Action::ToggleSelectionUp => "toggle_selection_up",
+            Action::ConfirmSelection => "confirm_selection",
+            Action::SelectAndExit => "select_and_exit",
+            Action::SelectNextEntry => "select_next_entry",
+            Action::SelectPrevEntry => "select_prev_entry",
+            Action::SelectNextPage => "select_next_page",
+            Action::SelectPrevPage => "select_prev_page",
+            Action::SelectFirstEntry => "select_first_entry",
+            Action::SelectLastEntry => "select_last_entry",
+            Action::DeselectEntry => "deselect_entry",
+            Action::ToggleVisualMode => "toggle_visual_mode",
+            Action::CopyEntryToClipboard => "copy_entry_to_clipboard",
+            Action::ScrollPreviewUp => "scroll_preview_up",
+            Action::ScrollPreviewDown => "scroll_preview_down",
+            Action::ScrollPreviewHalfPageUp => "scroll_preview_half_page_up",
+            Action::ScrollPreviewHalfPageDown => {
+                "scroll_preview_half_page_down"
+            }
+            Action::Quit => "quit",
+            Action::ToggleRemoteControl => "toggle_remote_control",
+            Action::ToggleActionPicker => "toggle_action_picker",
+            Action::ToggleCommandPalette => "toggle_command_palette",
+            Action::ToggleHelp => "toggle_help",
+            Action::ToggleStatusBar => "toggle_status_bar",
+            Action::TogglePreview => "toggle_preview",
+            Action::ToggleNotificationHistory => "toggle_notification_history",
+            Action::ToggleOrientation => "toggle_layout",
+            Action::NoOp => "no_op",
+            Action::CycleSources => "cycle_sources",
+            Action::CyclePreviews => "cycle_previews",
+            Action::ReloadSource => "reload_source",
+            Action::CycleMatchMode => "cycle_match_mode",
+            Action::ToggleCaseSensitivity => "toggle_case_sensitivity",
+            Action::ToggleSort => "toggle_sort",
+            Action::SelectPrevHistory => "select_prev_history",
+            Action::SelectNextHistory => "select_next_history",
+            Action::ToggleHistoryPicker => "toggle_history_picker",
+            Action::ExternalAction(name) => name,
+            // internal actions, and `expect` which needs a key
+            Action::AddInputChar(_)
+            | Action::InsertText(_)
+            | Action::SetInput(_)
+            | Action::Render
+            | Action::Resize(_, _)
+            | Action::ClearScreen
+            | Action::Expect(_)
+            | Action::OpenEntry
+            | Action::Tick
+            | Action::Suspend
+            | Action::Resume
+            | Action::Error(_)
+            | Action::Notify(_, _)
+            | Action::SwitchToChannel(_)
+            | Action::WatchTimer
+            | Action::ReloadConfig
+            | Action::SelectEntryAtPosition(_, _)
+            | Action::MouseClickAt(_, _)
+            | Action::ToggleSelectionAtPosition(_, _)
+            | Action::SelectRangeToPosition(_, _)
+            | Action::MouseDragTo(_, _)
+            | Action::MouseRelease
+            | Action::SubmitActionInputs(_, _)
+            | Action::RunExternalAction(_, _)
+            | Action::ShowActionOutput(_) => return None,
+        })
+    }
+
     /// Returns a user-friendly description of the action for help panels and UI display.
     ///
     /// This method provides human-readable descriptions of actions that are suitable
@@ -342,6 +527,8 @@ impl Action {
         match self {
             // Input actions
             Action::AddInputChar(_) => "Add character",
+            Action::InsertText(_) => "Insert text",
+            Action::SetInput(_) => "Set input",
             Action::DeletePrevChar => "Delete previous char",
             Action::DeletePrevWord => "Delete previous word",
             Action::DeleteNextChar => "Delete next char",
@@ -350,6 +537,8 @@ impl Action {
             Action::GoToNextChar => "Move cursor right",
             Action::GoToInputStart => "Move to start",
             Action::GoToInputEnd => "Move to end",
+            Action::EnterInsertMode => "Insert mode",
+            Action::EnterNormalMode => "Normal mode",
 
             // Rendering actions (typically not shown in help)
             Action::Render => "Render",
@@ -362,12 +551,16 @@ impl Action {
             Action::ConfirmSelection => "Select entry",
             Action::SelectAndExit => "Select and exit",
             Action::Expect(_) => "Expect key",
+            Action::DeselectEntry => "Deselect entry",
+            Action::ToggleVisualMode => "Toggle visual selection",
 
             // Navigation actions
             Action::SelectNextEntry => "Navigate down",
             Action::SelectPrevEntry => "Navigate up",
             Action::SelectNextPage => "Page down",
             Action::SelectPrevPage => "Page up",
+            Action::SelectFirstEntry => "Go to first",
+            Action::SelectLastEntry => "Go to last",
             Action::CopyEntryToClipboard => "Copy to clipboard",
 
             // Preview actions
@@ -388,32 +581,49 @@ impl Action {
             // Toggle actions
             Action::ToggleRemoteControl => "Toggle remote control",
             Action::ToggleActionPicker => "Toggle action picker",
+            Action::ToggleCommandPalette => "Toggle command palette",
             Action::ToggleHelp => "Toggle help",
             Action::ToggleStatusBar => "Toggle status bar",
             Action::TogglePreview => "Toggle preview",
             Action::ToggleOrientation => "Toggle layout",
+            Action::ToggleNotificationHistory => "Toggle notifications",
 
             // Error and no-op
             Action::Error(_) => "Error",
+            Action::Notify(_, _) => "Notify",
             Action::NoOp => "No operation",
 
             // Channel actions
             Action::CycleSources => "Cycle sources",
             Action::CyclePreviews => "Cycle previews",
             Action::ReloadSource => "Reload source",
+            Action::CycleMatchMode => "Cycle match mode",
+            Action::ToggleCaseSensitivity => "Toggle case sensitivity",
+            Action::ToggleSort => "Toggle sort",
             Action::SwitchToChannel(_) => "Switch to channel",
             Action::WatchTimer => "Watch timer",
+            Action::ReloadConfig => "Reload configuration",
 
             // History actions
             Action::SelectPrevHistory => "Previous history",
             Action::SelectNextHistory => "Next history",
+            Action::ToggleHistoryPicker => "Search history",
 
             // Mouse actions
             Action::SelectEntryAtPosition(_, _) => "Select at position",
             Action::MouseClickAt(_, _) => "Mouse click",
+            Action::ToggleSelectionAtPosition(_, _) => {
+                "Toggle selection at position"
+            }
+            Action::SelectRangeToPosition(_, _) => "Select range to position",
+            Action::MouseDragTo(_, _) => "Mouse drag",
+            Action::MouseRelease => "Mouse release",
 
             // External actions
-            Action::ExternalAction(a) => a,
+            Action::ExternalAction(a)
+            | Action::SubmitActionInputs(a, _)
+            | Action::RunExternalAction(a, _) => a,
+            Action::ShowActionOutput(_) => "Show action output",
         }
     }
 }
@@ -446,6 +656,39 @@ mod tests {
         assert_eq!(multiple_from_vec.as_slice(), actions_vec.as_slice());
     }
 
+    #[test]
+    fn test_action_config_name() {
+        assert_eq!(
+            Action::TogglePreview.config_name(),
+            Some("toggle_preview")
+        );
+        assert_eq!(
+            Action::ToggleOrientation.config_name(),
+            Some("toggle_layout")
+        );
+        assert_eq!(
+            Action::ExternalAction("actions:edit".to_string()).config_name(),
+            Some("actions:edit")
+        );
+        assert_eq!(Action::Render.config_name(), None);
+        assert_eq!(
+            Action::SwitchToChannel("files".into()).config_name(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_action_config_names_match_deserialization() {
+        for action in Action::iter() {
+            if let Some(name) = action.config_name() {
+                let parsed: Action =
+                    serde_json::from_value(serde_json::json!(name)).unwrap();
+                assert_eq!(parsed, action, "`{name}`");
+            }
+        }
+        assert!(command_palette_actions().any(|a| a == Action::DeleteLine));
+    }
+
     #[test]
     fn test_actions_as_slice() {
         let single: Actions = Actions::single(Action::DeleteLine);
@@ -7,19 +7,28 @@ use crate::{
         entry::Entry,
         prototypes::{ActionSpec, ExecutionMode},
     },
-    config::layers::ConfigLayers,
+    config::{
+        CONFIG_FILE_NAME, Config, ConfigEnv, THEMES_DIR_NAME,
+        describe_toml_error, get_config_dir, layers::ConfigLayers,
+        user_config::ConfigFileContext, watcher::ConfigWatcher,
+    },
     event::{ControlEvent, Event, EventLoop, Key},
     frecency::{Frecency, FrecencyHandle},
     history::History,
+    keymap::ResolvedInput,
     mouse::get_action_for_mouse_event,
+    notifications::Severity,
     render::{RenderingTask, UiState, render},
-    television::{Mode, Television},
+    television::{
+        ActionInputPopup, ActionOutputPopup, ConfirmActionPopup, Mode,
+        Television, VimMode,
+    },
     tui::{IoStream, Tui, TuiMode},
-    utils::command::execute_action,
+    utils::command::{capture_action, execute_action},
 };
 use anyhow::Result;
 use rustc_hash::FxHashSet;
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 use tokio::sync::mpsc;
 use tracing::{debug, error, trace};
 
@@ -55,6 +64,8 @@ pub struct App {
     render_task: Option<tokio::task::JoinHandle<Result<()>>>,
     /// Watch timer task handle for periodic reloading
     watch_timer_task: Option<tokio::task::JoinHandle<()>>,
+    /// Task watching the configuration, themes and cable channels for changes
+    config_watcher_task: Option<tokio::task::JoinHandle<()>>,
     /// Global history for selected entries
     history: History,
     /// Frecency manager for ranking previously-selected entries
@@ -68,7 +79,7 @@ pub enum ActionOutcome {
     EntriesWithExpect(FxHashSet<Entry>, Key),
     Input(String),
     None,
-    ExternalAction(ActionSpec, FxHashSet<Entry>),
+    ExternalAction(Box<ActionSpec>, FxHashSet<Entry>),
 }
 
 /// The result of the application.
@@ -107,7 +118,7 @@ impl AppOutput {
             ActionOutcome::ExternalAction(action_spec, entries) => Self {
                 selected_entries: None,
                 expect_key: None,
-                external_action: Some((action_spec, entries)),
+                external_action: Some((*action_spec, entries)),
             },
         }
     }
@@ -127,10 +138,13 @@ impl App {
 
         // Create frecency handle before Television so we can pass it
         let merged_for_frecency = layered_config.merge();
-        let frecency = Arc::new(Frecency::new(
-            merged_for_frecency.frecency_max_entries,
-            &merged_for_frecency.data_dir,
-        ));
+        let frecency = Arc::new(
+            Frecency::new(
+                merged_for_frecency.frecency_max_entries,
+                &merged_for_frecency.data_dir,
+            )
+            .with_config(merged_for_frecency.frecency),
+        );
         if let Err(e) = frecency.init() {
             error!("Failed to initialize frecency: {}", e);
         }
@@ -165,6 +179,7 @@ impl App {
             ui_state_tx,
             render_task: None,
             watch_timer_task: None,
+            config_watcher_task: None,
             history,
             frecency,
         };
@@ -224,6 +239,75 @@ impl App {
         self.start_watch_timer();
     }
 
+    /// The configuration file in use, whether or not it exists.
+    fn config_file(&self) -> PathBuf {
+        self.television
+            .merged_config
+            .config_file
+            .clone()
+            .unwrap_or_else(|| get_config_dir().join(CONFIG_FILE_NAME))
+    }
+
+    /// (Re)start watching the configuration file, the themes and the cable
+    /// directory so that changes are applied without restarting tv.
+    fn restart_config_watcher(&mut self) {
+        if let Some(task) = self.config_watcher_task.take() {
+            task.abort();
+        }
+        let mut paths = vec![
+            self.config_file(),
+            get_config_dir().join(THEMES_DIR_NAME),
+            self.television.merged_config.cable_dir.clone(),
+        ];
+        paths.extend(self.television.merged_config.included_paths.clone());
+        self.config_watcher_task =
+            Some(ConfigWatcher::spawn(paths, self.action_tx.clone()));
+    }
+
+    /// Reload the configuration after it or one of the files it refers to
+    /// changed on disk.
+    ///
+    /// A configuration file that can't be parsed is reported and the current
+    /// configuration is kept.
+    fn reload_config(&mut self) {
+        let config = ConfigEnv::init().and_then(|config_env| {
+            Config::with_overrides(
+                &config_env,
+                self.television.merged_config.config_file.as_deref(),
+                self.television.merged_config.profile.as_deref(),
+                self.television.layered_config.env_overrides(),
+            )
+        });
+        match config {
+            Ok(config) => {
+                self.television.reload_config(config);
+                self.update_history();
+                self.restart_watch_timer();
+                self.restart_config_watcher();
+            }
+            Err(e) => {
+                // the error may come from a file the configuration includes
+                let message = match (
+                    e.downcast_ref::<ConfigFileContext>(),
+                    e.downcast_ref::<toml::de::Error>(),
+                ) {
+                    (Some(ConfigFileContext(file)), Some(toml_error)) => {
+                        describe_toml_error(
+                            file,
+                            &std::fs::read_to_string(file).unwrap_or_default(),
+                            toml_error,
+                        )
+                    }
+                    _ => format!("{e:#}"),
+                };
+                self.television.notify(
+                    Severity::Error,
+                    format!("Failed to reload the configuration: {message}"),
+                );
+            }
+        }
+    }
+
     /// Update the `input_map` from the television's current config.
     ///
     /// This should be called whenever the channel changes to ensure the `input_map` includes the
@@ -235,8 +319,7 @@ impl App {
             let shortcut_keybindings =
                 rc.cable_channels.get_channels_shortcut_keybindings();
             Arc::make_mut(&mut self.television.merged_config)
-                .input_map
-                .merge_globals_with(&shortcut_keybindings);
+                .merge_global_shortcuts(&shortcut_keybindings);
         }
         debug!(
             "Updated input_map (with shortcuts): {:?}",
@@ -293,6 +376,8 @@ impl App {
             };
             let mut tui = Tui::new(stream, &tui_mode)
                 .expect("Failed to create TUI instance");
+            tui.keyboard_enhancements =
+                self.television.merged_config.keyboard_enhancements;
             debug!("Entering tui");
             tui.enter().expect("Failed to enter TUI mode");
 
@@ -339,6 +424,10 @@ impl App {
         // Start watch timer if configured
         self.start_watch_timer();
 
+        if !headless {
+            self.restart_config_watcher();
+        }
+
         self.television.channel.load();
 
         // Main loop
@@ -359,7 +448,7 @@ impl App {
                 > 0
             {
                 for event in event_buf.drain(..) {
-                    let actions = self.convert_event_to_actions(event);
+                    let actions = self.convert_event_to_actions(&event);
                     for action in actions {
                         if action != Action::Tick {
                             debug!("Queuing new action: {action:?}");
@@ -409,6 +498,9 @@ impl App {
                 if !headless {
                     self.event_control_tx.send(ControlEvent::Abort)?;
                 }
+                if let Some(task) = self.config_watcher_task.take() {
+                    task.abort();
+                }
 
                 if let Err(e) = self.history.save_to_file() {
                     error!("Failed to persist history: {}", e);
@@ -448,37 +540,64 @@ impl App {
     /// # Returns
     /// A vector of actions that correspond to the given event. Multiple actions
     /// will be returned for keys/events bound to action sequences.
-    fn convert_event_to_actions(&self, event: Event<Key>) -> Vec<Action> {
+    fn convert_event_to_actions(&mut self, event: &Event<Key>) -> Vec<Action> {
         let actions = match event {
             Event::Input(keycode) => {
-                // First try to get actions based on keybindings
-                if let Some(actions) = self
+                let had_pending = !self.television.pending_keys.is_empty();
+                let vim_mode = self.television.effective_vim_mode();
+                let resolved = self
                     .television
                     .merged_config
-                    .input_map
-                    .get_actions_for_key(&keycode, &self.television.mode)
-                {
-                    let actions_vec = actions.as_slice().to_vec();
-                    debug!("Keybinding found: {actions_vec:?}");
-                    actions_vec
+                    .input_map_for(vim_mode)
+                    .resolve_key(
+                        &mut self.television.pending_keys,
+                        *keycode,
+                        &self.television.mode,
+                    );
+                let mut actions = self.convert_resolved_input(resolved);
+                // refresh the pending sequence indicator
+                if had_pending || !self.television.pending_keys.is_empty() {
+                    actions.push(Action::Render);
+                }
+                actions
+            }
+            Event::Paste(text) => {
+                if self.television.accepts_text_input() {
+                    vec![Action::InsertText(text.clone())]
                 } else {
-                    // fallback to text input events
-                    match keycode {
-                        Key::Char(c) => vec![Action::AddInputChar(c)],
-                        _ => vec![Action::NoOp],
-                    }
+                    vec![]
                 }
             }
             Event::Mouse(me) => {
                 vec![get_action_for_mouse_event(
-                    me,
+                    *me,
                     &self.television.ui_state.layout,
                     self.television.mode,
                 )]
             }
             // terminal events
-            Event::Tick => vec![Action::Tick],
-            Event::Resize(x, y) => vec![Action::Resize(x, y)],
+            Event::Tick => {
+                let vim_mode = self.television.effective_vim_mode();
+                let resolved = self
+                    .television
+                    .merged_config
+                    .input_map_for(vim_mode)
+                    .resolve_timeout(
+                        &mut self.television.pending_keys,
+                        Duration::from_millis(
+                            self.television.merged_config.key_sequence_timeout,
+                        ),
+                        &self.television.mode,
+                    );
+                let mut actions = Vec::new();
+                if !resolved.is_empty() {
+                    actions = self.convert_resolved_input(resolved);
+                    actions.push(Action::Render);
+                }
+                actions.push(Action::Tick);
+                actions
+            }
+            Event::Resize(x, y) => vec![Action::Resize(*x, *y)],
             Event::FocusGained => vec![Action::Resume],
             Event::FocusLost => vec![Action::Suspend],
             Event::Closed => vec![Action::NoOp],
@@ -498,6 +617,44 @@ impl App {
             .collect()
     }
 
+    /// Convert keys (and completed key sequences) to the actions they're
+    /// bound to, falling back to text input for unbound characters unless
+    /// the input is in vim normal mode.
+    fn convert_resolved_input(
+        &self,
+        resolved: Vec<ResolvedInput>,
+    ) -> Vec<Action> {
+        let input_map = self
+            .television
+            .merged_config
+            .input_map_for(self.television.effective_vim_mode());
+        let mut actions = Vec::new();
+        for input in resolved {
+            match input {
+                ResolvedInput::Sequence(bound) => {
+                    debug!("Key sequence binding found: {bound:?}");
+                    actions.extend(bound.into_vec());
+                }
+                ResolvedInput::Key(keycode) => {
+                    // First try to get actions based on keybindings
+                    if let Some(bound) = input_map
+                        .get_actions_for_key(&keycode, &self.television.mode)
+                    {
+                        let actions_vec = bound.as_slice().to_vec();
+                        debug!("Keybinding found: {actions_vec:?}");
+                        actions.extend(actions_vec);
+                    } else if let Key::Char(c) = keycode
+                        && self.television.accepts_text_input()
+                    {
+                        // fallback to text input events
+                        actions.push(Action::AddInputChar(c));
+                    }
+                }
+            }
+        }
+        actions
+    }
+
     /// Handle actions.
     ///
     /// This function will handle all actions that are sent to the application.
@@ -524,11 +681,24 @@ impl App {
                 }
                 match action {
                     Action::Quit => {
-                        if self.television.mode == Mode::RemoteControl {
+                        if self.television.has_blocking_popup() {
+                            // let the television dismiss the popup
+                        } else if self.television.mode == Mode::RemoteControl {
                             self.action_tx
                                 .send(Action::ToggleRemoteControl)?;
                         } else if self.television.mode == Mode::ActionPicker {
                             self.action_tx.send(Action::ToggleActionPicker)?;
+                        } else if self.television.mode == Mode::CommandPalette
+                        {
+                            self.action_tx
+                                .send(Action::ToggleCommandPalette)?;
+                        } else if self.television.mode == Mode::HistoryPicker {
+                            self.action_tx
+                                .send(Action::ToggleHistoryPicker)?;
+                        } else if self.television.effective_vim_mode()
+                            == Some(VimMode::Visual)
+                        {
+                            self.action_tx.send(Action::ToggleVisualMode)?;
                         } else {
                             self.stop_watch_timer();
                             self.should_quit = true;
@@ -541,7 +711,11 @@ impl App {
                     Action::Resume => {
                         self.render_tx.send(RenderingTask::Resume)?;
                     }
-                    Action::SelectAndExit => {
+                    // popups (e.g. a pending confirmation) must be dismissed
+                    // before leaving tv
+                    Action::SelectAndExit
+                        if !self.television.has_blocking_popup() =>
+                    {
                         self.should_quit = true;
                         if !self.render_tx.is_closed() {
                             self.render_tx.send(RenderingTask::Quit)?;
@@ -557,7 +731,9 @@ impl App {
                             self.television.current_pattern.clone(),
                         ));
                     }
-                    Action::Expect(k) => {
+                    Action::Expect(k)
+                        if !self.television.has_blocking_popup() =>
+                    {
                         self.should_quit = true;
                         if !self.render_tx.is_closed() {
                             self.render_tx.send(RenderingTask::Quit)?;
@@ -608,47 +784,43 @@ impl App {
                             self.television.set_pattern("");
                         }
                     }
-                    Action::ExternalAction(ref action_name) => {
+                    Action::ReloadConfig => self.reload_config(),
+                    Action::ToggleHistoryPicker => {
+                        // list the queries of this session as well
+                        self.television.history =
+                            self.history.get_entries().to_vec();
+                    }
+                    Action::ExternalAction(ref action_name)
+                        if !self.television.has_blocking_popup() =>
+                    {
                         debug!("External action triggered: {}", action_name);
-
-                        if let Some(selected_entries) =
-                            self.television.get_selected_entries()
-                        {
-                            if let Some(action_spec) =
-                                self.television
-                                    .merged_config
-                                    .channel_actions
-                                    .get(action_name.trim_start_matches(
-                                        CUSTOM_ACTION_PREFIX,
-                                    ))
-                                    .cloned()
-                            {
-                                self.record_selection(&selected_entries)?;
-
-                                match action_spec.mode {
-                                    // suspend the TUI and execute the action
-                                    ExecutionMode::Fork => {
-                                        self.run_external_command_fork(
-                                            &action_spec,
-                                            &selected_entries,
-                                        )?;
-                                    }
-                                    // clean up and exit the TUI and execute the action
-                                    ExecutionMode::Execute => {
-                                        self.run_external_command_execute(
-                                            &action_spec,
-                                            &selected_entries,
-                                        )?;
-                                    }
-                                }
-                            }
-                        } else {
-                            debug!("No entries available for external action");
-                            self.action_tx.send(Action::Error(
-                                "No entry available for external action"
-                                    .to_string(),
-                            ))?;
-                        }
+                        self.handle_external_action(action_name, None, false)?;
+                    }
+                    Action::SubmitActionInputs(
+                        ref action_name,
+                        ref inputs,
+                    ) => {
+                        debug!(
+                            "External action inputs submitted: {}",
+                            action_name
+                        );
+                        self.handle_external_action(
+                            action_name,
+                            Some(inputs),
+                            false,
+                        )?;
+                    }
+                    Action::RunExternalAction(ref action_name, ref inputs) => {
+                        debug!("External action confirmed: {}", action_name);
+                        self.handle_external_action(
+                            action_name,
+                            Some(inputs),
+                            true,
+                        )?;
+                    }
+                    Action::ShowActionOutput(ref popup) => {
+                        self.television.action_output_popup =
+                            Some((**popup).clone());
                     }
                     _ => {}
                 }
@@ -675,11 +847,133 @@ impl App {
         Ok(ActionOutcome::None)
     }
 
+    /// Run the external action with the given name on the selected entries.
+    ///
+    /// Actions with unmet requirements are refused. Actions that declare
+    /// `inputs` first prompt the user for them (`inputs` is `None` until they
+    /// have been collected), and actions that define a `confirm` message open
+    /// a confirmation dialog instead of running unless `confirmed` is set.
+    fn handle_external_action(
+        &mut self,
+        action_name: &str,
+        inputs: Option<&[(String, String)]>,
+        confirmed: bool,
+    ) -> Result<()> {
+        let Some(selected_entries) = self.television.get_selected_entries()
+        else {
+            debug!("No entries available for external action");
+            self.action_tx.send(Action::Notify(
+                Severity::Warning,
+                "No entry available for external action".to_string(),
+            ))?;
+            return Ok(());
+        };
+        let action_name = action_name.trim_start_matches(CUSTOM_ACTION_PREFIX);
+        let Some(action_spec) = self
+            .television
+            .merged_config
+            .channel_actions
+            .get(action_name)
+            .cloned()
+        else {
+            return Ok(());
+        };
+
+        let unmet_requirements = action_spec.unmet_requirements();
+        if !unmet_requirements.is_empty() {
+            self.action_tx.send(Action::Error(format!(
+                "Action '{}' requires missing binaries: {}",
+                action_name,
+                unmet_requirements.join(", ")
+            )))?;
+            return Ok(());
+        }
+
+        let inputs = match inputs {
+            Some(inputs) => inputs.to_vec(),
+            None if !action_spec.inputs.is_empty() => {
+                self.television.action_input_popup = Some(
+                    ActionInputPopup::new(action_name, &action_spec.inputs),
+                );
+                return Ok(());
+            }
+            None => Vec::new(),
+        };
+        let action_spec = match action_spec.with_inputs(&inputs) {
+            Ok(action_spec) => action_spec,
+            Err(e) => {
+                self.action_tx.send(Action::Error(format!(
+                    "Failed to run action '{action_name}': {e}"
+                )))?;
+                return Ok(());
+            }
+        };
+
+        if !confirmed && let Some(confirm) = &action_spec.confirm {
+            match ConfirmActionPopup::new(
+                action_name,
+                confirm,
+                &action_spec,
+                &selected_entries,
+                inputs,
+            ) {
+                Ok(popup) => {
+                    self.television.confirm_action_popup = Some(popup);
+                }
+                Err(e) => {
+                    self.action_tx.send(Action::Error(e.to_string()))?;
+                }
+            }
+            return Ok(());
+        }
+
+        self.record_selection(&selected_entries)?;
+
+        match action_spec.mode {
+            // suspend the TUI and execute the action
+            ExecutionMode::Fork => {
+                if self.run_external_command_fork(
+                    action_name,
+                    &action_spec,
+                    &selected_entries,
+                )? {
+                    self.television.apply_post_actions(&action_spec.after)?;
+                }
+            }
+            // clean up and exit the TUI and execute the action
+            ExecutionMode::Execute => {
+                self.run_external_command_execute(
+                    &action_spec,
+                    &selected_entries,
+                )?;
+            }
+            // run the action in the background and show its output once it
+            // exits, post-actions are applied when the output panel is dismissed
+            ExecutionMode::Capture => {
+                self.action_tx.send(Action::Notify(
+                    Severity::Info,
+                    format!("Running action '{action_name}'..."),
+                ))?;
+                self.run_external_command_capture(
+                    action_name,
+                    action_spec,
+                    selected_entries,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Suspend the TUI while running the action.
+    ///
+    /// Returns whether the command could be run, failures being reported to
+    /// the user as notifications.
     fn run_external_command_fork(
         &self,
+        action_name: &str,
         action_spec: &ActionSpec,
         entries: &FxHashSet<Entry>,
-    ) -> Result<()> {
+    ) -> Result<bool> {
         // suspend the event loop
         self.event_control_tx
             .send(ControlEvent::Pause)
@@ -689,10 +983,7 @@ impl App {
             })?;
 
         // execute the external command in a separate process
-        execute_action(action_spec, entries).map_err(|e| {
-            error!("Failed to execute external action: {}", e);
-            anyhow::anyhow!("Failed to execute external action: {}", e)
-        })?;
+        let result = execute_action(action_spec, entries);
         // resume the event loop
         self.event_control_tx
             .send(ControlEvent::Resume)
@@ -702,7 +993,65 @@ impl App {
         // resume the TUI (after the event loop so as not to produce any artifacts)
         self.render_tx.send(RenderingTask::Resume)?;
 
-        Ok(())
+        match result {
+            Ok(status) if status.success() => Ok(true),
+            Ok(status) => {
+                let reason = status.code().map_or_else(
+                    || "was terminated by a signal".to_string(),
+                    |code| format!("exited with code {code}"),
+                );
+                self.action_tx.send(Action::Notify(
+                    Severity::Warning,
+                    format!("Action '{action_name}' {reason}"),
+                ))?;
+                Ok(true)
+            }
+            Err(e) => {
+                self.action_tx.send(Action::Error(format!(
+                    "Failed to run action '{action_name}': {e}"
+                )))?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Run the action on a blocking thread so the UI stays responsive, its
+    /// output is sent back as an `Action::ShowActionOutput`.
+    fn run_external_command_capture(
+        &self,
+        action_name: &str,
+        action_spec: ActionSpec,
+        entries: FxHashSet<Entry>,
+    ) {
+        let action_name = action_name.to_string();
+        let action_tx = self.action_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let popup = match capture_action(&action_spec, &entries) {
+                Ok(output) => ActionOutputPopup {
+                    action_name,
+                    command: output.command,
+                    stdout: output.stdout,
+                    stderr: output.stderr,
+                    exit_code: output.status.code(),
+                    scroll: 0,
+                    after: action_spec.after,
+                },
+                Err(e) => {
+                    error!("Failed to execute external action: {}", e);
+                    ActionOutputPopup {
+                        action_name,
+                        command: action_spec.command.to_string(),
+                        stdout: String::new(),
+                        stderr: e.to_string(),
+                        exit_code: None,
+                        scroll: 0,
+                        after: Vec::new(),
+                    }
+                }
+            };
+            // the receiver is gone if tv exited in the meantime
+            let _ = action_tx.send(Action::ShowActionOutput(Box::new(popup)));
+        });
     }
 
     fn run_external_command_execute(
@@ -780,14 +1129,33 @@ impl App {
         }
     }
 
+    /// Save the query and the selected entries to the history and frecency
+    /// data, unless the session, the channel or the exclusion patterns say
+    /// otherwise.
     fn record_selection(&mut self, entries: &FxHashSet<Entry>) -> Result<()> {
-        let channel_name = self.television.current_channel();
-        for entry in entries {
-            self.frecency.record_access(&channel_name, &entry.raw);
+        let config = &self.television.merged_config;
+        if config.incognito {
+            return Ok(());
+        }
+        let mut selected: Vec<String> = entries
+            .iter()
+            .map(|entry| entry.raw.clone())
+            .filter(|raw| !config.history_exclude.is_match(raw))
+            .collect();
+        selected.sort();
+
+        if config.channel_frecency {
+            let frecency = self.television.channel_frecency();
+            for raw in &selected {
+                frecency.record_access(raw);
+            }
         }
 
         let query = self.television.current_pattern.clone();
-        self.history.add_entry(query, channel_name)?;
+        if config.channel_history && !config.history_exclude.is_match(&query) {
+            let channel_name = self.television.current_channel();
+            self.history.add_selection(query, channel_name, selected)?;
+        }
         Ok(())
     }
 
@@ -818,6 +1186,8 @@ impl App {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::exclude::ExcludePatterns;
+    use rustc_hash::FxHashMap;
 
     #[test]
     fn test_determine_tui_mode() {
@@ -863,4 +1233,77 @@ mod tests {
         // Test error case for width without height
         assert!(App::determine_tui_mode(None, Some(80), false).is_err());
     }
+
+    /// The number of history and frecency entries recorded when picking an
+    /// entry with an app configured by `configure`.
+    fn recorded_selection(
+        configure: impl FnOnce(
+            &mut crate::config::Config,
+            &mut crate::channels::prototypes::ChannelPrototype,
+            &mut crate::cli::PostProcessedCli,
+        ),
+    ) -> (usize, usize) {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = crate::config::Config::default();
+        config.application.data_dir = dir.path().to_path_buf();
+        let mut prototype = crate::channels::prototypes::ChannelPrototype::new(
+            "test", "echo 1",
+        );
+        let mut cli = crate::cli::PostProcessedCli::default();
+        configure(&mut config, &mut prototype, &mut cli);
+        let mut app = App::new(
+            ConfigLayers::new(config, prototype, cli),
+            Cable::from_prototypes(vec![]),
+        );
+        app.television.set_pattern("secret query");
+
+        let entries =
+            FxHashSet::from_iter([Entry::new("secret entry".into())]);
+        app.record_selection(&entries).unwrap();
+        let frecency_entries = app
+            .frecency
+            .data()
+            .channels
+            .get("test")
+            .map_or(0, FxHashMap::len);
+        (app.history.get_entries().len(), frecency_entries)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_record_selection() {
+        assert_eq!(recorded_selection(|_, _, _| {}), (1, 1));
+        // incognito
+        assert_eq!(
+            recorded_selection(|_, _, cli| cli.global.no_history = true),
+            (0, 0)
+        );
+        // per-channel settings
+        assert_eq!(
+            recorded_selection(|_, prototype, _| {
+                prototype.history.enabled = Some(false);
+            }),
+            (0, 1)
+        );
+        assert_eq!(
+            recorded_selection(|_, prototype, _| {
+                prototype.source.frecency = false;
+            }),
+            (1, 0)
+        );
+        // exclusion patterns, for the query and the entries
+        assert_eq!(
+            recorded_selection(|config, _, _| {
+                config.application.history_exclude =
+                    ExcludePatterns::new(&["secret"]).unwrap();
+            }),
+            (0, 0)
+        );
+        assert_eq!(
+            recorded_selection(|config, _, _| {
+                config.application.history_exclude =
+                    ExcludePatterns::new(&["query"]).unwrap();
+            }),
+            (0, 1)
+        );
+    }
 }
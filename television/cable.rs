@@ -1,9 +1,13 @@
 use crate::{
-    action::Action, channels::prototypes::ChannelPrototype,
-    config::Keybindings, errors::unknown_channel_exit, event::Key,
+    action::Action,
+    channels::prototypes::ChannelPrototype,
+    config::{Keybindings, describe_toml_error, provenance::Source},
+    errors::unknown_channel_exit,
+    event::Key,
 };
 use rustc_hash::FxHashMap;
 use std::{
+    ffi::OsStr,
     ops::Deref,
     path::{Path, PathBuf},
 };
@@ -99,7 +103,7 @@ pub const CHANNEL_FILE_FORMAT: &str = "toml";
 /// ```
 pub const CABLE_DIR_NAME: &str = "cable";
 
-fn get_cable_files<P>(cable_dir: P) -> Vec<PathBuf>
+pub fn get_cable_files<P>(cable_dir: P) -> Vec<PathBuf>
 where
     P: AsRef<Path>,
 {
@@ -120,32 +124,37 @@ where
         .collect::<Vec<_>>()
 }
 
+/// Parses the given channel files, returning the prototypes that could be
+/// parsed along with an error message for each file that couldn't.
 fn load_prototypes(
     toml_prototypes: FxHashMap<PathBuf, String>,
-) -> Vec<ChannelPrototype> {
-    toml_prototypes
-        .into_iter()
-        .filter_map(|(path, content)| {
-            match toml::from_str::<ChannelPrototype>(&content) {
-                Ok(prototype) => {
-                    debug!(
-                        "Loaded cable channel prototype from {}: {}",
-                        path.display(),
-                        prototype.metadata.name
-                    );
-                    Some(prototype)
-                }
-                Err(e) => {
-                    eprintln!(
-                        "Failed to parse cable channel file {}: {}",
-                        path.display(),
-                        e
-                    );
-                    None
-                }
+) -> (Vec<ChannelPrototype>, Vec<String>) {
+    let mut prototypes = Vec::with_capacity(toml_prototypes.len());
+    let mut errors = Vec::new();
+    for (path, content) in toml_prototypes {
+        match toml::from_str::<ChannelPrototype>(&content) {
+            Ok(prototype) => {
+                debug!(
+                    "Loaded cable channel prototype from {}: {}",
+                    path.display(),
+                    prototype.metadata.name
+                );
+                prototypes.push(prototype);
             }
-        })
-        .collect()
+            Err(e) => {
+                error!(
+                    "Failed to parse cable channel file {}: {}",
+                    path.display(),
+                    e
+                );
+                errors.push(format!(
+                    "Failed to parse cable channel {}",
+                    describe_toml_error(&path, &content, &e)
+                ));
+            }
+        }
+    }
+    (prototypes, errors)
 }
 
 /// Load cable channels from the provided directory.
@@ -155,6 +164,9 @@ fn load_prototypes(
 /// channels taking precedence over defaults. For a list of default cable channels,
 /// see `DEFAULT_CABLE_FILES`.
 ///
+/// Files that can't be parsed are skipped and an error message is returned
+/// for each of them so that the caller can report them to the user.
+///
 /// # Example:
 /// ```ignore
 ///   config_folder/
@@ -164,7 +176,7 @@ fn load_prototypes(
 ///    ├── channel_2.toml
 ///    └── ...
 /// ```
-pub fn load_cable<P>(cable_dir: P) -> Cable
+pub fn load_cable<P>(cable_dir: P) -> (Cable, Vec<String>)
 where
     P: AsRef<Path>,
 {
@@ -197,11 +209,58 @@ where
             .collect::<FxHashMap<_, _>>(),
     );
 
-    let prototypes = load_prototypes(cable_map);
+    let (prototypes, errors) = load_prototypes(cable_map);
 
     debug!("Loaded {} cable channels", prototypes.len());
 
-    Cable::from_prototypes(prototypes)
+    (Cable::from_prototypes(prototypes), errors)
+}
+
+/// Finds the file defining the channel called `name` and returns its content
+/// along with where it comes from: a file of the cable directory, or the
+/// builtin channel if none of them defines it.
+pub fn find_channel_file<P>(
+    cable_dir: P,
+    name: &str,
+) -> Option<(Source, String)>
+where
+    P: AsRef<Path>,
+{
+    let defines_channel = |content: &str| {
+        toml::from_str::<toml::Table>(content)
+            .ok()
+            .is_some_and(|table| {
+                table
+                    .get("metadata")
+                    .and_then(|metadata| metadata.get("name"))
+                    .and_then(toml::Value::as_str)
+                    == Some(name)
+            })
+    };
+
+    let cable_files = get_cable_files(cable_dir);
+    for path in &cable_files {
+        if let Ok(content) = std::fs::read_to_string(path)
+            && defines_channel(&content)
+        {
+            return Some((Source::Channel(path.clone()), content));
+        }
+    }
+    // builtin channels are overridden by cable files with the same name
+    DEFAULT_CABLE_FILES
+        .iter()
+        .filter(|(file_name, _)| {
+            !cable_files
+                .iter()
+                .any(|path| path.file_name() == Some(OsStr::new(file_name)))
+        })
+        .find(|(_, content)| defines_channel(content))
+        .map(|(_, content)| {
+            (
+                Source::BuiltinChannel(name.to_string()),
+                (*content).to_string(),
+            )
+        })
 }
 
 #[cfg(unix)]
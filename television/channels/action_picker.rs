@@ -1,6 +1,10 @@
 use crate::{
-    action::CUSTOM_ACTION_PREFIX, channels::entry::into_ranges,
-    channels::prototypes::ActionSpec, event::Key, matcher::Matcher,
+    action::{Action, CUSTOM_ACTION_PREFIX},
+    channels::entry::into_ranges,
+    channels::prototypes::{ActionSpec, BinaryRequirement, ChannelPrototype},
+    event::Key,
+    history::HistoryEntry,
+    matcher::Matcher,
     screen::result_item::ResultItem,
 };
 use anyhow::Result;
@@ -11,11 +15,15 @@ use smallvec::SmallVec;
 #[derive(Debug, Clone)]
 pub struct ActionEntry {
     pub action_name: String,
-    pub action_string: String,
+    /// The action dispatched when this entry is confirmed
+    pub action: Action,
     pub description: Option<String>,
     pub commands: Vec<String>,
     pub keybinding: Option<Key>,
+    pub requirements: Vec<BinaryRequirement>,
     pub match_ranges: Option<SmallVec<[(u32, u32); 8]>>,
+    /// The past query this entry reuses, in the history picker
+    pub history_entry: Option<HistoryEntry>,
 }
 
 impl ActionEntry {
@@ -31,15 +39,92 @@ impl ActionEntry {
             .map(|c| c.template().raw().to_string())
             .collect();
         ActionEntry {
-            action_string: format!("{}{}", CUSTOM_ACTION_PREFIX, action_name),
+            action: Action::ExternalAction(format!(
+                "{}{}",
+                CUSTOM_ACTION_PREFIX, action_name
+            )),
             action_name,
             description: action_spec.description.clone(),
             commands,
             keybinding,
+            requirements: action_spec.requirements.clone(),
             match_ranges: None,
+            history_entry: None,
         }
     }
 
+    /// Create an entry for one of tv's built-in actions.
+    pub fn builtin(action: Action, keybinding: Option<Key>) -> Self {
+        ActionEntry {
+            action_name: action
+                .config_name()
+                .unwrap_or_else(|| action.description())
+                .to_string(),
+            description: Some(action.description().to_string()),
+            commands: Vec::new(),
+            keybinding,
+            requirements: Vec::new(),
+            match_ranges: None,
+            history_entry: None,
+            action,
+        }
+    }
+
+    /// Create an entry that switches to the given channel.
+    pub fn channel(
+        prototype: &ChannelPrototype,
+        keybinding: Option<Key>,
+    ) -> Self {
+        let channel_name = prototype.metadata.name.clone();
+        ActionEntry {
+            action_name: format!("channel:{channel_name}"),
+            description: Some(
+                prototype.metadata.description.clone().unwrap_or_else(|| {
+                    format!("Switch to the {channel_name} channel")
+                }),
+            ),
+            commands: Vec::new(),
+            keybinding,
+            requirements: prototype
+                .metadata
+                .requirements
+                .iter()
+                .cloned()
+                .map(|mut r| {
+                    r.init();
+                    r
+                })
+                .collect(),
+            match_ranges: None,
+            history_entry: None,
+            action: Action::SwitchToChannel(channel_name),
+        }
+    }
+
+    /// Create an entry that sets the input to a past query.
+    pub fn history(entry: &HistoryEntry) -> Self {
+        ActionEntry {
+            action_name: entry.query.clone(),
+            action: Action::SetInput(entry.query.clone()),
+            description: None,
+            commands: Vec::new(),
+            keybinding: None,
+            requirements: Vec::new(),
+            match_ranges: None,
+            history_entry: Some(entry.clone()),
+        }
+    }
+
+    /// Whether this entry runs one of the channel's external actions.
+    pub fn is_external(&self) -> bool {
+        matches!(self.action, Action::ExternalAction(_))
+    }
+
+    /// Whether all of the action's requirements are met.
+    pub fn is_runnable(&self) -> bool {
+        self.requirements.iter().all(BinaryRequirement::is_met)
+    }
+
     pub fn with_match_indices(mut self, indices: &[u32]) -> Self {
         self.match_ranges = Some(into_ranges(indices));
         self
@@ -56,7 +141,11 @@ impl ResultItem for ActionEntry {
     }
 
     fn output(&self) -> Result<String> {
-        Ok(self.action_string.clone())
+        Ok(self
+            .action
+            .config_name()
+            .unwrap_or(&self.action_name)
+            .to_string())
     }
 
     fn match_ranges(&self) -> Option<&[(u32, u32)]> {
@@ -68,6 +157,26 @@ impl ResultItem for ActionEntry {
     }
 }
 
+/// Build the entries for a channel's external actions, sorted alphabetically
+/// for consistent display.
+fn external_action_entries(
+    channel_actions: &FxHashMap<String, ActionSpec>,
+    action_keybindings: &FxHashMap<String, Key>,
+) -> Vec<ActionEntry> {
+    let mut actions: Vec<_> = channel_actions.iter().collect();
+    actions.sort_by(|a, b| a.0.cmp(b.0));
+
+    actions
+        .into_iter()
+        .map(|(action_name, action_spec)| {
+            let action_string =
+                format!("{}{}", CUSTOM_ACTION_PREFIX, action_name);
+            let keybinding = action_keybindings.get(&action_string).copied();
+            ActionEntry::new(action_name.clone(), action_spec, keybinding)
+        })
+        .collect()
+}
+
 pub struct ActionPicker {
     matcher: Matcher<ActionEntry>,
 }
@@ -79,19 +188,41 @@ impl ActionPicker {
         channel_actions: &FxHashMap<String, ActionSpec>,
         action_keybindings: &FxHashMap<String, Key>,
     ) -> Self {
+        Self::from_entries(external_action_entries(
+            channel_actions,
+            action_keybindings,
+        ))
+    }
+
+    /// Create a command palette listing tv's built-in actions, followed by
+    /// the channel's actions and a shortcut to every available channel.
+    pub fn command_palette(
+        builtin_actions: impl IntoIterator<Item = ActionEntry>,
+        channel_actions: &FxHashMap<String, ActionSpec>,
+        action_keybindings: &FxHashMap<String, Key>,
+        channels: impl IntoIterator<Item = ActionEntry>,
+    ) -> Self {
+        Self::from_entries(
+            builtin_actions
+                .into_iter()
+                .chain(external_action_entries(
+                    channel_actions,
+                    action_keybindings,
+                ))
+                .chain(channels),
+        )
+    }
+
+    /// Create a picker listing past queries, in the given order.
+    pub fn history(entries: impl IntoIterator<Item = ActionEntry>) -> Self {
+        Self::from_entries(entries)
+    }
+
+    fn from_entries(entries: impl IntoIterator<Item = ActionEntry>) -> Self {
         let matcher = Matcher::new(SortStrategy::Score, NUM_THREADS);
         let injector = matcher.injector();
 
-        // Sort actions alphabetically for consistent display
-        let mut actions: Vec<_> = channel_actions.iter().collect();
-        actions.sort_by(|a, b| a.0.cmp(b.0));
-
-        for (action_name, action_spec) in actions {
-            let action_string =
-                format!("{}{}", CUSTOM_ACTION_PREFIX, action_name);
-            let keybinding = action_keybindings.get(&action_string).copied();
-            let entry =
-                ActionEntry::new(action_name.clone(), action_spec, keybinding);
+        for entry in entries {
             let () = injector.push(entry, |e, cols| {
                 cols[0] = e.action_name.clone().into();
             });
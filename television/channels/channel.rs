@@ -5,14 +5,19 @@ use crate::{
             AnsiProcessor, DisplayProcessor, EntryProcessor, PlainProcessor,
         },
         prototypes::{CommandSpec, Template},
+        sort::{self, SortCriterion},
+    },
+    frecency::ChannelFrecency,
+    matcher::{
+        Matcher,
+        config::{CaseSensitivity, MatchingMode},
+        injector::Injector,
+        matcher_threads,
     },
-    frecency::FrecencyHandle,
-    matcher::{Matcher, injector::Injector, matcher_threads},
     utils::command::shell_command,
 };
 use nucleo::SortStrategy;
 use rustc_hash::{FxBuildHasher, FxHashSet};
-use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::process::Stdio;
 use std::sync::Arc;
@@ -44,6 +49,11 @@ pub struct Channel<P: EntryProcessor> {
     /// source command. When true, `load()` reads `tokio::io::stdin()` and
     /// `reload()` is a no-op (stdin can only be consumed once).
     is_stdin: bool,
+    no_sort: bool,
+    /// Sort criteria, when they differ from the default ones
+    sort: Option<Vec<SortCriterion>>,
+    /// Frecency data used to rank results when sorting is enabled.
+    frecency: Option<ChannelFrecency>,
 }
 
 impl<P: EntryProcessor> Channel<P> {
@@ -54,34 +64,16 @@ impl<P: EntryProcessor> Channel<P> {
         source_output: Option<Template>,
         supports_preview: bool,
         no_sort: bool,
+        sort: Option<Vec<SortCriterion>>,
         processor: P,
-        frecency: Option<(FrecencyHandle, String)>,
+        frecency: Option<ChannelFrecency>,
         is_stdin: bool,
     ) -> Self {
-        let sort_strategy = if no_sort {
-            SortStrategy::Index
-        } else if let Some((frecency_handle, channel_name)) = frecency {
-            let cache = frecency_handle.create_cache(channel_name);
-            SortStrategy::Custom(Box::new(move |m1, i1, m2, i2| {
-                let scores = cache.snapshot();
-                let key1 = P::frecency_key(&i1);
-                let key2 = P::frecency_key(&i2);
-                let f1 = scores.get(&key1);
-                let f2 = scores.get(&key2);
-
-                match (f1, f2) {
-                    (Some(s1), Some(s2)) => {
-                        s2.cmp(&s1).then_with(|| m2.score.cmp(&m1.score))
-                    }
-                    (Some(_), None) => Ordering::Less,
-                    (None, Some(_)) => Ordering::Greater,
-                    (None, None) => m2.score.cmp(&m1.score),
-                }
-            }))
-        } else {
-            SortStrategy::Score
-        };
-
+        let sort_strategy = sort::sort_strategy::<P>(
+            no_sort,
+            sort.as_deref(),
+            frecency.as_ref(),
+        );
         let matcher = Matcher::new(sort_strategy, matcher_threads());
         let current_source_index = 0;
         Self {
@@ -96,9 +88,48 @@ impl<P: EntryProcessor> Channel<P> {
             current_source_index,
             reloading: Arc::new(AtomicBool::new(false)),
             is_stdin,
+            no_sort,
+            sort,
+            frecency,
         }
     }
 
+    fn sort_strategy(&self) -> SortStrategy<P::Data> {
+        sort::sort_strategy::<P>(
+            self.no_sort,
+            self.sort.as_deref(),
+            self.frecency.as_ref(),
+        )
+    }
+
+    /// Whether the sort strategy caches values extracted from the items.
+    fn caches_item_keys(&self) -> bool {
+        self.sort.as_ref().is_some_and(|criteria| {
+            criteria
+                .iter()
+                .any(|c| matches!(c, SortCriterion::Key { .. }))
+        }) || self.frecency.as_ref().is_some_and(|f| f.key.is_some())
+    }
+
+    /// Set how the query is matched against the entries.
+    ///
+    /// Takes effect on the next call to `find`.
+    pub fn set_matching(
+        &mut self,
+        mode: MatchingMode,
+        case_sensitivity: CaseSensitivity,
+    ) {
+        self.matcher.set_matching(mode, case_sensitivity);
+    }
+
+    /// Enable or disable sorting the results by score (and frecency).
+    ///
+    /// Takes effect on the next call to `find`.
+    pub fn set_no_sort(&mut self, no_sort: bool) {
+        self.no_sort = no_sort;
+        self.matcher.set_sort_strategy(self.sort_strategy());
+    }
+
     pub fn load(&mut self) {
         let injector = self.matcher.injector();
         let processor = self.processor.clone();
@@ -120,14 +151,18 @@ impl<P: EntryProcessor> Channel<P> {
         self.crawl_handle = Some(crawl_handle);
     }
 
-    pub fn reload(&mut self) {
+    /// Restart the source command and load its entries again.
+    ///
+    /// Returns whether a reload was started, which isn't the case for stdin
+    /// channels or while a reload is already in progress.
+    pub fn reload(&mut self) -> bool {
         if self.is_stdin {
             debug!("Stdin channel cannot be reloaded, skipping.");
-            return;
+            return false;
         }
         if self.reloading.load(std::sync::atomic::Ordering::Relaxed) {
             debug!("Reload already in progress, skipping.");
-            return;
+            return false;
         }
         self.reloading
             .store(true, std::sync::atomic::Ordering::Relaxed);
@@ -138,6 +173,11 @@ impl<P: EntryProcessor> Channel<P> {
             handle.abort();
         }
         self.matcher.restart();
+        // sort and frecency keys are cached by item index, which the restart
+        // invalidates
+        if self.caches_item_keys() {
+            self.matcher.set_sort_strategy(self.sort_strategy());
+        }
         self.load();
         // Spawn a thread that turns off reloading after a short delay
         // to avoid UI flickering (this boolean is used by `Television::should_render`)
@@ -146,6 +186,7 @@ impl<P: EntryProcessor> Channel<P> {
             tokio::time::sleep(RELOAD_RENDERING_DELAY).await;
             reloading.store(false, std::sync::atomic::Ordering::Relaxed);
         });
+        true
     }
 
     pub fn current_command(&self) -> &str {
@@ -208,6 +249,18 @@ impl<P: EntryProcessor> Channel<P> {
         }
     }
 
+    pub fn select(&mut self, entry: &Entry) {
+        self.selected_entries.insert(entry.clone());
+    }
+
+    pub fn deselect(&mut self, entry: &Entry) {
+        self.selected_entries.remove(entry);
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selected_entries.clear();
+    }
+
     pub fn result_count(&self) -> u32 {
         self.matcher.matched_item_count
     }
@@ -535,7 +588,8 @@ impl ChannelKind {
         source_output: Option<Template>,
         supports_preview: bool,
         no_sort: bool,
-        frecency: Option<(FrecencyHandle, String)>,
+        sort: Option<Vec<SortCriterion>>,
+        frecency: Option<ChannelFrecency>,
         is_stdin: bool,
     ) -> Self {
         match (source_ansi, source_display) {
@@ -545,6 +599,7 @@ impl ChannelKind {
                 source_output,
                 supports_preview,
                 no_sort,
+                sort,
                 PlainProcessor,
                 frecency,
                 is_stdin,
@@ -555,6 +610,7 @@ impl ChannelKind {
                 source_output,
                 supports_preview,
                 no_sort,
+                sort,
                 AnsiProcessor,
                 frecency,
                 is_stdin,
@@ -565,6 +621,7 @@ impl ChannelKind {
                 source_output,
                 supports_preview,
                 no_sort,
+                sort,
                 DisplayProcessor { template },
                 frecency,
                 is_stdin,
@@ -575,13 +632,18 @@ impl ChannelKind {
     // Generate all mutable delegation methods
     delegate_to_channel!(mut
         load() -> (),
-        reload() -> (),
+        reload() -> bool,
         find(pattern: &str) -> (),
         tick() -> (),
         results(num_entries: u32, offset: u32) -> Vec<Entry>,
         get_result(index: u32) -> Option<Entry>,
         toggle_selection(entry: &Entry) -> (),
+        select(entry: &Entry) -> (),
+        deselect(entry: &Entry) -> (),
+        clear_selection() -> (),
         cycle_sources() -> (),
+        set_matching(mode: MatchingMode, case_sensitivity: CaseSensitivity) -> (),
+        set_no_sort(no_sort: bool) -> (),
     );
 
     // Generate all immutable delegation methods
@@ -604,7 +666,11 @@ impl ChannelKind {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::channels::prototypes::SourceSpec;
+    use crate::{
+        channels::prototypes::SourceSpec,
+        config::{FrecencyConfig, frecency::FrecencyBlend},
+        frecency::Frecency,
+    };
     use nucleo::SortStrategy;
 
     const MATCHER_TEST_THREADS: usize = 1;
@@ -767,4 +833,331 @@ mod tests {
         assert_eq!(results[1].matched_string, "test2");
         assert_eq!(results[2].matched_string, "test3");
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn test_regex_and_case_matching() {
+        let source_spec: SourceSpec = toml::from_str(
+            r#"
+            command = "printf 'ERROR: disk full\nerror: timeout\nwarning: retry\n'"
+            match_mode = "regex"
+            case_sensitivity = "sensitive"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(source_spec.match_mode, MatchingMode::Regex);
+
+        let mut matcher =
+            Matcher::<()>::new(SortStrategy::Score, MATCHER_TEST_THREADS);
+        let injector = matcher.injector();
+        load_candidates(
+            source_spec.command,
+            source_spec.entry_delimiter,
+            0,
+            PlainProcessor,
+            injector,
+        )
+        .await;
+
+        matcher
+            .set_matching(MatchingMode::Regex, source_spec.case_sensitivity);
+        matcher.find("^error|retry$");
+        matcher.tick();
+        let results = matcher.results(10, 0);
+        assert_eq!(matcher.matched_item_count, 2);
+        assert_eq!(results[0].matched_string, "error: timeout");
+        assert_eq!(results[0].match_indices, vec![0, 1, 2, 3, 4]);
+        assert_eq!(results[1].matched_string, "warning: retry");
+
+        matcher
+            .set_matching(MatchingMode::Regex, CaseSensitivity::Insensitive);
+        matcher.find("^error|retry$");
+        matcher.tick();
+        assert_eq!(matcher.results(10, 0).len(), 3);
+
+        // an invalid regex is matched literally
+        matcher.find("full (");
+        matcher.tick();
+        assert_eq!(matcher.results(10, 0).len(), 0);
+        matcher.find("(");
+        matcher.tick();
+        assert_eq!(matcher.results(10, 0).len(), 0);
+
+        // back to fuzzy matching, respecting case
+        matcher.set_matching(MatchingMode::Fuzzy, CaseSensitivity::Sensitive);
+        matcher.find("error");
+        matcher.tick();
+        let results = matcher.results(10, 0);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].matched_string, "error: timeout");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_alternation_matching() {
+        let source_spec: SourceSpec = toml::from_str(
+            r#"command = "printf 'src/main.rs\nCargo.toml\nREADME.md\nsrc/lib.rs\n'""#,
+        )
+        .unwrap();
+        let mut matcher =
+            Matcher::<()>::new(SortStrategy::Index, MATCHER_TEST_THREADS);
+        let injector = matcher.injector();
+        load_candidates(
+            source_spec.command,
+            source_spec.entry_delimiter,
+            0,
+            PlainProcessor,
+            injector,
+        )
+        .await;
+
+        matcher.find(".rs$ | .toml$");
+        matcher.tick();
+        let results = matcher.results(10, 0);
+        let matched: Vec<_> =
+            results.iter().map(|r| r.matched_string.as_str()).collect();
+        assert_eq!(matched, vec!["src/main.rs", "Cargo.toml", "src/lib.rs"]);
+        // highlighted according to the alternative that matched
+        assert_eq!(results[1].match_indices, vec![5, 6, 7, 8, 9]);
+        assert_eq!(results[2].match_indices, vec![7, 8, 9]);
+
+        matcher.find("'src ('main | 'lib)");
+        matcher.tick();
+        assert_eq!(matcher.matched_item_count, 2);
+        assert_eq!(
+            matcher.results(10, 0)[1].match_indices,
+            vec![0, 1, 2, 4, 5, 6]
+        );
+
+        // a single alternative is handed over to nucleo as is
+        matcher.find("(readme)");
+        matcher.tick();
+        assert_eq!(matcher.results(10, 0)[0].matched_string, "README.md");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_path_matching() {
+        let source_spec: SourceSpec = toml::from_str(
+            r#"
+            command = "printf 'src/domain/main_logic/x.rs\nsrc/main.rs\nsrc/bin/main.rs\n'"
+            match_mode = "path"
+            "#,
+        )
+        .unwrap();
+        let mut matcher =
+            Matcher::<()>::new(SortStrategy::Score, MATCHER_TEST_THREADS);
+        let injector = matcher.injector();
+        load_candidates(
+            source_spec.command,
+            source_spec.entry_delimiter,
+            0,
+            PlainProcessor,
+            injector,
+        )
+        .await;
+
+        matcher.set_matching(source_spec.match_mode, CaseSensitivity::Smart);
+        matcher.find("main");
+        matcher.tick();
+        let results = matcher.results(10, 0);
+        let matched: Vec<_> =
+            results.iter().map(|r| r.matched_string.as_str()).collect();
+        // file name matches first, then shorter paths
+        assert_eq!(
+            matched,
+            vec![
+                "src/main.rs",
+                "src/bin/main.rs",
+                "src/domain/main_logic/x.rs"
+            ]
+        );
+        assert_eq!(results[0].match_indices, vec![4, 5, 6, 7]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_path_matching_prefers_file_names() {
+        let source_spec: SourceSpec = toml::from_str(
+            r#"
+            command = "printf 'src/main_x/a.rs\ncrates/app/src/main.rs\n'"
+            match_mode = "path"
+            "#,
+        )
+        .unwrap();
+        let mut matcher =
+            Matcher::<()>::new(SortStrategy::Score, MATCHER_TEST_THREADS);
+        let injector = matcher.injector();
+        load_candidates(
+            source_spec.command,
+            source_spec.entry_delimiter,
+            0,
+            PlainProcessor,
+            injector,
+        )
+        .await;
+
+        matcher.set_matching(source_spec.match_mode, CaseSensitivity::Smart);
+        matcher.find("main");
+        matcher.tick();
+        let matched: Vec<_> = matcher
+            .results(10, 0)
+            .into_iter()
+            .map(|r| r.matched_string)
+            .collect();
+        // the file name match wins even though its path is longer
+        assert_eq!(matched, vec!["crates/app/src/main.rs", "src/main_x/a.rs"]);
+        assert_eq!(
+            matcher.get_result(1).unwrap().matched_string,
+            "src/main_x/a.rs"
+        );
+
+        // fuzzy mode doesn't favor file names
+        matcher.set_matching(MatchingMode::Fuzzy, CaseSensitivity::Smart);
+        matcher.find("main");
+        matcher.tick();
+        assert_eq!(matcher.results(1, 0)[0].matched_string, "src/main_x/a.rs");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_sort_criteria() {
+        let source_spec: SourceSpec = toml::from_str(
+            r#"
+            command = "printf 'b 3\\nlonger 3\\na 10\\nc x\\n'"
+            sort = [{ key = "{split: :1}", reverse = true }, "length"]
+            "#,
+        )
+        .unwrap();
+        let sort_strategy = sort::sort_strategy::<PlainProcessor>(
+            false,
+            source_spec.sort.as_deref(),
+            None,
+        );
+        let mut matcher =
+            Matcher::<()>::new(sort_strategy, MATCHER_TEST_THREADS);
+        let injector = matcher.injector();
+        load_candidates(
+            source_spec.command,
+            source_spec.entry_delimiter,
+            0,
+            PlainProcessor,
+            injector,
+        )
+        .await;
+
+        matcher.find("");
+        matcher.tick();
+        let results = matcher.results(10, 0);
+        let matched: Vec<_> =
+            results.iter().map(|r| r.matched_string.as_str()).collect();
+        // numeric keys first, descending, then by length
+        assert_eq!(matched, vec!["a 10", "b 3", "longer 3", "c x"]);
+    }
+
+    async fn sorted_by_frecency(
+        command: &str,
+        pattern: &str,
+        frecency: &ChannelFrecency,
+    ) -> Vec<String> {
+        let sort_strategy =
+            sort::sort_strategy::<PlainProcessor>(false, None, Some(frecency));
+        let mut matcher =
+            Matcher::<()>::new(sort_strategy, MATCHER_TEST_THREADS);
+        load_candidates(
+            CommandSpec::from(Template::parse(command).unwrap()),
+            None,
+            0,
+            PlainProcessor,
+            matcher.injector(),
+        )
+        .await;
+        matcher.find(pattern);
+        matcher.tick();
+        matcher
+            .results(10, 0)
+            .into_iter()
+            .map(|r| r.matched_string)
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_frecency_namespace_and_blend() {
+        let dir = tempfile::tempdir().unwrap();
+        let frecency = Arc::new(Frecency::new(100, dir.path()));
+        // picking a line in a `text`-like channel boosts its file in `files`
+        ChannelFrecency::new(frecency.clone(), "files".to_string())
+            .with_key(Some(Template::parse("{split:\\::0}").unwrap()))
+            .record_access("src/b.rs:3:fn main() {}");
+        let files =
+            ChannelFrecency::new(frecency.clone(), "files".to_string());
+        assert_eq!(
+            sorted_by_frecency("printf 'src/a.rs\\nsrc/b.rs\\n'", "", &files)
+                .await,
+            vec!["src/b.rs", "src/a.rs"]
+        );
+
+        // a much better match only wins when frecency is blended with score
+        frecency.record_access("words", "a_b_c");
+        let words =
+            ChannelFrecency::new(frecency.clone(), "words".to_string());
+        let command = "printf 'abc\\na_b_c\\n'";
+        assert_eq!(
+            sorted_by_frecency(command, "abc", &words).await,
+            vec!["a_b_c", "abc"]
+        );
+        let blended = Arc::new(Frecency::new(100, dir.path()).with_config(
+            FrecencyConfig {
+                blend: FrecencyBlend::Weighted,
+                ..FrecencyConfig::default()
+            },
+        ));
+        blended.record_access("words", "a_b_c");
+        let words = ChannelFrecency::new(blended, "words".to_string());
+        assert_eq!(
+            sorted_by_frecency(command, "abc", &words).await,
+            vec!["abc", "a_b_c"]
+        );
+    }
+
+    /// Wait for the channel to load its entries and return them.
+    async fn loaded_entries(
+        channel: &mut Channel<PlainProcessor>,
+    ) -> Vec<String> {
+        channel.find("");
+        for _ in 0..200 {
+            channel.tick();
+            if !channel.running() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        channel.results(10, 0).into_iter().map(|e| e.raw).collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_reload_with_frecency_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        std::fs::write(&source, "a/1\nb/1\n").unwrap();
+        let frecency = Arc::new(Frecency::new(100, dir.path()));
+        let channel_frecency =
+            ChannelFrecency::new(frecency, "test".to_string())
+                .with_key(Some(Template::parse("{split:/:0}").unwrap()));
+        channel_frecency.record_access("b/2");
+        let command = format!("cat '{}'", source.display());
+        let mut channel = Channel::new(
+            CommandSpec::from(Template::parse(&command).unwrap()),
+            None,
+            None,
+            false,
+            false,
+            None,
+            PlainProcessor,
+            Some(channel_frecency),
+            false,
+        );
+        channel.load();
+        assert_eq!(loaded_entries(&mut channel).await, ["b/1", "a/1"]);
+
+        // the keys cached for the previous items must not be reused
+        std::fs::write(&source, "b/1\na/1\n").unwrap();
+        assert!(channel.reload());
+        assert_eq!(loaded_entries(&mut channel).await, ["b/1", "a/1"]);
+    }
 }
@@ -0,0 +1,762 @@
+use crate::{
+    action::{Action, CUSTOM_ACTION_PREFIX},
+    channels::{
+        prototypes::{
+            ActionSpec, ChannelPrototype, CommandSpec, SourceCommand, Template,
+        },
+        sort::SortCriterion,
+    },
+    config::{
+        Keybindings,
+        actions::GlobalActionSpec,
+        keybindings::{KeySequence, describe_prefix_conflict},
+        merge_keybindings,
+    },
+    event::Key,
+};
+use lazy_regex::regex;
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::Range,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use string_pipeline::MultiTemplate;
+use toml::{
+    Spanned,
+    de::{DeTable, DeValue},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Warning,
+    Error,
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Level::Warning => write!(f, "warning"),
+            Level::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem found in a channel file.
+///
+/// `line` and `column` are 1-based and point at the offending value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    pub level: Level,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}: {}",
+            self.path.display(),
+            self.line,
+            self.column,
+            self.level,
+            self.message
+        )
+    }
+}
+
+/// A step in the path to a value of a channel file.
+#[derive(Debug, Clone, Copy)]
+enum Segment<'a> {
+    Field(&'a str),
+    Index(usize),
+}
+
+use Segment::{Field, Index};
+
+/// Checks the given channel files for the problems that would otherwise
+/// only show up once the channel is used: files that don't parse, templates
+/// that can't be parsed, keybindings referencing unknown actions,
+/// non-numeric preview offsets, as well as channel names and shortcuts
+/// defined more than once.
+///
+/// `global_actions` are the actions of the configuration file, which
+/// keybindings may reference as well.
+pub fn lint_channel_files(
+    files: &[(PathBuf, String)],
+    global_actions: &FxHashMap<String, GlobalActionSpec>,
+    global_keybindings: &Keybindings,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut names: FxHashMap<String, &Path> = FxHashMap::default();
+    let mut shortcuts: FxHashMap<Key, (String, &Path)> = FxHashMap::default();
+
+    for (path, content) in files {
+        let Some(mut file) = ChannelFile::parse(path, content) else {
+            diagnostics.push(parse_error(path, content));
+            continue;
+        };
+        file.check(global_actions, global_keybindings);
+
+        let name = file.prototype.metadata.name.clone();
+        if let Some(other) = names.get(&name) {
+            file.report(
+                Level::Error,
+                &[Field("metadata"), Field("name")],
+                format!(
+                    "channel `{name}` is already defined in {}",
+                    other.display()
+                ),
+            );
+        } else {
+            names.insert(name.clone(), path);
+        }
+
+        if let Some(shortcut) = file
+            .prototype
+            .keybindings
+            .as_ref()
+            .and_then(|keybindings| keybindings.channel_shortcut())
+        {
+            match shortcuts.get(shortcut) {
+                Some((other, other_path)) if *other != name => {
+                    file.report(
+                        Level::Error,
+                        &[Field("keybindings"), Field("shortcut")],
+                        format!(
+                            "shortcut `{shortcut}` is already used by channel \
+                             `{other}` ({})",
+                            other_path.display()
+                        ),
+                    );
+                }
+                Some(_) => {}
+                None => {
+                    shortcuts.insert(*shortcut, (name, path));
+                }
+            }
+        }
+        file.diagnostics.sort_by_key(|d| (d.line, d.column));
+        diagnostics.append(&mut file.diagnostics);
+    }
+    diagnostics
+}
+
+/// Describes why `content` isn't a valid channel file.
+fn parse_error(path: &Path, content: &str) -> Diagnostic {
+    let error = match DeTable::parse(content) {
+        Err(e) => e,
+        Ok(_) => match toml::from_str::<ChannelPrototype>(content) {
+            Err(e) => e,
+            Ok(_) => unreachable!("only called on invalid channel files"),
+        },
+    };
+    let (line, column) =
+        position(content, error.span().map_or(0, |span| span.start));
+    Diagnostic {
+        path: path.to_path_buf(),
+        line,
+        column,
+        level: Level::Error,
+        message: error.message().trim().to_string(),
+    }
+}
+
+/// The outermost `{...}` groups of `text`.
+fn brace_groups(text: &str) -> Vec<&str> {
+    let mut groups = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => {
+                if depth == 0 {
+                    start = i;
+                }
+                depth += 1;
+            }
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    groups.push(&text[start..=i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    groups
+}
+
+/// Converts a byte offset into a 1-based line and column.
+fn position(content: &str, offset: usize) -> (usize, usize) {
+    let before = content.get(..offset).unwrap_or(content);
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count());
+    (line, column + 1)
+}
+
+struct ChannelFile<'a> {
+    path: &'a Path,
+    content: &'a str,
+    document: DeTable<'a>,
+    prototype: ChannelPrototype,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> ChannelFile<'a> {
+    fn parse(path: &'a Path, content: &'a str) -> Option<Self> {
+        let document = DeTable::parse(content).ok()?.into_inner();
+        let prototype = toml::from_str::<ChannelPrototype>(content).ok()?;
+        Some(Self {
+            path,
+            content,
+            document,
+            prototype,
+            diagnostics: Vec::new(),
+        })
+    }
+
+    fn check(
+        &mut self,
+        global_actions: &FxHashMap<String, GlobalActionSpec>,
+        global_keybindings: &Keybindings,
+    ) {
+        let prototype = self.prototype.clone();
+
+        let source = &prototype.source;
+        self.check_command(&source.command, "source");
+        for (key, template) in [
+            ("display", &source.display),
+            ("output", &source.output),
+            ("frecency_key", &source.frecency_key),
+        ] {
+            if let Some(template) = template {
+                self.check_template(template, &[Field("source"), Field(key)]);
+            }
+        }
+        for (i, criterion) in source.sort.iter().flatten().enumerate() {
+            if let SortCriterion::Key { key, .. } = criterion {
+                self.check_template(
+                    key,
+                    &[Field("source"), Field("sort"), Index(i), Field("key")],
+                );
+            }
+        }
+
+        if let Some(preview) = &prototype.preview {
+            self.check_command(&preview.command, "preview");
+            if let Some(offset) = &preview.offset {
+                self.check_offset(offset);
+            }
+        }
+
+        for (name, action) in &prototype.actions {
+            self.check_quoted_inputs(action, name);
+            // input placeholders are no template syntax
+            let values: Vec<_> = action
+                .inputs
+                .iter()
+                .map(|input| (input.name.clone(), String::new()))
+                .collect();
+            let action = match action.with_inputs(&values) {
+                Ok(action) => action,
+                Err(e) => {
+                    self.report(
+                        Level::Error,
+                        &[Field("actions"), Field(name)],
+                        e.to_string(),
+                    );
+                    continue;
+                }
+            };
+            let path = [Field("actions"), Field(name), Field("command")];
+            for (i, command) in action.command.inner.iter().enumerate() {
+                self.check_source_command(command, &path, i);
+            }
+            if let Some(confirm) = &action.confirm {
+                self.check_template(
+                    confirm,
+                    &[Field("actions"), Field(name), Field("confirm")],
+                );
+            }
+        }
+
+        self.check_keybindings(global_actions, global_keybindings);
+    }
+
+    /// Input values are quoted by tv, so quoting their placeholders again
+    /// would leave the values unquoted.
+    fn check_quoted_inputs(&mut self, action: &ActionSpec, name: &str) {
+        let path = [Field("actions"), Field(name), Field("command")];
+        for command in &action.command.inner {
+            let raw = command.template().raw();
+            if let Some(quoted) =
+                regex!(r"'\{input\.[^}]*\}|\{input\.[^}]*\}'").find(raw)
+            {
+                let placeholder = quoted.as_str().trim_matches('\'');
+                self.report(
+                    Level::Warning,
+                    &path,
+                    format!(
+                        "`{placeholder}` is quoted by tv, the quotes around \
+                         it would leave its value unquoted"
+                    ),
+                );
+            }
+        }
+    }
+
+    fn check_command(&mut self, command: &CommandSpec, section: &str) {
+        let path = [Field(section), Field("command")];
+        for (i, source_command) in command.inner.iter().enumerate() {
+            self.check_source_command(source_command, &path, i);
+        }
+    }
+
+    /// `command` may be a single value or a list, so the index is only
+    /// followed when there is a list.
+    fn check_source_command(
+        &mut self,
+        command: &SourceCommand,
+        path: &[Segment],
+        index: usize,
+    ) {
+        let mut path = path.to_vec();
+        if self.lookup(&path).is_some_and(|v| v.as_array().is_some()) {
+            path.push(Index(index));
+        }
+        if let SourceCommand::Named { .. } = command {
+            path.push(Field("run"));
+        }
+        self.check_placeholders(command.template(), &path);
+    }
+
+    /// Commands often contain braces meant for the shell or the programs
+    /// they run (e.g. `awk '{print $1}'`), in which case they can't be parsed
+    /// as a template and only `{}` is replaced with the entry. This is fine
+    /// unless the command also contains placeholders that would have been
+    /// formatted otherwise.
+    fn check_placeholders(&mut self, template: &Template, path: &[Segment]) {
+        let Template::Raw(raw) = template else {
+            return;
+        };
+        for placeholder in brace_groups(raw) {
+            if placeholder != "{}" && MultiTemplate::parse(placeholder).is_ok()
+            {
+                self.report(
+                    Level::Warning,
+                    path,
+                    format!(
+                        "placeholder `{placeholder}` is left as is since the \
+                         command can't be parsed as a template as a whole"
+                    ),
+                );
+            }
+        }
+    }
+
+    fn check_template(&mut self, template: &Template, path: &[Segment]) {
+        if let Template::Raw(raw) = template
+            && let Err(e) = MultiTemplate::parse(raw)
+        {
+            let reason = e
+                .lines()
+                .rev()
+                .map(|line| line.trim().trim_start_matches('=').trim())
+                .find(|line| !line.is_empty())
+                .unwrap_or_default();
+            self.report(
+                Level::Warning,
+                path,
+                format!(
+                    "invalid template `{raw}` ({reason}), it is used as \
+                     plain text with `{{}}` replaced by the entry"
+                ),
+            );
+        }
+    }
+
+    fn check_offset(&mut self, offset: &Template) {
+        let raw = offset.raw();
+        if !raw.contains('{') && raw.parse::<u16>().is_err() {
+            self.report(
+                Level::Error,
+                &[Field("preview"), Field("offset")],
+                format!(
+                    "preview offset `{raw}` is neither a line number nor a \
+                     template"
+                ),
+            );
+        }
+    }
+
+    fn check_keybindings(
+        &mut self,
+        global_actions: &FxHashMap<String, GlobalActionSpec>,
+        global_keybindings: &Keybindings,
+    ) {
+        let prototype = &self.prototype;
+        let is_defined = |name: &str| {
+            let name = name.trim_start_matches(CUSTOM_ACTION_PREFIX);
+            prototype.actions.contains_key(name)
+                || global_actions.get(name).is_some_and(|action| {
+                    action.applies_to(&prototype.metadata.name)
+                })
+        };
+
+        let Some(bindings) = self
+            .document
+            .get("keybindings")
+            .and_then(|bindings| bindings.get_ref().as_table())
+        else {
+            return;
+        };
+        // the conflicts the channel's bindings are involved in, reported at
+        // the channel's binding
+        let mut conflicts = Vec::new();
+        if let Some(keybindings) = &prototype.keybindings {
+            let merged = merge_keybindings(
+                global_keybindings.clone(),
+                &keybindings.bindings,
+            );
+            for (prefix, sequence) in merged.prefix_conflicts() {
+                let span = bindings.iter().find_map(|(key, _)| {
+                    KeySequence::from_str(key.get_ref())
+                        .is_ok_and(|keys| keys == prefix || keys == sequence)
+                        .then(|| key.span())
+                });
+                if let Some(span) = span {
+                    conflicts.push((span, prefix, sequence));
+                }
+            }
+        }
+        let mut unknown = Vec::new();
+        for (key, value) in bindings {
+            if key.get_ref().as_ref() == "shortcut" {
+                continue;
+            }
+            let values = match value.get_ref() {
+                DeValue::Array(array) => array.into_iter().collect(),
+                _ => vec![value],
+            };
+            for value in values {
+                let Some(raw) = value.get_ref().as_str() else {
+                    continue;
+                };
+                if let Ok(Action::ExternalAction(name)) =
+                    Action::deserialize(toml::Value::String(raw.to_string()))
+                    && !is_defined(&name)
+                {
+                    unknown.push((name, value.span()));
+                }
+            }
+        }
+        for (span, prefix, sequence) in conflicts {
+            self.report_at(
+                Level::Warning,
+                span,
+                describe_prefix_conflict(&prefix, &sequence),
+            );
+        }
+        for (name, span) in unknown {
+            self.report_at(
+                Level::Error,
+                span,
+                format!(
+                    "unknown action `{name}`: neither a builtin action nor \
+                     defined in `[actions]`"
+                ),
+            );
+        }
+    }
+
+    /// The deepest value found along `path`, and whether it is the value
+    /// at the end of the path.
+    fn resolve(
+        &self,
+        path: &[Segment],
+    ) -> (Option<&Spanned<DeValue<'a>>>, bool) {
+        let mut deepest: Option<&Spanned<DeValue>> = None;
+        for segment in path {
+            let next = match (deepest, segment) {
+                (None, Field(key)) => self.document.get(*key),
+                (None, Index(_)) => None,
+                (Some(value), Field(key)) => value.get_ref().get(*key),
+                (Some(value), Index(i)) => value.get_ref().get(*i),
+            };
+            match next {
+                Some(value) => deepest = Some(value),
+                None => return (deepest, false),
+            }
+        }
+        (deepest, true)
+    }
+
+    fn lookup(&self, path: &[Segment]) -> Option<&DeValue<'a>> {
+        match self.resolve(path) {
+            (Some(value), true) => Some(value.get_ref()),
+            _ => None,
+        }
+    }
+
+    /// Reports a problem at the deepest value of `path` that exists.
+    fn report(&mut self, level: Level, path: &[Segment], message: String) {
+        let span = self.resolve(path).0.map_or(0..0, Spanned::span);
+        self.report_at(level, span, message);
+    }
+
+    fn report_at(
+        &mut self,
+        level: Level,
+        span: Range<usize>,
+        message: String,
+    ) {
+        let (line, column) = position(self.content, span.start);
+        self.diagnostics.push(Diagnostic {
+            path: self.path.to_path_buf(),
+            line,
+            column,
+            level,
+            message,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cable::get_cable_files;
+
+    fn lint(files: &[(&str, &str)]) -> Vec<String> {
+        lint_with_actions(files, &FxHashMap::default())
+    }
+
+    fn lint_with_actions(
+        files: &[(&str, &str)],
+        global_actions: &FxHashMap<String, GlobalActionSpec>,
+    ) -> Vec<String> {
+        let files: Vec<_> = files
+            .iter()
+            .map(|(path, content)| (PathBuf::from(path), content.to_string()))
+            .collect();
+        lint_channel_files(&files, global_actions, &Keybindings::default())
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            lint(&[("a.toml", "[metadata]\nname = \"a\"\n[source\n")]),
+            ["a.toml:3:8: error: unclosed table, expected `]`"]
+        );
+        assert_eq!(
+            lint(&[(
+                "a.toml",
+                "[metadata]\nname = \"a\"\n[source]\ncommand = \"ls\"\nansi = 1\n"
+            )]),
+            [
+                "a.toml:5:8: error: invalid type: integer `1`, expected a boolean"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_templates_and_offset() {
+        let channel = r#"
+[metadata]
+name = "a"
+
+[source]
+command = ["ls", { name = "git", run = "git ls-files | awk '{print $1}'" }]
+display = "{split:/:-1"
+
+[preview]
+command = "jq '{name}' '{split: :0}'"
+offset = "line"
+"#;
+        assert_eq!(
+            lint(&[("a.toml", channel)]),
+            [
+                "a.toml:7:11: warning: invalid template `{split:/:-1` \
+                 (Unclosed template brace), it is used as plain text with \
+                 `{}` replaced by the entry",
+                "a.toml:10:11: warning: placeholder `{split: :0}` is left as \
+                 is since the command can't be parsed as a template as a \
+                 whole",
+                "a.toml:11:10: error: preview offset `line` is neither a \
+                 line number nor a template",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_action_inputs() {
+        let channel = r#"
+[metadata]
+name = "a"
+
+[source]
+command = "git log --oneline"
+
+[actions.branch]
+command = "git branch {input.branch} {split: :0}"
+inputs = [{ name = "branch" }]
+
+[actions.tag]
+command = "git tag {input.tag} {split: :0}"
+
+[actions.rename]
+command = "git branch -m '{input.name}'"
+inputs = [{ name = "name" }]
+"#;
+        assert_eq!(
+            lint(&[("a.toml", channel)]),
+            [
+                "a.toml:12:1: error: '{input.tag}' doesn't match any of the \
+                 action's inputs",
+                "a.toml:16:11: warning: `{input.name}` is quoted by tv, the \
+                 quotes around it would leave its value unquoted",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prefix_conflicts() {
+        let channel = r#"
+[metadata]
+name = "a"
+
+[source]
+command = "ls"
+
+[keybindings]
+"g g" = "select_prev_page"
+"ctrl-x ctrl-e" = "reload_source"
+"ctrl-y" = "quit"
+"#;
+        let global_keybindings: Keybindings = toml::from_str(
+            r#"
+            "g" = "select_next_page"
+            "ctrl-y ctrl-y" = "quit"
+            "#,
+        )
+        .unwrap();
+        let diagnostics = lint_channel_files(
+            &[(PathBuf::from("a.toml"), channel.to_string())],
+            &FxHashMap::default(),
+            &global_keybindings,
+        );
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "a.toml:9:1: warning: `g` is bound and also starts `g g`: it \
+                 will only trigger after the key sequence timeout",
+                "a.toml:11:1: warning: `ctrl-y` is bound and also starts \
+                 `ctrl-y ctrl-y`: it will only trigger after the key \
+                 sequence timeout",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_actions() {
+        let channel = r#"
+[metadata]
+name = "a"
+
+[source]
+command = "ls"
+
+[keybindings]
+ctrl-e = "actions:edit"
+ctrl-d = ["select_next_entry", "actions:delete"]
+ctrl-x = "selct_next_entry"
+ctrl-o = "actions:open"
+
+[actions.edit]
+command = "vim {}"
+"#;
+        let open: GlobalActionSpec =
+            toml::from_str("command = \"xdg-open {}\"").unwrap();
+        let mut global_actions = FxHashMap::default();
+        global_actions.insert("open".to_string(), open.clone());
+        assert_eq!(
+            lint_with_actions(&[("a.toml", channel)], &global_actions),
+            [
+                "a.toml:10:32: error: unknown action `actions:delete`: \
+                 neither a builtin action nor defined in `[actions]`",
+                "a.toml:11:10: error: unknown action `selct_next_entry`: \
+                 neither a builtin action nor defined in `[actions]`",
+            ]
+        );
+
+        // global actions only count in the channels they apply to
+        global_actions.insert(
+            "open".to_string(),
+            GlobalActionSpec {
+                channels: vec!["b".to_string()],
+                ..open
+            },
+        );
+        assert_eq!(
+            lint_with_actions(&[("a.toml", channel)], &global_actions).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_duplicate_names_and_shortcuts() {
+        let channel = |name: &str, shortcut: &str| {
+            format!(
+                "[metadata]\nname = \"{name}\"\n[source]\ncommand = \"ls\"\n\
+                 [keybindings]\nshortcut = \"{shortcut}\"\n"
+            )
+        };
+        assert_eq!(
+            lint(&[
+                ("a.toml", &channel("a", "f1")),
+                ("b.toml", &channel("b", "f1")),
+                ("c.toml", &channel("a", "f2")),
+            ]),
+            [
+                "b.toml:6:12: error: shortcut `f1` is already used by \
+                 channel `a` (a.toml)",
+                "c.toml:2:8: error: channel `a` is already defined in a.toml",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bundled_channels_have_no_errors() {
+        let cable_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("cable")
+            .join("unix");
+        let files: Vec<_> = get_cable_files(&cable_dir)
+            .into_iter()
+            .map(|path| {
+                let content = std::fs::read_to_string(&path).unwrap();
+                (path, content)
+            })
+            .collect();
+        assert!(!files.is_empty());
+        let errors: Vec<_> = lint_channel_files(
+            &files,
+            &FxHashMap::default(),
+            &Keybindings::default(),
+        )
+        .into_iter()
+        .filter(|d| d.level == Level::Error)
+        .collect();
+        assert!(errors.is_empty(), "{errors:#?}");
+    }
+}
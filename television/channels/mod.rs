@@ -2,5 +2,7 @@ pub mod action_picker;
 pub mod channel;
 pub mod entry;
 pub mod entry_processor;
+pub mod lint;
 pub mod prototypes;
 pub mod remote_control;
+pub mod sort;
@@ -1,3 +1,4 @@
+use crate::channels::sort::SortCriterion;
 use crate::cli::parse_source_entry_delimiter;
 use crate::config::ui::{InputBarConfig, ThemeOverrides};
 use crate::utils::shell::Shell;
@@ -5,12 +6,16 @@ use crate::utils::strings::SPACE;
 use crate::{
     config::{Keybindings, ui},
     event::Key,
+    matcher::config::{CaseSensitivity, MatchingMode},
     screen::layout::Orientation,
 };
 use anyhow::Result;
+use lazy_regex::regex;
 use rustc_hash::FxHashMap;
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
 use serde::{Deserialize, Serialize};
 use serde_with::{OneOrMany, serde_as};
+use std::borrow::Cow;
 use std::fmt::{self, Display, Formatter};
 use std::hash::{Hash, Hasher};
 use string_pipeline::MultiTemplate;
@@ -98,12 +103,27 @@ impl<'de> Deserialize<'de> for Template {
     }
 }
 
+impl JsonSchema for Template {
+    fn schema_name() -> Cow<'static, str> {
+        "Template".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "description": "A template formatted with the entry, e.g. `cat {}` or `{split: :0}`",
+        })
+    }
+}
+
 /// A single source command, optionally with a display name.
 ///
 /// Accepts two TOML forms in arrays/single values:
 /// - Bare string: `"fd -t f"` — no display name.
 /// - Inline table: `{ name = "Default", run = "fd -t f" }` — named.
-#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, PartialEq)]
+#[derive(
+    Debug, Clone, serde::Deserialize, serde::Serialize, PartialEq, JsonSchema,
+)]
 #[serde(untagged)]
 pub enum SourceCommand {
     Bare(Template),
@@ -135,7 +155,13 @@ impl From<Template> for SourceCommand {
 
 #[serde_as]
 #[derive(
-    Debug, Clone, serde::Deserialize, serde::Serialize, PartialEq, Default,
+    Debug,
+    Clone,
+    serde::Deserialize,
+    serde::Serialize,
+    PartialEq,
+    Default,
+    JsonSchema,
 )]
 pub struct CommandSpec {
     #[serde(rename = "command")]
@@ -186,7 +212,13 @@ impl CommandSpec {
 
 /// Execution mode for external actions
 #[derive(
-    Debug, Clone, Default, serde::Deserialize, serde::Serialize, PartialEq,
+    Debug,
+    Clone,
+    Default,
+    serde::Deserialize,
+    serde::Serialize,
+    PartialEq,
+    JsonSchema,
 )]
 #[serde(rename_all = "lowercase")]
 pub enum ExecutionMode {
@@ -195,13 +227,106 @@ pub enum ExecutionMode {
     Fork,
     /// Replace the current process with the command (tv exits, command takes over)
     Execute,
+    /// Run the command in the background and display its output in a panel
+    /// without leaving tv
+    Capture,
+}
+
+/// What tv should do once an external action has completed.
+///
+/// Parsed from strings such as `"reload"`, `"quit"`, `"clear_selection"`
+/// or `"switch:<channel>"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PostAction {
+    /// Reload the current channel's source
+    Reload,
+    /// Exit tv
+    Quit,
+    /// Switch to the given channel
+    Switch(String),
+    /// Clear the current multi-selection
+    ClearSelection,
+}
+
+const SWITCH_POST_ACTION_PREFIX: &str = "switch:";
+
+impl std::str::FromStr for PostAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "reload" => Ok(Self::Reload),
+            "quit" => Ok(Self::Quit),
+            "clear_selection" => Ok(Self::ClearSelection),
+            other => match other.strip_prefix(SWITCH_POST_ACTION_PREFIX) {
+                Some(channel) if !channel.trim().is_empty() => {
+                    Ok(Self::Switch(channel.trim().to_string()))
+                }
+                Some(_) => Err(format!(
+                    "missing channel name in '{other}' (expected 'switch:<channel>')"
+                )),
+                None => Err(format!(
+                    "unknown post-action '{other}' (expected one of 'reload', \
+                     'quit', 'clear_selection' or 'switch:<channel>')"
+                )),
+            },
+        }
+    }
+}
+
+impl Display for PostAction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Reload => write!(f, "reload"),
+            Self::Quit => write!(f, "quit"),
+            Self::Switch(channel) => {
+                write!(f, "{SWITCH_POST_ACTION_PREFIX}{channel}")
+            }
+            Self::ClearSelection => write!(f, "clear_selection"),
+        }
+    }
+}
+
+impl Serialize for PostAction {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for PostAction {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        raw.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl JsonSchema for PostAction {
+    fn schema_name() -> Cow<'static, str> {
+        "PostAction".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "pattern": "^\\s*(reload|quit|clear_selection|switch:\\s*\\S.*)\\s*$",
+        })
+    }
 }
 
 fn default_separator() -> String {
     SPACE.to_string()
 }
 
-#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, PartialEq)]
+#[serde_as]
+#[derive(
+    Debug, Clone, serde::Deserialize, serde::Serialize, PartialEq, JsonSchema,
+)]
 pub struct ActionSpec {
     #[serde(default)]
     pub description: Option<String>,
@@ -215,10 +340,147 @@ pub struct ActionSpec {
     /// Example: `rm file1+SEPARATOR+file2+SEPARATOR+file3`
     #[serde(default = "default_separator")]
     pub separator: String,
-    // TODO: add `requirements` (see `prototypes::BinaryRequirement`)
+    /// What to do once the command has completed (e.g. `"reload"`)
+    ///
+    /// Accepts a single value or a list which is applied in order. Has no
+    /// effect with `ExecutionMode::Execute` since tv is no longer running.
+    #[serde(default)]
+    #[serde_as(as = "OneOrMany<_>")]
+    pub after: Vec<PostAction>,
+    /// Optional confirmation message shown in a yes/no dialog before the
+    /// command runs (e.g. `"Delete {} ?"`)
+    ///
+    /// The message is formatted with the selected entries the same way the
+    /// command is.
+    #[serde(default)]
+    pub confirm: Option<Template>,
+    /// Binaries that need to be available for this action to be runnable
+    #[serde(default)]
+    pub requirements: Vec<BinaryRequirement>,
+    /// Values to prompt the user for before running the action
+    ///
+    /// Each input is available in the command (and `confirm`) template as
+    /// `{input.NAME}`.
+    #[serde(default)]
+    pub inputs: Vec<ActionInput>,
+    /// Values entered for `inputs`, see [`ActionSpec::with_inputs`]
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub input_values: Vec<(String, String)>,
+}
+
+/// A named value prompted for before running an action.
+#[derive(
+    Debug, Clone, serde::Deserialize, serde::Serialize, PartialEq, JsonSchema,
+)]
+pub struct ActionInput {
+    pub name: String,
+    /// Text displayed when asking for the value (defaults to the name)
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Value used when the user submits an empty input
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+impl ActionInput {
+    pub fn prompt(&self) -> &str {
+        self.prompt.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// Stands for the value of the `index`-th input in a template.
+///
+/// Values are only put in place once the template has been formatted, so that
+/// nothing they contain can be mistaken for template syntax.
+fn input_marker(index: usize) -> String {
+    format!("\u{1}{index}\u{1}")
+}
+
+/// Replace every `{input.NAME}` placeholder in `template` with the marker of
+/// its value.
+fn mark_inputs(
+    template: &Template,
+    values: &[(String, String)],
+) -> Result<Template> {
+    let mut raw = template.raw().to_string();
+    for (index, (name, _)) in values.iter().enumerate() {
+        raw = raw.replace(&format!("{{input.{name}}}"), &input_marker(index));
+    }
+    if let Some(placeholder) = regex!(r"\{input\.[^}]*\}").find(&raw) {
+        anyhow::bail!(
+            "'{}' doesn't match any of the action's inputs",
+            placeholder.as_str()
+        );
+    }
+    Template::parse(&raw).map_err(|e| anyhow::anyhow!(e))
+}
+
+impl ActionSpec {
+    /// Check the action's requirements against the system's PATH.
+    pub fn init_requirements(&mut self) {
+        self.requirements
+            .iter_mut()
+            .for_each(BinaryRequirement::init);
+    }
+
+    /// Return a copy of this action that will use the given input values.
+    ///
+    /// The `{input.NAME}` placeholders of the command and `confirm`
+    /// templates are filled by [`ActionSpec::fill_inputs`] once these have
+    /// been formatted.
+    ///
+    /// # Errors
+    /// If a template references an input that has no value.
+    pub fn with_inputs(&self, values: &[(String, String)]) -> Result<Self> {
+        let mut spec = self.clone();
+        for command in &mut spec.command.inner {
+            match command {
+                SourceCommand::Bare(template)
+                | SourceCommand::Named { run: template, .. } => {
+                    *template = mark_inputs(template, values)?;
+                }
+            }
+        }
+        spec.confirm = spec
+            .confirm
+            .as_ref()
+            .map(|template| mark_inputs(template, values))
+            .transpose()?;
+        spec.input_values = values.to_vec();
+        Ok(spec)
+    }
+
+    /// Put the input values in a command or message formatted from one of
+    /// this action's templates, each value going through `escape` first.
+    pub fn fill_inputs(
+        &self,
+        formatted: &str,
+        escape: impl Fn(&str) -> Result<String>,
+    ) -> Result<String> {
+        let mut filled = formatted.to_string();
+        for (index, (_, value)) in self.input_values.iter().enumerate() {
+            let marker = input_marker(index);
+            if filled.contains(&marker) {
+                filled = filled.replace(&marker, &escape(value)?);
+            }
+        }
+        Ok(filled)
+    }
+
+    /// Names of the required binaries that are not available.
+    ///
+    /// This should be called after `init_requirements()`.
+    pub fn unmet_requirements(&self) -> Vec<&str> {
+        self.requirements
+            .iter()
+            .filter(|r| !r.is_met())
+            .map(|r| r.bin_name.as_str())
+            .collect()
+    }
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, JsonSchema)]
 pub struct ChannelKeyBindings {
     /// Optional channel specific shortcut that, when pressed, switches directly to this channel.
     #[serde(default)]
@@ -241,14 +503,20 @@ pub fn remove_enter_keybinding(prototype: &mut ChannelPrototype) {
     }
 }
 
-#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(
+    Default, Debug, Clone, serde::Deserialize, serde::Serialize, JsonSchema,
+)]
 pub struct HistoryConfig {
     /// Whether to use global history for this channel (overrides global setting)
     #[serde(default)]
     pub global_mode: Option<bool>,
+    /// Whether queries typed in this channel are saved to the history
+    /// (defaults to true)
+    #[serde(default)]
+    pub enabled: Option<bool>,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, JsonSchema)]
 pub struct ChannelPrototype {
     pub metadata: Metadata,
     pub source: SourceSpec,
@@ -291,7 +559,12 @@ impl ChannelPrototype {
                 display: None,
                 output: None,
                 no_sort: false,
+                sort: None,
+                match_mode: MatchingMode::default(),
+                case_sensitivity: CaseSensitivity::default(),
                 frecency: true,
+                frecency_key: None,
+                frecency_namespace: None,
             },
             preview: None,
             ui: None,
@@ -342,7 +615,7 @@ impl Display for ChannelPrototype {
     }
 }
 
-#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, JsonSchema)]
 pub struct Metadata {
     pub name: String,
     pub description: Option<String>,
@@ -350,7 +623,7 @@ pub struct Metadata {
     pub requirements: Vec<BinaryRequirement>,
 }
 
-#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, JsonSchema)]
 #[serde(transparent)]
 pub struct BinaryRequirement {
     pub bin_name: String,
@@ -382,11 +655,20 @@ impl BinaryRequirement {
     }
 }
 
-#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+impl PartialEq for BinaryRequirement {
+    fn eq(&self, other: &Self) -> bool {
+        self.bin_name == other.bin_name
+    }
+}
+
+#[derive(
+    Debug, Clone, Default, serde::Deserialize, serde::Serialize, JsonSchema,
+)]
 pub struct SourceSpec {
     #[serde(flatten)]
     pub command: CommandSpec,
     #[serde(deserialize_with = "deserialize_entry_delimiter", default)]
+    #[schemars(with = "Option<String>")]
     pub entry_delimiter: Option<char>,
     #[serde(default)]
     pub ansi: bool,
@@ -400,11 +682,34 @@ pub struct SourceSpec {
     /// Matches the `--no-sort` CLI flag.
     #[serde(default)]
     pub no_sort: bool,
+    /// Criteria the results are sorted by, in order of precedence, e.g.
+    /// `["frecency", "score", "length", "index"]`. Defaults to frecency (when
+    /// enabled) then score. Ignored when `no_sort` is set.
+    #[serde(default)]
+    pub sort: Option<Vec<SortCriterion>>,
+    /// How the query is matched against the entries (`fuzzy`, `substring`,
+    /// `regex` or `path`). Defaults to `fuzzy`.
+    #[serde(default)]
+    pub match_mode: MatchingMode,
+    /// Whether the query is matched case sensitively (`smart`, `sensitive`
+    /// or `insensitive`). Defaults to `smart`, which only respects case when
+    /// the query contains uppercase characters.
+    #[serde(default)]
+    pub case_sensitivity: CaseSensitivity,
     /// Whether to use frecency-based ranking for this channel.
     /// Defaults to true. Set to false for channels where frecency doesn't make sense
     /// (e.g., channels with dynamic/random results, or where order is meaningful).
     #[serde(default = "default_frecency")]
     pub frecency: bool,
+    /// Template mapping entries to their key in the frecency table, e.g. to
+    /// only keep the path of `path:line` entries. Defaults to the raw entry.
+    #[serde(default)]
+    pub frecency_key: Option<Template>,
+    /// Name of the frecency table the channel uses. Channels with the same
+    /// namespace (and compatible keys) share frecency, e.g. `files` and
+    /// `git-files`. Defaults to the channel's name.
+    #[serde(default)]
+    pub frecency_namespace: Option<String>,
 }
 
 const fn default_frecency() -> bool {
@@ -427,7 +732,7 @@ where
     }
 }
 
-#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, JsonSchema)]
 pub struct PreviewSpec {
     #[serde(flatten)]
     pub command: CommandSpec,
@@ -474,7 +779,7 @@ impl PreviewSpec {
     }
 }
 
-#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, JsonSchema)]
 pub struct UiSpec {
     #[serde(default)]
     pub ui_scale: Option<u16>,
@@ -1046,6 +1351,149 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_action_spec_post_actions() {
+        let toml_data = r#"
+        [metadata]
+        name = "containers"
+
+        [source]
+        command = "docker ps -a"
+
+        [actions.stop]
+        command = "docker stop {}"
+        after = "reload"
+
+        [actions.remove]
+        command = "docker rm {}"
+        mode = "capture"
+        after = ["clear_selection", "reload", "switch:images"]
+
+        [actions.inspect]
+        command = "docker inspect {}"
+        "#;
+
+        let prototype: ChannelPrototype = from_str(toml_data).unwrap();
+
+        let stop = prototype.actions.get("stop").unwrap();
+        assert_eq!(stop.mode, ExecutionMode::Fork);
+        assert_eq!(stop.after, vec![PostAction::Reload]);
+
+        let remove = prototype.actions.get("remove").unwrap();
+        assert_eq!(remove.mode, ExecutionMode::Capture);
+        assert_eq!(
+            remove.after,
+            vec![
+                PostAction::ClearSelection,
+                PostAction::Reload,
+                PostAction::Switch("images".to_string()),
+            ]
+        );
+
+        let inspect = prototype.actions.get("inspect").unwrap();
+        assert!(inspect.after.is_empty());
+    }
+
+    #[test]
+    fn test_action_spec_confirm_and_requirements() {
+        let toml_data = r#"
+        [metadata]
+        name = "git-stash"
+
+        [source]
+        command = "git stash list"
+
+        [actions.drop]
+        command = "git stash drop {}"
+        confirm = "Drop {} ?"
+        requirements = ["git", "surely-not-an-installed-binary"]
+        "#;
+
+        let mut prototype: ChannelPrototype = from_str(toml_data).unwrap();
+        let drop = prototype.actions.get_mut("drop").unwrap();
+        assert_eq!(drop.confirm.as_ref().unwrap().raw(), "Drop {} ?");
+        assert_eq!(
+            drop.requirements,
+            vec![
+                BinaryRequirement::new("git"),
+                BinaryRequirement::new("surely-not-an-installed-binary"),
+            ]
+        );
+
+        drop.init_requirements();
+        assert!(
+            drop.unmet_requirements()
+                .contains(&"surely-not-an-installed-binary")
+        );
+    }
+
+    #[test]
+    fn test_action_spec_inputs() {
+        let toml_data = r#"
+        [metadata]
+        name = "git-log"
+
+        [source]
+        command = "git log --oneline"
+
+        [actions.branch]
+        command = "git branch {input.branch} {split: :0}"
+        confirm = "Create {input.branch} from {split: :0} ?"
+        inputs = [
+            { name = "branch", prompt = "New branch name" },
+            { name = "remote", default = "origin" },
+        ]
+        "#;
+
+        let prototype: ChannelPrototype = from_str(toml_data).unwrap();
+        let branch = prototype.actions.get("branch").unwrap();
+        assert_eq!(branch.inputs.len(), 2);
+        assert_eq!(branch.inputs[0].prompt(), "New branch name");
+        assert_eq!(branch.inputs[1].prompt(), "remote");
+        assert_eq!(branch.inputs[1].default.as_deref(), Some("origin"));
+
+        let resolved = branch
+            .with_inputs(&[("branch".to_string(), "feature/x".to_string())])
+            .unwrap();
+        let fill = |template: &Template| {
+            resolved
+                .fill_inputs(&template.format("1a2b3c fix").unwrap(), |v| {
+                    Ok(v.to_string())
+                })
+                .unwrap()
+        };
+        assert_eq!(
+            fill(resolved.command.inner[0].template()),
+            "git branch feature/x 1a2b3c"
+        );
+        assert_eq!(
+            fill(resolved.confirm.as_ref().unwrap()),
+            "Create feature/x from 1a2b3c ?"
+        );
+        // placeholders must refer to one of the inputs
+        assert!(branch.with_inputs(&[]).is_err());
+        // the original spec is left untouched
+        assert_eq!(
+            branch.command.inner[0].template().raw(),
+            "git branch {input.branch} {split: :0}"
+        );
+    }
+
+    #[test]
+    fn test_post_action_parsing() {
+        assert_eq!("quit".parse::<PostAction>(), Ok(PostAction::Quit));
+        assert_eq!(
+            "switch: files".parse::<PostAction>(),
+            Ok(PostAction::Switch("files".to_string()))
+        );
+        assert!("switch:".parse::<PostAction>().is_err());
+        assert!("explode".parse::<PostAction>().is_err());
+        assert_eq!(
+            PostAction::Switch("files".to_string()).to_string(),
+            "switch:files"
+        );
+    }
+
     #[test]
     fn test_command_spec_with_shell_override() {
         let toml_data = r#"
@@ -0,0 +1,398 @@
+use crate::{
+    channels::{entry_processor::EntryProcessor, prototypes::Template},
+    config::frecency::FrecencyBlend,
+    frecency::ChannelFrecency,
+};
+use nucleo::{Item, Match, SortStrategy};
+use parking_lot::RwLock;
+use rustc_hash::FxHashMap;
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// A builtin sort criterion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortField {
+    /// Entries picked often and recently first (see `source.frecency`)
+    Frecency,
+    /// Best matches first
+    Score,
+    /// Shortest entries first
+    Length,
+    /// Source order
+    Index,
+}
+
+/// A builtin sort criterion, written with a leading `-` when reversed (e.g.
+/// `-index` for the latest entries of the source first).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct FieldOrder {
+    pub field: SortField,
+    pub reverse: bool,
+}
+
+impl FromStr for FieldOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (reverse, name) = match s.trim().strip_prefix('-') {
+            Some(name) => (true, name),
+            None => (false, s.trim()),
+        };
+        let field = match name {
+            "frecency" => SortField::Frecency,
+            "score" => SortField::Score,
+            "length" => SortField::Length,
+            "index" => SortField::Index,
+            _ => {
+                return Err(format!(
+                    "unknown sort criterion '{s}', expected one of: frecency, score, length, index"
+                ));
+            }
+        };
+        Ok(Self { field, reverse })
+    }
+}
+
+impl TryFrom<String> for FieldOrder {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl Display for FieldOrder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self.field {
+            SortField::Frecency => "frecency",
+            SortField::Score => "score",
+            SortField::Length => "length",
+            SortField::Index => "index",
+        };
+        if self.reverse {
+            write!(f, "-{name}")
+        } else {
+            write!(f, "{name}")
+        }
+    }
+}
+
+impl From<FieldOrder> for String {
+    fn from(order: FieldOrder) -> Self {
+        order.to_string()
+    }
+}
+
+impl JsonSchema for FieldOrder {
+    fn schema_name() -> Cow<'static, str> {
+        "FieldOrder".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "pattern": "^\\s*-?(frecency|score|length|index)\\s*$",
+        })
+    }
+}
+
+/// One of the criteria of a channel's `source.sort` list.
+///
+/// ```toml
+/// [source]
+/// sort = ["score", { key = "{split: :1}", reverse = true }, "index"]
+/// ```
+#[derive(
+    Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(untagged)]
+pub enum SortCriterion {
+    Field(FieldOrder),
+    /// Sort by a value extracted from each entry with a template, numerically
+    /// when both values are numbers. Entries the template fails on go last.
+    Key {
+        key: Template,
+        #[serde(default)]
+        reverse: bool,
+    },
+}
+
+impl SortCriterion {
+    pub fn field(field: SortField) -> Self {
+        Self::Field(FieldOrder {
+            field,
+            reverse: false,
+        })
+    }
+}
+
+/// Parses a comma separated list of builtin criteria, e.g. `length,-index`.
+pub fn parse_sort_criteria(s: &str) -> Result<Vec<SortCriterion>, String> {
+    s.split(',')
+        .filter(|c| !c.trim().is_empty())
+        .map(|c| c.parse().map(SortCriterion::Field))
+        .collect()
+}
+
+type Comparator<T> = Box<
+    dyn Fn(&Match, &Item<'_, T>, &Match, &Item<'_, T>) -> Ordering
+        + Send
+        + Sync,
+>;
+
+/// Build the strategy used to sort a channel's results.
+///
+/// Without explicit criteria, results are sorted by frecency (when enabled)
+/// and then by score. Remaining ties are broken by source order.
+pub fn sort_strategy<P: EntryProcessor>(
+    no_sort: bool,
+    criteria: Option<&[SortCriterion]>,
+    frecency: Option<&ChannelFrecency>,
+) -> SortStrategy<P::Data> {
+    if no_sort {
+        return SortStrategy::Index;
+    }
+    let criteria = match criteria {
+        Some(criteria) => criteria.to_vec(),
+        // nucleo's builtin sort is faster than going through a comparator
+        None if frecency.is_none() => return SortStrategy::Score,
+        None => vec![
+            SortCriterion::field(SortField::Frecency),
+            SortCriterion::field(SortField::Score),
+        ],
+    };
+    let comparators: Vec<Comparator<P::Data>> = criteria
+        .into_iter()
+        .filter_map(|criterion| comparator::<P>(criterion, frecency))
+        .collect();
+    SortStrategy::Custom(Box::new(move |m1, i1, m2, i2| {
+        comparators
+            .iter()
+            .map(|compare| compare(m1, &i1, m2, &i2))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| m1.idx.cmp(&m2.idx))
+    }))
+}
+
+/// `None` for criteria that don't apply to the channel, i.e. frecency when
+/// it's disabled.
+fn comparator<P: EntryProcessor>(
+    criterion: SortCriterion,
+    frecency: Option<&ChannelFrecency>,
+) -> Option<Comparator<P::Data>> {
+    let (compare, reverse): (Comparator<P::Data>, bool) = match criterion {
+        SortCriterion::Field(FieldOrder { field, reverse }) => {
+            let compare: Comparator<P::Data> = match field {
+                SortField::Frecency => frecency_comparator::<P>(frecency?),
+                SortField::Score => {
+                    Box::new(|m1, _, m2, _| m2.score.cmp(&m1.score))
+                }
+                SortField::Length => Box::new(|_, i1, _, i2| {
+                    i1.matcher_columns[0]
+                        .len()
+                        .cmp(&i2.matcher_columns[0].len())
+                }),
+                SortField::Index => {
+                    Box::new(|m1, _, m2, _| m1.idx.cmp(&m2.idx))
+                }
+            };
+            (compare, reverse)
+        }
+        SortCriterion::Key { key, reverse } => {
+            let keys = ItemKeys::new(key);
+            let compare: Comparator<P::Data> =
+                Box::new(move |m1, i1, m2, i2| {
+                    match (
+                        keys.get::<P>(m1.idx, i1, SortValue::parse),
+                        keys.get::<P>(m2.idx, i2, SortValue::parse),
+                    ) {
+                        (Some(v1), Some(v2)) => v1.compare(&v2, reverse),
+                        (Some(_), None) => Ordering::Less,
+                        (None, Some(_)) => Ordering::Greater,
+                        (None, None) => Ordering::Equal,
+                    }
+                });
+            // reversing is handled by `SortValue::compare`, so that text and
+            // missing keys stay last
+            (compare, false)
+        }
+    };
+    if reverse {
+        Some(Box::new(move |m1, i1, m2, i2| {
+            compare(m1, i1, m2, i2).reverse()
+        }))
+    } else {
+        Some(compare)
+    }
+}
+
+/// Sorts by frecency, or by match score and frecency combined depending on
+/// `FrecencyConfig::blend`.
+fn frecency_comparator<P: EntryProcessor>(
+    frecency: &ChannelFrecency,
+) -> Comparator<P::Data> {
+    let cache = frecency.handle.create_cache(frecency.namespace.clone());
+    let keys = frecency.key.clone().map(ItemKeys::new);
+    let score = move |idx: u32, item: &Item<'_, P::Data>| {
+        let scores = cache.snapshot();
+        match &keys {
+            Some(keys) => keys
+                .get::<P>(idx, item, str::to_string)
+                .and_then(|key| scores.get(&key)),
+            None => scores.get(&P::frecency_key(item)),
+        }
+    };
+    match frecency.handle.config().blend {
+        FrecencyBlend::Strict => Box::new(move |m1, i1, m2, i2| {
+            match (score(m1.idx, i1), score(m2.idx, i2)) {
+                (Some(s1), Some(s2)) => s2.cmp(&s1),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+        }),
+        FrecencyBlend::Weighted => {
+            let weight = frecency.handle.config().weight;
+            #[allow(clippy::cast_precision_loss)]
+            let blended = move |m: &Match, item: &Item<'_, P::Data>| {
+                f64::from(m.score)
+                    + weight * score(m.idx, item).unwrap_or(0) as f64
+            };
+            Box::new(move |m1, i1, m2, i2| {
+                blended(m2, i2).total_cmp(&blended(m1, i1))
+            })
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SortValue {
+    Number(f64),
+    Text(String),
+}
+
+impl SortValue {
+    fn parse(value: &str) -> Self {
+        value.trim().parse().map_or_else(
+            |_| SortValue::Text(value.to_string()),
+            SortValue::Number,
+        )
+    }
+
+    /// Numbers sort before text, whether reversed or not.
+    fn compare(&self, other: &Self, reverse: bool) -> Ordering {
+        let ordering = match (self, other) {
+            (SortValue::Number(a), SortValue::Number(b)) => a.total_cmp(b),
+            (SortValue::Text(a), SortValue::Text(b)) => a.cmp(b),
+            (SortValue::Number(_), SortValue::Text(_)) => {
+                return Ordering::Less;
+            }
+            (SortValue::Text(_), SortValue::Number(_)) => {
+                return Ordering::Greater;
+            }
+        };
+        if reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+/// Values computed from the items with a template, once per item since
+/// formatting a template on every comparison would be too slow.
+///
+/// Values are cached by item index, so this must be rebuilt whenever the
+/// matcher is restarted.
+struct ItemKeys<V> {
+    template: Template,
+    values: RwLock<FxHashMap<u32, Option<V>>>,
+}
+
+impl<V: Clone> ItemKeys<V> {
+    fn new(template: Template) -> Self {
+        Self {
+            template,
+            values: RwLock::default(),
+        }
+    }
+
+    fn get<P: EntryProcessor>(
+        &self,
+        idx: u32,
+        item: &Item<'_, P::Data>,
+        parse: impl FnOnce(&str) -> V,
+    ) -> Option<V> {
+        if let Some(value) = self.values.read().get(&idx) {
+            return value.clone();
+        }
+        // the frecency key is the entry's raw text
+        let value = self
+            .template
+            .format(&P::frecency_key(item))
+            .ok()
+            .map(|value| parse(&value));
+        self.values.write().insert(idx, value.clone());
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_criteria() {
+        let criteria: Vec<SortCriterion> = toml::from_str::<
+            FxHashMap<String, Vec<SortCriterion>>,
+        >(
+            r#"sort = ["score", "-index", { key = "{split: :1}", reverse = true }]"#,
+        )
+        .unwrap()
+        .remove("sort")
+        .unwrap();
+        assert_eq!(
+            criteria,
+            vec![
+                SortCriterion::field(SortField::Score),
+                SortCriterion::Field(FieldOrder {
+                    field: SortField::Index,
+                    reverse: true
+                }),
+                SortCriterion::Key {
+                    key: Template::parse("{split: :1}").unwrap(),
+                    reverse: true
+                },
+            ]
+        );
+        assert_eq!(
+            parse_sort_criteria("length, -index").unwrap(),
+            vec![
+                SortCriterion::field(SortField::Length),
+                SortCriterion::Field(FieldOrder {
+                    field: SortField::Index,
+                    reverse: true
+                }),
+            ]
+        );
+        assert!(parse_sort_criteria("size").is_err());
+    }
+
+    #[test]
+    fn test_sort_values() {
+        let values = ["10", "9", "b", "a"].map(SortValue::parse);
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| a.compare(b, false));
+        assert_eq!(
+            sorted,
+            [&values[1], &values[0], &values[3], &values[2]].map(Clone::clone)
+        );
+    }
+}
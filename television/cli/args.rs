@@ -6,7 +6,7 @@ use clap::{Parser, Subcommand, ValueEnum};
 /// When no channel is specified, flags define a custom channel and some combinations
 /// require related flags (for example, preview flags need `--preview-command`).
 #[allow(clippy::struct_excessive_bools)]
-#[derive(Parser, Debug, Default)]
+#[derive(Parser, Debug, Default, Clone)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
     /// Which channel shall we watch?
@@ -69,6 +69,22 @@ pub struct Cli {
     )]
     pub no_sort: bool,
 
+    /// Criteria used to break ties between entries with the same match score.
+    ///
+    /// A comma separated list of `length`, `index` and `frecency`, each of
+    /// which can be reversed with a leading `-`. Results are then sorted by
+    /// frecency (when enabled), score and the given criteria, overriding the
+    /// channel's `source.sort` setting.
+    ///
+    /// Example: `tv files --tiebreak length,-index`
+    #[arg(
+        long,
+        value_name = "CRITERIA",
+        verbatim_doc_comment,
+        help_heading = "Source"
+    )]
+    pub tiebreak: Option<String>,
+
     /// Source display template to use for the current channel.
     ///
     /// When a channel is specified: This overrides the display template defined in the channel prototype.
@@ -643,6 +659,20 @@ pub struct Cli {
     #[arg(long, value_name = "PATH", verbatim_doc_comment, value_parser = validate_file_path, help_heading = "Configuration")]
     pub config_file: Option<String>,
 
+    /// Use a profile of the configuration.
+    ///
+    /// Profiles are defined in `[profiles.<name>]` tables of the configuration
+    /// file and override the rest of the configuration.
+    ///
+    /// Defaults to the `TV_PROFILE` environment variable.
+    #[arg(
+        long,
+        value_name = "NAME",
+        verbatim_doc_comment,
+        help_heading = "Configuration"
+    )]
+    pub profile: Option<String>,
+
     /// Provide a custom cable directory to use.
     ///
     /// This flag works identically in both channel mode and ad-hoc mode.
@@ -658,6 +688,18 @@ pub struct Cli {
     #[arg(long, verbatim_doc_comment, help_heading = "History")]
     pub global_history: bool,
 
+    /// Don't save anything to the history or the frecency data.
+    ///
+    /// Queries typed and entries selected during this session are forgotten
+    /// when tv exits, e.g. when searching for secrets.
+    #[arg(
+        long,
+        visible_alias = "incognito",
+        verbatim_doc_comment,
+        help_heading = "History"
+    )]
+    pub no_history: bool,
+
     #[command(subcommand)]
     pub command: Option<Command>,
 }
@@ -686,6 +728,177 @@ pub enum Command {
         #[arg(long, default_value = "false")]
         force: bool,
     },
+    /// Inspects and edits the frecency data used to rank entries.
+    Frecency {
+        #[command(subcommand)]
+        command: FrecencyCommand,
+    },
+    /// Inspects and edits the search history.
+    History {
+        #[command(subcommand)]
+        command: HistoryCommand,
+    },
+    /// Shows the configuration in use or writes the default one.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Checks cable channel files for errors or describes their format.
+    Channels {
+        #[command(subcommand)]
+        command: ChannelsCommand,
+    },
+}
+
+#[derive(Subcommand, Debug, PartialEq, Clone)]
+pub enum ChannelsCommand {
+    /// Checks channel files for errors and prints them along with their
+    /// location, exiting with a non-zero status if any are found.
+    ///
+    /// Lints the cable directory when no path is given. Directories are
+    /// searched for channel files recursively.
+    #[command(verbatim_doc_comment)]
+    Lint {
+        /// Channel files or directories to lint.
+        paths: Vec<String>,
+    },
+    /// Prints the JSON Schema of channel files, for editor completion and
+    /// validation.
+    Schema,
+}
+
+#[derive(Subcommand, Debug, PartialEq, Clone)]
+pub enum ConfigCommand {
+    /// Prints the merged configuration, each value annotated with where it
+    /// comes from: the defaults, the configuration file, a channel file or a
+    /// command line flag.
+    ///
+    /// Flags are taken into account when given before the subcommand, e.g.
+    /// `tv --preview-size 30 config show --channel files`.
+    #[command(verbatim_doc_comment)]
+    Show {
+        /// Include the settings of this channel.
+        #[arg(long)]
+        channel: Option<String>,
+        /// Output format.
+        #[arg(long, value_enum, default_value = "toml")]
+        format: ConfigFormat,
+    },
+    /// Writes the default configuration file, with comments describing
+    /// each setting.
+    Init {
+        /// Overwrite the configuration file if it already exists.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Prints the JSON Schema of the configuration file, for editor
+    /// completion and validation.
+    Schema,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+}
+
+#[derive(Subcommand, Debug, PartialEq, Clone)]
+pub enum FrecencyCommand {
+    /// Lists the top entries of each channel with their scores.
+    List {
+        /// Only list the entries of this channel (or frecency namespace).
+        channel: Option<String>,
+        /// Maximum number of entries to list per channel.
+        #[arg(short = 'n', long, default_value = "10")]
+        limit: usize,
+    },
+    /// Removes entries from a channel.
+    Remove {
+        channel: String,
+        #[arg(required = true)]
+        entries: Vec<String>,
+    },
+    /// Pins entries to the top of a channel's results.
+    ///
+    /// Pinned entries are never pruned.
+    Pin {
+        channel: String,
+        #[arg(required = true)]
+        entries: Vec<String>,
+    },
+    /// Unpins entries previously pinned.
+    Unpin {
+        channel: String,
+        #[arg(required = true)]
+        entries: Vec<String>,
+    },
+    /// Removes stale entries, in all channels or a single one.
+    #[command(group(
+        clap::ArgGroup::new("criteria")
+            .args(["older_than", "missing"])
+            .required(true)
+            .multiple(true)
+    ))]
+    Prune {
+        channel: Option<String>,
+        /// Remove entries last selected more than this many days ago.
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u64>,
+        /// Remove absolute paths that don't exist anymore, e.g. deleted
+        /// files. Requires a channel.
+        #[arg(long, requires = "channel")]
+        missing: bool,
+    },
+    /// Removes all the entries of a channel.
+    Clear { channel: String },
+    /// Writes the frecency data as JSON to a file, or to stdout.
+    Export { file: Option<String> },
+    /// Merges frecency data previously exported, e.g. on another machine.
+    Import {
+        file: String,
+        /// Replace the current data instead of merging into it.
+        #[arg(long)]
+        replace: bool,
+    },
+}
+
+#[derive(Subcommand, Debug, PartialEq, Clone)]
+pub enum HistoryCommand {
+    /// Lists the history entries, most recent first.
+    List {
+        /// Only list the entries of this channel.
+        #[arg(long)]
+        channel: Option<String>,
+        /// Maximum number of entries to list.
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+    },
+    /// Removes every entry with the given query.
+    Remove {
+        query: String,
+        /// Only remove the entries of this channel.
+        #[arg(long)]
+        channel: Option<String>,
+    },
+    /// Removes entries older than a number of days.
+    Prune {
+        #[arg(long, value_name = "DAYS")]
+        older_than: u64,
+    },
+    /// Removes all entries, or those of a channel.
+    Clear {
+        #[arg(long)]
+        channel: Option<String>,
+    },
+    /// Writes the history as JSON to a file, or to stdout.
+    Export { file: Option<String> },
+    /// Merges a history previously exported, e.g. on another machine.
+    Import {
+        file: String,
+        /// Replace the current history instead of merging into it.
+        #[arg(long)]
+        replace: bool,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
@@ -0,0 +1,116 @@
+//! The `tv channels` subcommands, which help maintaining cable channel
+//! files.
+
+use crate::{
+    cable::get_cable_files,
+    channels::{
+        lint::{Level, lint_channel_files},
+        prototypes::ChannelPrototype,
+    },
+    cli::args::ChannelsCommand,
+    config::Config,
+    utils::paths::expand_tilde,
+};
+use anyhow::{Context, Result, bail};
+use colored::Colorize;
+use schemars::schema_for;
+use std::{
+    fs,
+    io::{Write, stdout},
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+/// Handles `tv channels`.
+///
+/// `cable_dir` is linted when no path is given, and actions referenced by
+/// channel keybindings are looked up in the configuration's as well.
+pub fn handle_channels_command(
+    command: &ChannelsCommand,
+    cable_dir: &Path,
+    config: &Config,
+) -> Result<()> {
+    match command {
+        ChannelsCommand::Lint { paths } => {
+            let paths = if paths.is_empty() {
+                vec![cable_dir.to_path_buf()]
+            } else {
+                paths.iter().map(expand_tilde).collect()
+            };
+            if lint(&paths, config)? {
+                Ok(())
+            } else {
+                exit(1);
+            }
+        }
+        ChannelsCommand::Schema => {
+            writeln!(
+                stdout().lock(),
+                "{}",
+                serde_json::to_string_pretty(&schema_for!(ChannelPrototype))?
+            )?;
+            Ok(())
+        }
+    }
+}
+
+/// Prints the problems found in the channel files of `paths` and returns
+/// whether there were no errors.
+fn lint(paths: &[PathBuf], config: &Config) -> Result<bool> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut channel_files = get_cable_files(path);
+            channel_files.sort();
+            files.extend(channel_files);
+        } else if path.exists() {
+            files.push(path.clone());
+        } else {
+            bail!("{} does not exist", path.display());
+        }
+    }
+    let files = files
+        .into_iter()
+        .map(|path| {
+            fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))
+                .map(|content| (path, content))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let diagnostics =
+        lint_channel_files(&files, &config.actions, &config.keybindings);
+    let mut out = stdout().lock();
+    for diagnostic in &diagnostics {
+        let level = match diagnostic.level {
+            Level::Error => diagnostic.level.to_string().red().bold(),
+            Level::Warning => diagnostic.level.to_string().yellow().bold(),
+        };
+        writeln!(
+            out,
+            "{}:{}:{}: {level}: {}",
+            diagnostic.path.display(),
+            diagnostic.line,
+            diagnostic.column,
+            diagnostic.message
+        )?;
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.level == Level::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+    eprintln!(
+        "Checked {} channel file{}: {errors} error{}, {warnings} warning{}",
+        files.len(),
+        plural(files.len()),
+        plural(errors),
+        plural(warnings)
+    );
+    Ok(errors == 0)
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
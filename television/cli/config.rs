@@ -0,0 +1,225 @@
+//! The `tv config` subcommands, which show the configuration tv uses, write
+//! the default one and describe its format.
+
+use crate::{
+    cable::{find_channel_file, load_cable},
+    channels::prototypes::ChannelPrototype,
+    cli::{
+        args::{Cli, ConfigCommand, ConfigFormat},
+        post_process,
+    },
+    config::{
+        AppConfig, CONFIG_FILE_NAME, Config, ConfigEnv, DEFAULT_CONFIG,
+        env_overrides::{env_overrides, merged_env_overrides},
+        get_config_dir,
+        layers::ConfigLayers,
+        provenance::{AnnotatedConfig, Source},
+        user_config::{UserConfig, json_schema, resolve_profile},
+    },
+    utils::paths::expand_tilde,
+};
+use anyhow::{Context, Result, bail};
+use std::{
+    fs,
+    io::{Write, stdout},
+    path::PathBuf,
+};
+use toml::{Table, Value};
+
+/// Handles `tv config`.
+///
+/// This runs before the configuration is loaded so that `tv config init
+/// --force` can replace a configuration file tv fails to parse.
+pub fn handle_config_command(
+    command: &ConfigCommand,
+    cli: &Cli,
+) -> Result<()> {
+    match command {
+        ConfigCommand::Show { channel, format } => {
+            let config = annotated_config(cli, channel.as_deref())?;
+            let mut out = stdout().lock();
+            match format {
+                ConfigFormat::Toml => write!(out, "{}", config.to_toml())?,
+                ConfigFormat::Json => writeln!(
+                    out,
+                    "{}",
+                    serde_json::to_string_pretty(&config.to_json())?
+                )?,
+            }
+            Ok(())
+        }
+        ConfigCommand::Init { force } => {
+            let path = config_file(cli);
+            if path.exists() && !force {
+                bail!(
+                    "{} already exists, use --force to overwrite it",
+                    path.display()
+                );
+            }
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, DEFAULT_CONFIG).with_context(|| {
+                format!("Failed to write {}", path.display())
+            })?;
+            writeln!(
+                stdout().lock(),
+                "Wrote the default configuration to {}",
+                path.display()
+            )?;
+            Ok(())
+        }
+        ConfigCommand::Schema => {
+            writeln!(
+                stdout().lock(),
+                "{}",
+                serde_json::to_string_pretty(&json_schema())?
+            )?;
+            Ok(())
+        }
+    }
+}
+
+/// The configuration file tv reads, whether or not it exists.
+fn config_file(cli: &Cli) -> PathBuf {
+    cli.config_file
+        .as_deref()
+        .map_or_else(|| get_config_dir().join(CONFIG_FILE_NAME), expand_tilde)
+}
+
+/// Builds the configuration tv would run with given `cli`, optionally
+/// including the settings of `channel`.
+fn annotated_config(
+    cli: &Cli,
+    channel: Option<&str>,
+) -> Result<AnnotatedConfig> {
+    let config_file = config_file(cli);
+    let profile = resolve_profile(cli.profile.as_deref());
+    let merged_env_overrides = merged_env_overrides()?;
+    // fail the same way tv would on an invalid configuration
+    let base_config = Config::with_overrides(
+        &ConfigEnv::init()?,
+        cli.config_file.is_some().then_some(config_file.as_path()),
+        profile.as_deref(),
+        &merged_env_overrides,
+    )?;
+
+    let mut config = AnnotatedConfig::default();
+    // settings the default configuration file leaves out
+    config.merge(Table::try_from(AppConfig::default())?, &Source::Default);
+    config.merge(toml::from_str(DEFAULT_CONFIG)?, &Source::Default);
+
+    if config_file.is_file() {
+        let user_config = UserConfig::load(&config_file, profile.as_deref())?;
+        // the user's channel triggers replace the default ones instead of
+        // being merged with them
+        if user_config.layers.iter().any(|(_, layer)| {
+            layer
+                .get("shell_integration")
+                .and_then(|section| section.get("channel_triggers"))
+                .and_then(Value::as_table)
+                .is_some_and(|triggers| !triggers.is_empty())
+        }) {
+            config.remove(&["shell_integration", "channel_triggers"]);
+        }
+        for (source, mut layer) in user_config.layers {
+            // see `merge_tables`
+            if let Some(Value::Table(actions)) = layer.remove("actions") {
+                for (name, action) in actions {
+                    config.set(&["actions", &name], action, &source);
+                }
+            }
+            config.merge(layer, &source);
+        }
+    }
+
+    let cable_dir = cli.cable_dir.as_deref().map_or_else(
+        || base_config.application.cable_dir.clone(),
+        expand_tilde,
+    );
+    let (cable, _) = load_cable(&cable_dir);
+    let prototype = match channel {
+        Some(name) => {
+            let (source, content) = find_channel_file(&cable_dir, name)
+                .with_context(|| format!("Unknown channel: {name}"))?;
+            config.merge_channel(toml::from_str(&content)?, &source);
+            cable
+                .get(name)
+                .cloned()
+                .with_context(|| format!("Unknown channel: {name}"))?
+        }
+        None => ChannelPrototype::new("", ""),
+    };
+    // environment variables override the channel's settings as well
+    for (source, layer) in env_overrides()? {
+        config.merge(layer, &source);
+    }
+
+    // the flags are applied by the same code as when running tv
+    let mut cli = cli.clone();
+    cli.channel = channel.map(str::to_string);
+    let cli = post_process(cli, false, &cable);
+    ConfigLayers::new(base_config, prototype, cli)
+        .with_env_overrides(merged_env_overrides)
+        .annotate_flags(&mut config);
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cable::Cable;
+    use clap::Parser;
+
+    #[test]
+    fn test_annotate_flags() {
+        let cli = post_process(
+            Cli::parse_from([
+                "tv",
+                "files",
+                "--preview-size",
+                "30",
+                "--show-preview",
+                "--preview-padding",
+                "top=1;left=2",
+                "--keybindings",
+                "ctrl-k=\"select_prev_entry\"",
+                "--no-history",
+            ]),
+            false,
+            &Cable::from_prototypes(vec![ChannelPrototype::new(
+                "files", "ls",
+            )]),
+        );
+        let mut config = AnnotatedConfig::default();
+        ConfigLayers::new(
+            Config::default(),
+            ChannelPrototype::new("files", "ls"),
+            cli,
+        )
+        .annotate_flags(&mut config);
+
+        assert_eq!(
+            config.get(&["ui", "preview_panel", "size"]),
+            Some((&Value::Integer(30), &Source::Cli("--preview-size")))
+        );
+        assert_eq!(
+            config.get(&["ui", "preview_panel", "hidden"]),
+            Some((&Value::Boolean(false), &Source::Cli("--show-preview")))
+        );
+        assert_eq!(
+            config.get(&["ui", "preview_panel", "padding", "left"]),
+            Some((&Value::Integer(2), &Source::Cli("--preview-padding")))
+        );
+        assert_eq!(
+            config.get(&["keybindings", "ctrl-k"]).unwrap().0,
+            &Value::String("select_prev_entry".into())
+        );
+        assert_eq!(
+            config.get(&["history", "enabled"]),
+            Some((&Value::Boolean(false), &Source::Cli("--no-history")))
+        );
+        // flags that weren't given aren't recorded
+        assert!(config.get(&["ui", "ui_scale"]).is_none());
+    }
+}
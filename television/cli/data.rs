@@ -0,0 +1,318 @@
+//! The `tv frecency` and `tv history` subcommands, which edit the data tv
+//! keeps in its data directory.
+
+use crate::{
+    cli::args::{FrecencyCommand, HistoryCommand},
+    config::Config,
+    frecency::{Frecency, FrecencyData, current_timestamp},
+    history::{History, HistoryEntry, merge_entries},
+    utils::{paths::expand_tilde, strings::format_age},
+};
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    fs,
+    io::{Write, stdout},
+    path::Path,
+};
+
+const SECONDS_PER_DAY: u64 = 24 * 3600;
+
+/// The timestamp `days` days ago, for `--older-than`.
+fn cutoff_timestamp(days: u64) -> Result<u64> {
+    let age = days
+        .checked_mul(SECONDS_PER_DAY)
+        .with_context(|| format!("--older-than {days} is too large"))?;
+    Ok(current_timestamp().saturating_sub(age))
+}
+
+/// Whether `raw` is an absolute path that doesn't exist anymore, for
+/// `--missing`.
+///
+/// Relative entries are kept since they depend on the directory tv ran in,
+/// as are entries that aren't paths at all, e.g. environment variables.
+fn is_missing_path(raw: &str) -> bool {
+    let path = Path::new(raw);
+    path.is_absolute() && !path.exists()
+}
+
+pub fn handle_frecency_command(
+    command: &FrecencyCommand,
+    config: &Config,
+) -> Result<()> {
+    let max_entries = config.application.frecency_max_entries;
+    let frecency = Frecency::new(max_entries, &config.application.data_dir)
+        .with_config(config.frecency.clone());
+    frecency.init()?;
+
+    match command {
+        FrecencyCommand::List { channel, limit } => {
+            list_frecency(&frecency, channel.as_deref(), *limit)
+        }
+        FrecencyCommand::Remove { channel, entries } => {
+            let mut removed = 0;
+            frecency.update_file(|data| {
+                removed = entries
+                    .iter()
+                    .filter(|raw| data.remove(channel, raw))
+                    .count();
+            })?;
+            report_removed(removed);
+            Ok(())
+        }
+        FrecencyCommand::Pin { channel, entries }
+        | FrecencyCommand::Unpin { channel, entries } => {
+            let pinned = matches!(command, FrecencyCommand::Pin { .. });
+            frecency.update_file(|data| {
+                for raw in entries {
+                    data.set_pinned(channel, raw, pinned);
+                }
+            })
+        }
+        FrecencyCommand::Prune {
+            channel,
+            older_than,
+            missing,
+        } => {
+            let cutoff = older_than.map(cutoff_timestamp).transpose()?;
+            let mut removed = 0;
+            frecency.update_file(|data| {
+                removed = data.prune(channel.as_deref(), |record| {
+                    cutoff.is_some_and(|cutoff| record.last_access < cutoff)
+                        || (*missing && is_missing_path(&record.raw))
+                });
+            })?;
+            report_removed(removed);
+            Ok(())
+        }
+        FrecencyCommand::Clear { channel } => {
+            let mut removed = 0;
+            frecency.update_file(|data| {
+                removed = data.channels.remove(channel).map_or(0, |e| e.len());
+            })?;
+            report_removed(removed);
+            Ok(())
+        }
+        FrecencyCommand::Export { file } => {
+            export(&frecency.data(), file.as_deref())
+        }
+        FrecencyCommand::Import { file, replace } => {
+            let imported: FrecencyData = import(file)?;
+            frecency.update_file(|data| {
+                if *replace {
+                    *data = FrecencyData::default();
+                }
+                data.merge(imported, max_entries, &config.frecency);
+            })
+        }
+    }
+}
+
+fn list_frecency(
+    frecency: &Frecency,
+    channel: Option<&str>,
+    limit: usize,
+) -> Result<()> {
+    let now = current_timestamp();
+    let data = frecency.data();
+    let mut channels: Vec<&String> = data
+        .channels
+        .keys()
+        .filter(|name| channel.is_none_or(|c| c == name.as_str()))
+        .collect();
+    channels.sort();
+
+    let mut out = stdout().lock();
+    for (i, name) in channels.into_iter().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "{}", name.bold())?;
+        for record in data
+            .ranked(name, frecency.config(), now)
+            .into_iter()
+            .take(limit)
+        {
+            writeln!(
+                out,
+                "{:>7}  {:>4}x  {:>9}  {}{}",
+                record.score(frecency.config(), now),
+                record.access_count,
+                format_age(now.saturating_sub(record.last_access)),
+                record.raw,
+                if record.pinned { " (pinned)" } else { "" },
+            )?;
+        }
+    }
+    Ok(())
+}
+
+pub fn handle_history_command(
+    command: &HistoryCommand,
+    config: &Config,
+) -> Result<()> {
+    let mut history = History::new(
+        config.application.history_size,
+        "",
+        true,
+        &config.application.data_dir,
+    );
+    history.init()?;
+
+    match command {
+        HistoryCommand::List { channel, limit } => {
+            let now = current_timestamp();
+            let mut out = stdout().lock();
+            for entry in history
+                .get_entries()
+                .iter()
+                .rev()
+                .filter(|e| channel.as_ref().is_none_or(|c| *c == e.channel))
+                .take(limit.unwrap_or(usize::MAX))
+            {
+                write!(
+                    out,
+                    "{:>9}  {:<16}  {}",
+                    format_age(now.saturating_sub(entry.timestamp)),
+                    entry.channel,
+                    entry.query
+                )?;
+                if entry.selected.is_empty() {
+                    writeln!(out)?;
+                } else {
+                    writeln!(out, "  -> {}", entry.selected.join(", "))?;
+                }
+            }
+            Ok(())
+        }
+        HistoryCommand::Remove { query, channel } => {
+            let removed = prune_history(&mut history, |e| {
+                e.query == *query
+                    && channel.as_ref().is_none_or(|c| *c == e.channel)
+            })?;
+            report_removed(removed);
+            Ok(())
+        }
+        HistoryCommand::Prune { older_than } => {
+            let cutoff = cutoff_timestamp(*older_than)?;
+            let removed =
+                prune_history(&mut history, |e| e.timestamp < cutoff)?;
+            report_removed(removed);
+            Ok(())
+        }
+        HistoryCommand::Clear { channel } => {
+            let removed = prune_history(&mut history, |e| {
+                channel.as_ref().is_none_or(|c| *c == e.channel)
+            })?;
+            report_removed(removed);
+            Ok(())
+        }
+        HistoryCommand::Export { file } => {
+            export(&history.get_entries(), file.as_deref())
+        }
+        HistoryCommand::Import { file, replace } => {
+            let imported: Vec<HistoryEntry> = import(file)?;
+            history.update_file(|entries| {
+                if *replace {
+                    entries.clear();
+                }
+                merge_entries(entries, &imported);
+            })
+        }
+    }
+}
+
+/// Remove the history entries matching `predicate`, returning how many were
+/// removed.
+fn prune_history(
+    history: &mut History,
+    predicate: impl Fn(&HistoryEntry) -> bool,
+) -> Result<usize> {
+    let mut removed = 0;
+    history.update_file(|entries| {
+        let before = entries.len();
+        entries.retain(|e| !predicate(e));
+        removed = before - entries.len();
+    })?;
+    Ok(removed)
+}
+
+fn export<T: Serialize>(data: &T, file: Option<&str>) -> Result<()> {
+    let json = serde_json::to_string_pretty(data)?;
+    match file.map(expand_tilde) {
+        Some(path) => fs::write(&path, json)
+            .with_context(|| format!("Failed to write {}", path.display())),
+        None => Ok(writeln!(stdout().lock(), "{json}")?),
+    }
+}
+
+fn import<T: DeserializeOwned>(file: &str) -> Result<T> {
+    let file = expand_tilde(file);
+    let json = fs::read_to_string(&file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    serde_json::from_str(&json)
+        .with_context(|| format!("Failed to parse {}", file.display()))
+}
+
+fn report_removed(count: usize) {
+    let noun = if count == 1 { "entry" } else { "entries" };
+    eprintln!("Removed {count} {noun}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cli::args::Cli, frecency::FrecencyRecord};
+    use clap::Parser;
+    use rustc_hash::FxHashMap;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_prune_missing() {
+        let dir = tempdir().unwrap();
+        let deleted = dir.path().join("deleted.txt").display().to_string();
+        let existing = dir.path().display().to_string();
+        let entries = |raws: &[&str]| -> FxHashMap<String, FrecencyRecord> {
+            raws.iter()
+                .map(|raw| {
+                    (
+                        (*raw).to_string(),
+                        FrecencyRecord::new((*raw).to_string()),
+                    )
+                })
+                .collect()
+        };
+        let mut data = FrecencyData::default();
+        data.channels.insert(
+            "files".to_string(),
+            entries(&[&deleted, &existing, "src/not/here.rs"]),
+        );
+        data.channels
+            .insert("env".to_string(), entries(&["HOME=/home/tv"]));
+
+        assert_eq!(data.prune(Some("files"), |r| is_missing_path(&r.raw)), 1);
+        assert_eq!(data.prune(Some("env"), |r| is_missing_path(&r.raw)), 0);
+        assert!(!data.channels["files"].contains_key(&deleted));
+        assert!(data.channels["files"].contains_key(&existing));
+        assert!(data.channels["files"].contains_key("src/not/here.rs"));
+    }
+
+    #[test]
+    fn test_prune_missing_requires_channel() {
+        assert!(
+            Cli::try_parse_from(["tv", "frecency", "prune", "--missing"])
+                .is_err()
+        );
+        assert!(
+            Cli::try_parse_from([
+                "tv",
+                "frecency",
+                "prune",
+                "files",
+                "--missing"
+            ])
+            .is_ok()
+        );
+    }
+}
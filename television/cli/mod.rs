@@ -1,11 +1,15 @@
 use crate::{
     action::{Action, Actions},
     cable::Cable,
-    channels::prototypes::{ChannelPrototype, Template},
+    channels::{
+        prototypes::{ChannelPrototype, Template},
+        sort::{SortCriterion, parse_sort_criteria},
+    },
     cli::args::{Cli, Command},
     config::{
         Keybindings, get_config_dir, get_data_dir, merge_keybindings,
         ui::{BorderType, Padding},
+        user_config::resolve_profile,
     },
     errors::{cli_parsing_error_exit, unknown_channel_exit},
     event::Key,
@@ -25,6 +29,9 @@ use std::{
 use tracing::debug;
 
 pub mod args;
+pub mod channels;
+pub mod config;
+pub mod data;
 
 /// # CLI Use Cases
 ///
@@ -72,6 +79,7 @@ pub struct ChannelCli {
     pub autocomplete_prompt: Option<String>,
     pub ansi: bool,
     pub no_sort: bool,
+    pub tiebreak: Option<Vec<SortCriterion>>,
 
     // Preview configuration
     pub preview_command: Option<Template>,
@@ -132,7 +140,9 @@ pub struct ChannelCli {
 pub struct GlobalCli {
     pub workdir: Option<PathBuf>,
     pub global_history: bool,
+    pub no_history: bool,
     pub config_file: Option<PathBuf>,
+    pub profile: Option<String>,
     pub cable_dir: Option<PathBuf>,
     pub command: Option<Command>,
     pub tick_rate: Option<u64>,
@@ -296,6 +306,12 @@ pub fn post_process(
                 .unwrap_or_else(|e| cli_parsing_error_exit(&e.to_string()))
         });
 
+    let tiebreak = cli.tiebreak.as_ref().map(|criteria| {
+        parse_sort_criteria(criteria).unwrap_or_else(|e| {
+            cli_parsing_error_exit(&format!("Error parsing tiebreak: {e}"))
+        })
+    });
+
     // Determine layout
     let layout: Option<Orientation> = cli.layout.map(Orientation::from);
     let input_position = cli.input_position.map(InputPosition::from);
@@ -328,6 +344,7 @@ pub fn post_process(
             source_output,
             source_entry_delimiter,
             no_sort: cli.no_sort,
+            tiebreak,
 
             // Autocomplete and ANSI configuration
             autocomplete_prompt: cli.autocomplete_prompt,
@@ -390,9 +407,11 @@ pub fn post_process(
             // Workdir and global history
             workdir: working_directory,
             global_history: cli.global_history,
+            no_history: cli.no_history,
 
             // Configuration sources
             config_file: cli.config_file.map(|p| expand_tilde(&p)),
+            profile: resolve_profile(cli.profile.as_deref()),
             cable_dir: cli.cable_dir.map(|p| expand_tilde(&p)),
 
             // Command handling
@@ -467,8 +486,8 @@ fn validate_adhoc_mode_constraints(cli: &Cli, readable_stdin: bool) {
     }
 }
 
-const CLI_KEYBINDINGS_DELIMITER: char = ';';
-const CLI_PADDING_DELIMITER: char = ';';
+pub(crate) const CLI_KEYBINDINGS_DELIMITER: char = ';';
+pub(crate) const CLI_PADDING_DELIMITER: char = ';';
 
 /// Parse a keybindings literal into a `KeyBindings` struct.
 ///
@@ -490,7 +509,7 @@ fn parse_keybindings_literal(
 }
 
 /// Parses the CLI padding values into `config::ui::Padding`
-fn parse_padding_literal(
+pub(crate) fn parse_padding_literal(
     cli_padding: &str,
     delimiter: char,
 ) -> Result<Padding> {
@@ -0,0 +1,129 @@
+use crate::channels::prototypes::ActionSpec;
+use rustc_hash::FxHashMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// An action defined in the `[actions]` table of the configuration file.
+///
+/// Global actions are merged into the actions of every channel, unless the
+/// channel defines an action with the same name in which case the channel's
+/// version takes precedence.
+///
+/// # Example
+/// ```toml
+/// [actions.edit]
+/// description = "Open the selected entries in $EDITOR"
+/// command = "${EDITOR:-vim} {}"
+/// mode = "execute"
+/// channels = ["files", "git-files"]
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema)]
+pub struct GlobalActionSpec {
+    #[serde(flatten)]
+    pub spec: ActionSpec,
+    /// Names of the channels this action is available in.
+    ///
+    /// An empty list (the default) makes the action available everywhere.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<String>,
+}
+
+impl GlobalActionSpec {
+    /// Whether this action should be offered in the given channel.
+    pub fn applies_to(&self, channel_name: &str) -> bool {
+        self.channels.is_empty()
+            || self.channels.iter().any(|name| name == channel_name)
+    }
+}
+
+/// Add the global actions that apply to `channel_name` to `channel_actions`.
+///
+/// Actions already defined by the channel are left untouched.
+pub fn merge_global_actions(
+    channel_actions: &mut FxHashMap<String, ActionSpec>,
+    global_actions: &FxHashMap<String, GlobalActionSpec>,
+    channel_name: &str,
+) {
+    for (name, action) in global_actions {
+        if action.applies_to(channel_name)
+            && !channel_actions.contains_key(name)
+        {
+            channel_actions.insert(name.clone(), action.spec.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::prototypes::ExecutionMode;
+
+    fn global_actions(toml_data: &str) -> FxHashMap<String, GlobalActionSpec> {
+        toml::from_str(toml_data).unwrap()
+    }
+
+    #[test]
+    fn test_global_action_deserialization() {
+        let actions = global_actions(
+            r#"
+            [edit]
+            description = "Open in editor"
+            command = "vim {}"
+            mode = "execute"
+            channels = ["files", "git-files"]
+
+            [copy]
+            command = "echo {} | wl-copy"
+            "#,
+        );
+
+        let edit = actions.get("edit").unwrap();
+        assert_eq!(edit.spec.description.as_deref(), Some("Open in editor"));
+        assert_eq!(edit.spec.command.inner[0].template().raw(), "vim {}");
+        assert_eq!(edit.spec.mode, ExecutionMode::Execute);
+        assert_eq!(edit.channels, vec!["files", "git-files"]);
+
+        let copy = actions.get("copy").unwrap();
+        assert_eq!(copy.spec.mode, ExecutionMode::Fork);
+        assert!(copy.channels.is_empty());
+    }
+
+    #[test]
+    fn test_merge_global_actions_respects_filters_and_precedence() {
+        let global = global_actions(
+            r#"
+            [edit]
+            command = "vim {}"
+            channels = ["files"]
+
+            [copy]
+            command = "echo {} | wl-copy"
+            "#,
+        );
+        let mut channel_actions = global_actions(
+            r#"
+            [copy]
+            command = "echo {} | xclip"
+            "#,
+        )
+        .into_iter()
+        .map(|(name, action)| (name, action.spec))
+        .collect::<FxHashMap<_, _>>();
+
+        let mut files_actions = channel_actions.clone();
+        merge_global_actions(&mut files_actions, &global, "files");
+        assert_eq!(files_actions.len(), 2);
+        assert_eq!(
+            files_actions["edit"].command.inner[0].template().raw(),
+            "vim {}"
+        );
+        assert_eq!(
+            files_actions["copy"].command.inner[0].template().raw(),
+            "echo {} | xclip"
+        );
+
+        merge_global_actions(&mut channel_actions, &global, "env");
+        assert_eq!(channel_actions.len(), 1);
+        assert!(!channel_actions.contains_key("edit"));
+    }
+}
@@ -0,0 +1,259 @@
+use super::{
+    AppConfig, Config, DEFAULT_CONFIG, FrecencyConfig, UiConfig,
+    provenance::Source,
+    shell_integration::ShellIntegrationConfig,
+    user_config::{PROFILE_ENV_VAR, merge_tables},
+};
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+use serde_json::{Map, Value as JsonValue, json};
+use toml::{Table, Value};
+use tracing::{debug, warn};
+
+/// Prefix of the environment variables overriding a setting, e.g.
+/// `TV_UI_THEME` or `TV_UI_PREVIEW_PANEL_SIZE`.
+pub const ENV_PREFIX: &str = "TV_";
+
+/// Section of the top-level settings, e.g. `TV_APPLICATION_TICK_RATE`.
+const APPLICATION_SECTION: &str = "application";
+
+/// The settings overridden by `TV_<SECTION>_<KEY>` environment variables,
+/// one table per variable.
+pub fn env_overrides() -> Result<Vec<(Source, Table)>> {
+    overrides_from(std::env::vars())
+}
+
+/// The settings overridden by `TV_<SECTION>_<KEY>` environment variables,
+/// as a single table.
+pub fn merged_env_overrides() -> Result<Table> {
+    let mut merged = Table::new();
+    for (_, table) in env_overrides()? {
+        merge_tables(&mut merged, table);
+    }
+    Ok(merged)
+}
+
+fn overrides_from(
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Result<Vec<(Source, Table)>> {
+    let schema = schema();
+    let mut vars: Vec<_> = vars
+        .into_iter()
+        .filter(|(name, _)| {
+            name.starts_with(ENV_PREFIX) && name != PROFILE_ENV_VAR
+        })
+        .collect();
+    // apply them in a stable order
+    vars.sort();
+
+    let mut overrides = Vec::new();
+    for (name, raw) in vars {
+        let Some((path, setting)) = resolve(&schema, &name) else {
+            continue;
+        };
+        let value = if setting.is_string() || setting.is_null() {
+            Value::String(raw.clone())
+        } else {
+            parse_value(&raw)
+                .with_context(|| format!("Invalid value for {name}: {raw}"))?
+        };
+
+        let mut table = Table::new();
+        let (last, parents) = path.split_last().expect("paths aren't empty");
+        let mut current = &mut table;
+        for key in parents {
+            current = current
+                .entry(key.clone())
+                .or_insert_with(|| Value::Table(Table::new()))
+                .as_table_mut()
+                .expect("just inserted a table");
+        }
+        current.insert(last.clone(), value);
+
+        // check the value here so that errors name the variable
+        Config::deserialize(Value::Table(table.clone())).map_err(|e| {
+            anyhow!("Invalid value for {name}: {}", e.message().trim())
+        })?;
+        debug!("{name} overrides {}", path.join("."));
+        overrides.push((Source::Env(name), table));
+    }
+    Ok(overrides)
+}
+
+/// The path of the setting `name` refers to, along with its default value.
+///
+/// Keys may contain underscores themselves, so the name is matched against
+/// the known settings rather than split on every underscore.
+fn resolve<'a>(
+    schema: &'a Map<String, JsonValue>,
+    name: &str,
+) -> Option<(Vec<String>, &'a JsonValue)> {
+    let name = name.strip_prefix(ENV_PREFIX)?.to_lowercase();
+    let resolved = if let Some(key) = name.strip_prefix(APPLICATION_SECTION) {
+        key.strip_prefix('_').and_then(|key| {
+            schema
+                .get_key_value(key)
+                .filter(|(_, value)| !value.is_object())
+                .map(|(key, value)| (vec![key.clone()], value))
+        })
+    } else {
+        let section = schema.iter().find(|(section, value)| {
+            value.is_object() && name.starts_with(&format!("{section}_"))
+        });
+        // not a setting, e.g. `TV_CI`
+        let (section, settings) = section?;
+        let key = &name[section.len() + 1..];
+        resolve_key(settings.as_object()?, key).map(|(mut path, value)| {
+            path.insert(0, section.clone());
+            (path, value)
+        })
+    };
+    if resolved.is_none() {
+        warn!(
+            "Ignoring {ENV_PREFIX}{}: unknown setting",
+            name.to_uppercase()
+        );
+    }
+    resolved
+}
+
+fn resolve_key<'a>(
+    table: &'a Map<String, JsonValue>,
+    key: &str,
+) -> Option<(Vec<String>, &'a JsonValue)> {
+    if let Some((name, value)) = table.get_key_value(key) {
+        return Some((vec![name.clone()], value));
+    }
+    table.iter().find_map(|(name, value)| {
+        let rest = key.strip_prefix(name.as_str())?.strip_prefix('_')?;
+        let (mut path, value) = resolve_key(value.as_object()?, rest)?;
+        path.insert(0, name.clone());
+        Some((path, value))
+    })
+}
+
+/// Parses `raw` as a TOML value, e.g. `42`, `true` or `["a", "b"]`.
+fn parse_value(raw: &str) -> Option<Value> {
+    toml::from_str::<Table>(&format!("value = {raw}"))
+        .ok()?
+        .remove("value")
+}
+
+/// Every known setting with its default value.
+///
+/// Settings without a default, e.g. `shell`, are only missing from the
+/// default configuration file, so both are combined. Serializing to JSON
+/// keeps these as `null` where TOML would leave them out.
+fn schema() -> Map<String, JsonValue> {
+    let mut schema = json!({
+        "ui": UiConfig::default(),
+        "shell_integration": ShellIntegrationConfig::default(),
+        "frecency": FrecencyConfig::default(),
+    });
+    union(&mut schema, json!(AppConfig::default()));
+    if let Ok(default_config) = toml::from_str::<Table>(DEFAULT_CONFIG) {
+        union(&mut schema, json!(default_config));
+    }
+    match schema {
+        JsonValue::Object(schema) => schema,
+        _ => Map::new(),
+    }
+}
+
+/// Adds the keys of `other` missing from `base`.
+fn union(base: &mut JsonValue, other: JsonValue) {
+    let (JsonValue::Object(base), JsonValue::Object(other)) = (base, other)
+    else {
+        return;
+    };
+    for (key, value) in other {
+        match base.get_mut(&key) {
+            Some(existing @ JsonValue::Null) => *existing = value,
+            Some(existing) => union(existing, value),
+            None => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+            .collect()
+    }
+
+    fn overrides(vars_: &[(&str, &str)]) -> Vec<(String, String)> {
+        overrides_from(vars(vars_))
+            .unwrap()
+            .into_iter()
+            .map(|(source, table)| (source.to_string(), table.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        assert_eq!(
+            overrides(&[
+                ("TV_UI_THEME", "nord"),
+                ("TV_APPLICATION_TICK_RATE", "100"),
+                ("TV_UI_PREVIEW_PANEL_SIZE", "30"),
+                ("TV_UI_PREVIEW_PANEL_HEADER", "{}"),
+                ("TV_APPLICATION_SHELL", "zsh"),
+                ("TV_APPLICATION_HISTORY_EXCLUDE", "[\"token\"]"),
+                ("TV_VIM_ENABLED", "true"),
+                ("HOME", "/home/tv"),
+            ]),
+            vars(&[
+                (
+                    "TV_APPLICATION_HISTORY_EXCLUDE",
+                    "history_exclude = [\"token\"]\n"
+                ),
+                ("TV_APPLICATION_SHELL", "shell = \"zsh\"\n"),
+                ("TV_APPLICATION_TICK_RATE", "tick_rate = 100\n"),
+                (
+                    "TV_UI_PREVIEW_PANEL_HEADER",
+                    "[ui.preview_panel]\nheader = \"{}\"\n"
+                ),
+                (
+                    "TV_UI_PREVIEW_PANEL_SIZE",
+                    "[ui.preview_panel]\nsize = 30\n"
+                ),
+                ("TV_UI_THEME", "[ui]\ntheme = \"nord\"\n"),
+                ("TV_VIM_ENABLED", "[vim]\nenabled = true\n"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_env_overrides_ignore_other_variables() {
+        assert!(
+            overrides(&[
+                ("TV_PROFILE", "work"),
+                ("TV_CI", "1"),
+                ("TV_UI_UNKNOWN", "1"),
+                ("TV_APPLICATION_UI", "1"),
+                ("TV_TICK_RATE", "1"),
+            ])
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_invalid_env_override() {
+        let error =
+            overrides_from(vars(&[("TV_UI_UI_SCALE", "big")])).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid value for TV_UI_UI_SCALE: big");
+        let error =
+            overrides_from(vars(&[("TV_APPLICATION_TICK_RATE", "-1")]))
+                .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid value for TV_APPLICATION_TICK_RATE: invalid value: integer `-1`, expected u64"
+        );
+    }
+}
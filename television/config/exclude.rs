@@ -0,0 +1,102 @@
+use lazy_regex::{Regex, RegexBuilder, regex};
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
+
+/// Patterns of queries and entries that are never saved to the history or
+/// the frecency data, e.g. `token|password`.
+///
+/// Patterns are case-insensitive regular expressions that may match anywhere
+/// in the text.
+///
+/// # Example
+/// ```toml
+/// history_exclude = ["token|password", "^export "]
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ExcludePatterns(Vec<Regex>);
+
+impl ExcludePatterns {
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Result<Self, regex::Error> {
+        patterns
+            .iter()
+            .map(|pattern| {
+                RegexBuilder::new(pattern.as_ref())
+                    .case_insensitive(true)
+                    .build()
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+
+    /// Whether `text` matches any of the patterns.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.iter().any(|pattern| pattern.is_match(text))
+    }
+
+    fn patterns(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(Regex::as_str)
+    }
+}
+
+impl PartialEq for ExcludePatterns {
+    fn eq(&self, other: &Self) -> bool {
+        self.patterns().eq(other.patterns())
+    }
+}
+
+impl Hash for ExcludePatterns {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for pattern in self.patterns() {
+            pattern.hash(state);
+        }
+    }
+}
+
+impl Serialize for ExcludePatterns {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.patterns())
+    }
+}
+
+impl<'de> Deserialize<'de> for ExcludePatterns {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let patterns = Vec::<String>::deserialize(deserializer)?;
+        Self::new(&patterns).map_err(|e| {
+            D::Error::custom(format!("invalid exclusion pattern: {e}"))
+        })
+    }
+}
+
+impl JsonSchema for ExcludePatterns {
+    fn schema_name() -> Cow<'static, str> {
+        "ExcludePatterns".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        Vec::<String>::json_schema(generator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exclude_patterns() {
+        let patterns: ExcludePatterns =
+            serde_json::from_str(r#"["token|password", "^ssh "]"#).unwrap();
+
+        assert!(patterns.is_match("GITHUB_TOKEN=abc"));
+        assert!(patterns.is_match("ssh prod"));
+        assert!(!patterns.is_match("echo ssh"));
+        assert!(!ExcludePatterns::default().is_match("password"));
+        assert!(serde_json::from_str::<ExcludePatterns>(r#"["("]"#).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Configuration of how frecency scores are computed and combined with the
+/// match score when sorting results.
+///
+/// # Example
+/// ```toml
+/// [frecency]
+/// decay = [
+///     { hours = 4, weight = 100 },
+///     { hours = 24, weight = 70 },
+///     { hours = 168, weight = 50 },
+///     { hours = 720, weight = 30 },
+/// ]
+/// base_weight = 10
+/// max_count = 20
+/// blend = "weighted"
+/// weight = 0.1
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct FrecencyConfig {
+    /// Weight of an entry depending on how long ago it was last selected.
+    pub decay: Vec<DecayBucket>,
+    /// Weight of entries last selected longer ago than the oldest bucket.
+    pub base_weight: u64,
+    /// Selections beyond this count don't raise an entry's score.
+    pub max_count: u32,
+    pub blend: FrecencyBlend,
+    /// Weight of the frecency score relative to the match score, with
+    /// `blend = "weighted"`.
+    pub weight: f64,
+}
+
+/// Entries last selected at most `hours` ago get `weight`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DecayBucket {
+    pub hours: u64,
+    pub weight: u64,
+}
+
+/// How frecency is combined with the match score.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Eq,
+    JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum FrecencyBlend {
+    /// Entries with a higher frecency always come first, the match score only
+    /// breaking ties
+    #[default]
+    Strict,
+    /// Entries are sorted by their match score plus their frecency score
+    /// times `weight`, so that a much better match can beat a frequently
+    /// selected entry
+    Weighted,
+}
+
+impl Default for FrecencyConfig {
+    fn default() -> Self {
+        Self {
+            decay: vec![
+                DecayBucket {
+                    hours: 4,
+                    weight: 100,
+                },
+                DecayBucket {
+                    hours: 24,
+                    weight: 70,
+                },
+                DecayBucket {
+                    hours: 24 * 7,
+                    weight: 50,
+                },
+                DecayBucket {
+                    hours: 24 * 30,
+                    weight: 30,
+                },
+            ],
+            base_weight: 10,
+            max_count: 20,
+            blend: FrecencyBlend::Strict,
+            weight: 0.1,
+        }
+    }
+}
+
+impl FrecencyConfig {
+    /// The weight of an entry last selected `age_hours` ago.
+    pub fn recency_weight(&self, age_hours: u64) -> u64 {
+        self.decay
+            .iter()
+            .filter(|bucket| age_hours <= bucket.hours)
+            .min_by_key(|bucket| bucket.hours)
+            .map_or(self.base_weight, |bucket| bucket.weight)
+    }
+
+    /// The highest score an unpinned entry can have.
+    pub fn max_score(&self) -> u64 {
+        let max_weight = self
+            .decay
+            .iter()
+            .map(|bucket| bucket.weight)
+            .chain([self.base_weight])
+            .max()
+            .unwrap_or_default();
+        max_weight.saturating_mul(u64::from(self.max_count))
+    }
+}
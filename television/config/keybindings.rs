@@ -1,30 +1,40 @@
 use crate::{
     action::{Action, Actions},
-    event::{Key, convert_raw_event_to_key},
+    event::Key,
 };
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use rustc_hash::FxHashMap;
-use serde::Deserialize;
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::{Deserialize, de};
+use std::borrow::Cow;
+use std::fmt::Display;
 use std::ops::Deref;
 use std::ops::DerefMut;
 use std::str::FromStr;
 use tracing::debug;
 
 /// A hashmap of keyboard key bindings to actions.
-#[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
-pub struct Keybindings(pub FxHashMap<Key, Actions>);
+///
+/// Bindings made of several keys pressed one after the other (e.g. `"g g"`
+/// or `"ctrl-x ctrl-e"`) are stored separately in `sequences`. Dereferencing
+/// a `Keybindings` gives access to the single key bindings.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Keybindings {
+    pub keys: FxHashMap<Key, Actions>,
+    pub sequences: FxHashMap<KeySequence, Actions>,
+}
 
 impl Deref for Keybindings {
     type Target = FxHashMap<Key, Actions>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.keys
     }
 }
 
 impl DerefMut for Keybindings {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.keys
     }
 }
 
@@ -34,18 +44,148 @@ impl From<Vec<(Key, Action)>> for Keybindings {
         for (key, action) in bindings {
             map.insert(key, action.into());
         }
-        Keybindings(map)
+        Keybindings {
+            keys: map,
+            sequences: FxHashMap::default(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Keybindings {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let raw = FxHashMap::<String, Actions>::deserialize(deserializer)?;
+        let mut keybindings = Keybindings::new();
+        for (raw_keys, actions) in raw {
+            let sequence =
+                KeySequence::from_str(&raw_keys).map_err(de::Error::custom)?;
+            keybindings.bind(sequence, actions);
+        }
+        Ok(keybindings)
+    }
+}
+
+impl JsonSchema for Keybindings {
+    fn schema_name() -> Cow<'static, str> {
+        "Keybindings".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "object",
+            "description": "Maps a key or a space separated key sequence (e.g. `\"g g\"`) to one or more actions",
+            "additionalProperties": generator.subschema_for::<Actions>(),
+        })
     }
 }
 
 impl Keybindings {
     pub fn new() -> Self {
-        Keybindings(FxHashMap::default())
+        Keybindings::default()
     }
 
     pub fn merge(self, new: &Keybindings) -> Keybindings {
         merge_keybindings(self, new)
     }
+
+    /// Bind a key sequence to the given actions.
+    ///
+    /// Sequences made of a single key are stored as regular key bindings.
+    pub fn bind(&mut self, sequence: KeySequence, actions: Actions) {
+        if let [key] = sequence.0.as_slice() {
+            self.keys.insert(*key, actions);
+        } else {
+            self.sequences.insert(sequence, actions);
+        }
+    }
+
+    /// Returns the bindings that shadow the start of a longer sequence.
+    ///
+    /// E.g. if both `g` and `"g g"` are bound, pressing `g` is ambiguous and
+    /// tv has to wait for the sequence timeout before running the action
+    /// bound to `g`. Each conflict is returned as a `(prefix, sequence)` pair.
+    pub fn prefix_conflicts(&self) -> Vec<(KeySequence, KeySequence)> {
+        let mut conflicts: Vec<_> = self
+            .sequences
+            .keys()
+            .flat_map(|sequence| {
+                (1..sequence.len())
+                    .map(|len| KeySequence(sequence[..len].to_vec()))
+                    .filter(|prefix| {
+                        if let [key] = prefix.0.as_slice() {
+                            self.keys.contains_key(key)
+                        } else {
+                            self.sequences.contains_key(prefix)
+                        }
+                    })
+                    .map(|prefix| (prefix, sequence.clone()))
+            })
+            .collect();
+        conflicts.sort();
+        conflicts
+    }
+}
+
+/// Describes a conflict found by [`Keybindings::prefix_conflicts`].
+pub fn describe_prefix_conflict(
+    prefix: &KeySequence,
+    sequence: &KeySequence,
+) -> String {
+    format!(
+        "`{prefix}` is bound and also starts `{sequence}`: it will only \
+         trigger after the key sequence timeout"
+    )
+}
+
+/// A series of keys that need to be pressed one after the other to trigger a
+/// binding.
+///
+/// In configuration files, keys are separated by whitespace:
+/// ```toml
+/// "g g" = "select_next_page"
+/// "ctrl-x ctrl-e" = "actions:edit"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct KeySequence(pub Vec<Key>);
+
+impl Deref for KeySequence {
+    type Target = [Key];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Key> for KeySequence {
+    fn from(key: Key) -> Self {
+        KeySequence(vec![key])
+    }
+}
+
+impl FromStr for KeySequence {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let trimmed = raw.trim();
+        // a lone space (`" "`) is a valid key on its own
+        if trimmed.is_empty() || !trimmed.contains(char::is_whitespace) {
+            return Ok(KeySequence(vec![Key::from_str(raw)?]));
+        }
+        trimmed
+            .split_whitespace()
+            .map(Key::from_str)
+            .collect::<Result<Vec<_>, _>>()
+            .map(KeySequence)
+    }
+}
+
+impl Display for KeySequence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let keys: Vec<String> = self.iter().map(ToString::to_string).collect();
+        write!(f, "{}", keys.join(" "))
+    }
 }
 
 /// Merges two binding collections, with new bindings taking precedence.
@@ -85,14 +225,17 @@ pub fn merge_keybindings(
     mut base: Keybindings,
     new: &Keybindings,
 ) -> Keybindings {
-    debug!("bindings before: {:?}", base.0);
+    debug!("bindings before: {:?}", base);
 
     // Merge new bindings - they take precedence over existing ones
-    for (key, actions) in &new.0 {
-        base.0.insert(*key, actions.clone());
+    for (key, actions) in &new.keys {
+        base.keys.insert(*key, actions.clone());
+    }
+    for (sequence, actions) in &new.sequences {
+        base.sequences.insert(sequence.clone(), actions.clone());
     }
 
-    debug!("bindings after: {:?}", base.0);
+    debug!("bindings after: {:?}", base);
 
     base
 }
@@ -119,6 +262,9 @@ pub fn merge_keybindings(
 /// - `cmd-` - Command key (macOS)
 /// - `super-` - Super key (Linux/Windows)
 ///
+/// Names are case-insensitive, except for a lone character without any
+/// modifier: `G` is the same key as `shift-g`.
+///
 /// # Examples
 ///
 /// ```rust
@@ -134,6 +280,12 @@ pub fn merge_keybindings(
 /// assert_eq!(event.modifiers, KeyModifiers::ALT);
 /// ```
 pub fn parse_key_event(raw: &str) -> anyhow::Result<KeyEvent, String> {
+    let mut chars = raw.chars();
+    if let (Some(c), None) = (chars.next(), chars.next())
+        && c.is_uppercase()
+    {
+        return Ok(KeyEvent::new(KeyCode::Char(c), KeyModifiers::SHIFT));
+    }
     let raw_lower = raw.to_ascii_lowercase();
     let (remaining, modifiers) = extract_modifiers(&raw_lower);
     parse_key_code_with_modifiers(remaining, modifiers)
@@ -240,6 +392,7 @@ fn parse_key_code_with_modifiers(
                 ("pagedown", KeyCode::PageDown),
                 ("backspace", KeyCode::Backspace),
                 ("delete", KeyCode::Delete),
+                ("del", KeyCode::Delete),
                 ("insert", KeyCode::Insert),
                 ("f1", KeyCode::F(1)),
                 ("f2", KeyCode::F(2)),
@@ -398,7 +551,7 @@ impl FromStr for Key {
         };
 
         let key_event = parse_key_event(raw)?;
-        Ok(convert_raw_event_to_key(key_event))
+        Ok(Key::from_event(key_event))
     }
 }
 
@@ -490,6 +643,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_uppercase_char_implies_shift() {
+        assert_eq!(
+            parse_key_event("G").unwrap(),
+            KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)
+        );
+        assert_eq!(Key::from_str("G").unwrap(), Key::Char('G'));
+        assert_eq!(Key::from_str("shift-g").unwrap(), Key::Char('G'));
+        // modifier combinations stay case-insensitive
+        assert_eq!(Key::from_str("CTRL-P").unwrap(), Key::Ctrl('p'));
+    }
+
+    #[test]
+    fn test_parse_generalized_keys() {
+        use crate::event::{KeyCode as TvKeyCode, Modifiers};
+
+        assert_eq!(
+            Key::from_str("ctrl-shift-p").unwrap(),
+            Key::Modified(
+                Modifiers::CTRL.union(Modifiers::SHIFT),
+                TvKeyCode::Char('p')
+            )
+        );
+        assert_eq!(
+            Key::from_str("shift-enter").unwrap(),
+            Key::Modified(Modifiers::SHIFT, TvKeyCode::Enter)
+        );
+        assert_eq!(
+            Key::from_str("ctrl-tab").unwrap(),
+            Key::Modified(Modifiers::CTRL, TvKeyCode::Tab)
+        );
+        assert_eq!(Key::from_str("shift-tab").unwrap(), Key::BackTab);
+        assert_ne!(Key::from_str("ctrl-i").unwrap(), Key::Tab);
+        // the same combination written in a different order
+        assert_eq!(
+            Key::from_str("alt-ctrl-x").unwrap(),
+            Key::from_str("ctrl-alt-x").unwrap()
+        );
+    }
+
     #[test]
     fn test_invalid_keys() {
         assert!(parse_key_event("invalid-key").is_err());
@@ -577,21 +770,21 @@ mod tests {
         let merged = merge_keybindings(base, &new);
 
         // Should contain both base and custom keybindings
-        assert!(merged.0.contains_key(&Key::Esc));
-        assert_eq!(merged.0.get(&Key::Esc), Some(&Action::Quit.into()));
-        assert!(merged.0.contains_key(&Key::Down));
+        assert!(merged.keys.contains_key(&Key::Esc));
+        assert_eq!(merged.keys.get(&Key::Esc), Some(&Action::Quit.into()));
+        assert!(merged.keys.contains_key(&Key::Down));
         assert_eq!(
-            merged.0.get(&Key::Down),
+            merged.keys.get(&Key::Down),
             Some(&Action::SelectNextEntry.into())
         );
-        assert!(merged.0.contains_key(&Key::Ctrl('j')));
+        assert!(merged.keys.contains_key(&Key::Ctrl('j')));
         assert_eq!(
-            merged.0.get(&Key::Ctrl('j')),
+            merged.keys.get(&Key::Ctrl('j')),
             Some(&Action::SelectNextEntry.into())
         );
-        assert!(merged.0.contains_key(&Key::PageDown));
+        assert!(merged.keys.contains_key(&Key::PageDown));
         assert_eq!(
-            merged.0.get(&Key::PageDown),
+            merged.keys.get(&Key::PageDown),
             Some(&Action::SelectNextPage.into())
         );
     }
@@ -608,15 +801,18 @@ mod tests {
         .unwrap();
 
         // Normal action binding should work
-        assert_eq!(keybindings.0.get(&Key::Esc), Some(&Action::Quit.into()));
         assert_eq!(
-            keybindings.0.get(&Key::Down),
+            keybindings.keys.get(&Key::Esc),
+            Some(&Action::Quit.into())
+        );
+        assert_eq!(
+            keybindings.keys.get(&Key::Down),
             Some(&Action::SelectNextEntry.into())
         );
 
         // false should bind to NoOp (unbinding)
         assert_eq!(
-            keybindings.0.get(&Key::Ctrl('c')),
+            keybindings.keys.get(&Key::Ctrl('c')),
             Some(&Action::NoOp.into())
         );
     }
@@ -633,11 +829,14 @@ mod tests {
         .unwrap();
 
         // Single action should work
-        assert_eq!(keybindings.0.get(&Key::Esc), Some(&Action::Quit.into()));
+        assert_eq!(
+            keybindings.keys.get(&Key::Esc),
+            Some(&Action::Quit.into())
+        );
 
         // Multiple actions should work
         assert_eq!(
-            keybindings.0.get(&Key::Ctrl('s')),
+            keybindings.keys.get(&Key::Ctrl('s')),
             Some(&Actions::multiple(vec![
                 Action::ReloadSource,
                 Action::CopyEntryToClipboard
@@ -646,7 +845,7 @@ mod tests {
 
         // Three actions should work
         assert_eq!(
-            keybindings.0.get(&Key::F(1)),
+            keybindings.keys.get(&Key::F(1)),
             Some(&Actions::multiple(vec![
                 Action::ToggleHelp,
                 Action::TogglePreview,
@@ -671,13 +870,16 @@ mod tests {
             ]),
         );
         custom_bindings.insert(Key::Esc, Action::NoOp.into()); // Override
-        let custom_keybindings = Keybindings(custom_bindings);
+        let custom_keybindings = Keybindings {
+            keys: custom_bindings,
+            sequences: FxHashMap::default(),
+        };
 
         let merged = merge_keybindings(base_keybindings, &custom_keybindings);
 
         // Custom multiple actions should be present
         assert_eq!(
-            merged.0.get(&Key::Ctrl('s')),
+            merged.keys.get(&Key::Ctrl('s')),
             Some(&Actions::multiple(vec![
                 Action::ReloadSource,
                 Action::CopyEntryToClipboard
@@ -685,11 +887,11 @@ mod tests {
         );
 
         // Override should work
-        assert_eq!(merged.0.get(&Key::Esc), Some(&Action::NoOp.into()));
+        assert_eq!(merged.keys.get(&Key::Esc), Some(&Action::NoOp.into()));
 
         // Original binding should be preserved
         assert_eq!(
-            merged.0.get(&Key::Enter),
+            merged.keys.get(&Key::Enter),
             Some(&Action::ConfirmSelection.into())
         );
     }
@@ -715,26 +917,26 @@ mod tests {
         )
         .unwrap();
 
-        assert_eq!(keybindings.0.len(), 6);
+        assert_eq!(keybindings.keys.len(), 6);
 
         // Verify all binding types work correctly
         assert_eq!(
-            keybindings.0.get(&Key::Esc),
+            keybindings.keys.get(&Key::Esc),
             Some(&Actions::single(Action::Quit))
         );
         assert_eq!(
-            keybindings.0.get(&Key::Enter),
+            keybindings.keys.get(&Key::Enter),
             Some(&Action::ConfirmSelection.into())
         );
         assert_eq!(
-            keybindings.0.get(&Key::Ctrl('s')),
+            keybindings.keys.get(&Key::Ctrl('s')),
             Some(&Actions::multiple(vec![
                 Action::ReloadSource,
                 Action::CopyEntryToClipboard
             ]))
         );
         assert_eq!(
-            keybindings.0.get(&Key::F(1)),
+            keybindings.keys.get(&Key::F(1)),
             Some(&Actions::multiple(vec![
                 Action::ToggleHelp,
                 Action::TogglePreview,
@@ -742,12 +944,72 @@ mod tests {
             ]))
         );
         assert_eq!(
-            keybindings.0.get(&Key::Ctrl('c')),
+            keybindings.keys.get(&Key::Ctrl('c')),
             Some(&Actions::single(Action::NoOp))
         );
         assert_eq!(
-            keybindings.0.get(&Key::Tab),
+            keybindings.keys.get(&Key::Tab),
             Some(&Actions::multiple(vec![Action::ToggleSelectionDown]))
         );
     }
+
+    #[test]
+    fn test_deserialize_key_sequences() {
+        let keybindings: Keybindings = toml::from_str(
+            r#"
+                "g" = "select_next_page"
+                "g g" = "select_prev_page"
+                "ctrl-x  ctrl-e" = "actions:edit"
+                " " = "toggle_selection_down"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            keybindings.keys.get(&Key::Char('g')),
+            Some(&Action::SelectNextPage.into())
+        );
+        assert_eq!(
+            keybindings.keys.get(&Key::Char(' ')),
+            Some(&Action::ToggleSelectionDown.into())
+        );
+        assert_eq!(
+            keybindings
+                .sequences
+                .get(&KeySequence(vec![Key::Char('g'), Key::Char('g')])),
+            Some(&Action::SelectPrevPage.into())
+        );
+        let edit = KeySequence(vec![Key::Ctrl('x'), Key::Ctrl('e')]);
+        assert_eq!(edit.to_string(), "ctrl-x ctrl-e");
+        assert_eq!(
+            keybindings.sequences.get(&edit),
+            Some(&Action::ExternalAction("actions:edit".to_string()).into())
+        );
+
+        assert!(
+            toml::from_str::<Keybindings>(r#""g nope" = "quit""#).is_err()
+        );
+    }
+
+    #[test]
+    fn test_prefix_conflicts() {
+        let keybindings: Keybindings = toml::from_str(
+            r#"
+                "g" = "select_next_page"
+                "g g" = "select_prev_page"
+                "g g x" = "quit"
+                "ctrl-x ctrl-e" = "reload_source"
+            "#,
+        )
+        .unwrap();
+
+        let g = KeySequence::from(Key::Char('g'));
+        let gg = KeySequence(vec![Key::Char('g'), Key::Char('g')]);
+        let ggx =
+            KeySequence(vec![Key::Char('g'), Key::Char('g'), Key::Char('x')]);
+        assert_eq!(
+            keybindings.prefix_conflicts(),
+            vec![(g.clone(), gg.clone()), (g, ggx.clone()), (gg, ggx)]
+        );
+    }
 }
@@ -1,19 +1,31 @@
 use crate::{
     action::{Action, CUSTOM_ACTION_PREFIX},
-    channels::prototypes::{
-        ActionSpec, BinaryRequirement, ChannelPrototype, CommandSpec, Template,
+    channels::{
+        prototypes::{
+            ActionSpec, BinaryRequirement, ChannelPrototype, CommandSpec,
+            Template, UiSpec,
+        },
+        sort::{SortCriterion, SortField},
     },
     cli::{ChannelCli, GlobalCli, PostProcessedCli},
     config::{
-        Config, Keybindings, merge_keybindings,
+        Config, ExcludePatterns, FrecencyConfig, Keybindings,
+        actions::merge_global_actions,
+        merge_keybindings,
+        provenance::{AnnotatedConfig, Source},
         ui::{BorderType, Padding, ThemeOverrides},
     },
-    keymap::InputMap,
+    keymap::{InputMap, VimInputMaps},
+    matcher::config::{CaseSensitivity, MatchingMode},
     screen::layout::{InputPosition, Orientation},
+    television::VimMode,
     utils::shell::Shell,
 };
 use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use toml::{Table, Value};
+use tracing::warn;
 
 /// Represents the different layers of configuration that make up the final
 /// merged configuration used by the application.
@@ -29,6 +41,9 @@ pub struct ConfigLayers {
     channel_cli: ChannelCli,
     /// The global CLI configuration options that will persist across channels.
     global_cli: GlobalCli,
+    /// The settings overridden by environment variables, which apply on top
+    /// of both the base configuration and the channel's settings.
+    env_overrides: Table,
 }
 
 impl ConfigLayers {
@@ -42,9 +57,25 @@ impl ConfigLayers {
             channel,
             channel_cli: cli.channel,
             global_cli: cli.global,
+            env_overrides: Table::new(),
         }
     }
 
+    /// Sets the settings overridden by environment variables.
+    ///
+    /// The base configuration is expected to include them already, see
+    /// `Config::with_overrides`.
+    #[must_use]
+    pub fn with_env_overrides(mut self, env_overrides: Table) -> Self {
+        self.env_overrides = env_overrides;
+        self
+    }
+
+    /// The settings overridden by environment variables.
+    pub fn env_overrides(&self) -> &Table {
+        &self.env_overrides
+    }
+
     /// Update the current channel prototype and reset channel CLI options.
     pub fn update_channel(&mut self, channel: ChannelPrototype) {
         self.channel = channel;
@@ -52,14 +83,66 @@ impl ConfigLayers {
         self.channel_cli = ChannelCli::default();
     }
 
+    /// Replace the base configuration, e.g. after the config file changed.
+    pub fn update_base_config(&mut self, base_config: Config) {
+        self.base_config = base_config;
+    }
+
+    /// Replace the current channel prototype with a new definition of the
+    /// same channel, keeping the CLI options that apply to it.
+    pub fn reload_channel(&mut self, channel: ChannelPrototype) {
+        self.channel = channel;
+    }
+
     /// Merges the different configuration layers into a single `MergedConfig`.
     pub fn merge(&self) -> MergedConfig {
+        self.merge_with(FlagRecorder(None))
+    }
+
+    /// Records in `config` the settings overridden by command line flags, as
+    /// `merge` applies them.
+    pub fn annotate_flags(&self, config: &mut AnnotatedConfig) {
+        self.merge_with(FlagRecorder(Some(config)));
+    }
+
+    /// The channel's UI settings, with the ones also set by environment
+    /// variables overridden.
+    fn channel_ui(&self) -> Option<UiSpec> {
+        let ui = self.channel.ui.as_ref()?;
+        let Some(Value::Table(overrides)) = self.env_overrides.get("ui")
+        else {
+            return Some(ui.clone());
+        };
+        let mut overrides = overrides.clone();
+        // see `UiSpec::orientation`
+        if let Some(orientation) = overrides.remove("orientation") {
+            overrides.insert("layout".to_string(), orientation);
+        }
+        let patched = Table::try_from(ui).map_err(|e| e.to_string()).and_then(
+            |mut table| {
+                override_existing(&mut table, overrides);
+                UiSpec::deserialize(Value::Table(table))
+                    .map_err(|e| e.to_string())
+            },
+        );
+        match patched {
+            Ok(patched) => Some(patched),
+            Err(e) => {
+                warn!("Failed to apply environment overrides to channel: {e}");
+                Some(ui.clone())
+            }
+        }
+    }
+
+    fn merge_with(&self, mut flags: FlagRecorder<'_>) -> MergedConfig {
+        let channel_ui = self.channel_ui();
         // CLI-only fields
         let config_file = self.global_cli.config_file.clone();
+        let profile = self.global_cli.profile.clone();
+        let included_paths = self.base_config.included_paths.clone();
         let working_directory = self.global_cli.workdir.clone();
         let autocomplete_prompt = self.channel_cli.autocomplete_prompt.clone();
         let input = self.channel_cli.input.clone();
-        let exact_match = self.channel_cli.exact;
         let select_1 = self.channel_cli.select_1;
         let take_1 = self.channel_cli.take_1;
         let take_1_fast = self.channel_cli.take_1_fast;
@@ -71,9 +154,14 @@ impl ConfigLayers {
         let data_dir = self.base_config.application.data_dir.clone();
         let default_channel =
             self.base_config.application.default_channel.clone();
+        let key_sequence_timeout =
+            self.base_config.application.key_sequence_timeout;
+        let keyboard_enhancements =
+            self.base_config.application.keyboard_enhancements;
         let history_size = self.base_config.application.history_size;
         let frecency_max_entries =
             self.base_config.application.frecency_max_entries;
+        let frecency = self.base_config.frecency.clone();
         let theme = self.base_config.ui.theme.clone();
         let shell_integration_commands =
             self.base_config.shell_integration.commands.clone();
@@ -83,27 +171,68 @@ impl ConfigLayers {
         // channel only fields
         let channel_description = self.channel.metadata.description.clone();
         let channel_requirements = self.channel.metadata.requirements.clone();
-        let channel_actions = self.channel.actions.clone();
+        let mut channel_actions = self.channel.actions.clone();
+        merge_global_actions(
+            &mut channel_actions,
+            &self.base_config.actions,
+            &self.channel.metadata.name,
+        );
+        channel_actions
+            .values_mut()
+            .for_each(ActionSpec::init_requirements);
 
         // CLI > base config fields
-        let cable_dir = self
-            .global_cli
-            .cable_dir
-            .as_ref()
-            .unwrap_or(&self.base_config.application.cable_dir)
-            .clone();
-        let tick_rate = self
-            .global_cli
-            .tick_rate
+        let cable_dir = flags
+            .option(
+                &["cable_dir"],
+                "--cable-dir",
+                self.global_cli.cable_dir.clone(),
+            )
+            .unwrap_or_else(|| self.base_config.application.cable_dir.clone());
+        let tick_rate = flags
+            .option(&["tick_rate"], "--tick-rate", self.global_cli.tick_rate)
             .unwrap_or(self.base_config.application.tick_rate);
 
         // CLI > channel fields
-        let watch = self
-            .channel_cli
-            .watch_interval
+        let watch = flags
+            .option(&["watch"], "--watch", self.channel_cli.watch_interval)
             .unwrap_or(self.channel.watch);
         // Determine if sorting is disabled: --no-sort CLI flag OR channel config
-        let no_sort = self.channel_cli.no_sort || self.channel.source.no_sort;
+        let no_sort = flags.switch(
+            &["source", "no_sort"],
+            "--no-sort",
+            self.channel_cli.no_sort,
+            true,
+        ) || self.channel.source.no_sort;
+        // --tiebreak only replaces what comes after frecency and score
+        let sort = match &self.channel_cli.tiebreak {
+            Some(tiebreak) => flags.option(
+                &["source", "sort"],
+                "--tiebreak",
+                Some(
+                    [
+                        SortCriterion::field(SortField::Frecency),
+                        SortCriterion::field(SortField::Score),
+                    ]
+                    .into_iter()
+                    .chain(tiebreak.iter().cloned())
+                    .collect(),
+                ),
+            ),
+            None => self.channel.source.sort.clone(),
+        };
+        // --exact forces substring matching
+        let match_mode = if flags.switch(
+            &["source", "match_mode"],
+            "--exact",
+            self.channel_cli.exact,
+            MatchingMode::Substring,
+        ) {
+            MatchingMode::Substring
+        } else {
+            self.channel.source.match_mode
+        };
+        let case_sensitivity = self.channel.source.case_sensitivity;
         let channel_name = self
             .channel_cli
             .channel
@@ -115,64 +244,92 @@ impl ConfigLayers {
         let global_shell = self.base_config.application.shell;
 
         // Build source command and apply global shell if no channel-specific shell
-        let mut channel_source_command =
-            if let Some(template) = &self.channel_cli.source_command {
-                CommandSpec::from(template.clone())
-            } else {
-                self.channel.source.command.clone()
-            };
+        let mut channel_source_command = flags
+            .option(
+                &["source", "command"],
+                "--source-command",
+                self.channel_cli.source_command.clone(),
+            )
+            .map_or_else(
+                || self.channel.source.command.clone(),
+                CommandSpec::from,
+            );
         if channel_source_command.shell.is_none() {
             channel_source_command.shell = global_shell;
         }
 
-        let channel_source_entry_delimiter = self
-            .channel_cli
-            .source_entry_delimiter
+        let channel_source_entry_delimiter = flags
+            .option(
+                &["source", "entry_delimiter"],
+                "--source-entry-delimiter",
+                self.channel_cli.source_entry_delimiter,
+            )
             .or(self.channel.source.entry_delimiter);
-        let channel_source_ansi =
-            self.channel_cli.ansi || self.channel.source.ansi;
+        let channel_source_ansi = flags.switch(
+            &["source", "ansi"],
+            "--ansi",
+            self.channel_cli.ansi,
+            true,
+        ) || self.channel.source.ansi;
         // Per-channel frecency setting (defaults to true, can be disabled per-channel)
         let channel_frecency = self.channel.source.frecency;
-        let channel_source_display = self
-            .channel_cli
-            .source_display
-            .as_ref()
-            .or(self.channel.source.display.as_ref())
-            .cloned();
-        let channel_source_output = self
-            .channel_cli
-            .source_output
-            .as_ref()
-            .or(self.channel.source.output.as_ref())
-            .cloned();
+        let channel_frecency_key = self.channel.source.frecency_key.clone();
+        let channel_frecency_namespace = self
+            .channel
+            .source
+            .frecency_namespace
+            .clone()
+            .unwrap_or_else(|| self.channel.metadata.name.clone());
+        let channel_source_display = flags
+            .option(
+                &["source", "display"],
+                "--source-display",
+                self.channel_cli.source_display.clone(),
+            )
+            .or_else(|| self.channel.source.display.clone());
+        let channel_source_output = flags
+            .option(
+                &["source", "output"],
+                "--source-output",
+                self.channel_cli.source_output.clone(),
+            )
+            .or_else(|| self.channel.source.output.clone());
 
         // Build preview command and apply global shell if no channel-specific shell
-        let mut channel_preview_command = self
-            .channel_cli
-            .preview_command
-            .as_ref()
-            .map(|t| CommandSpec::from(t.clone()))
+        let mut channel_preview_command = flags
+            .option(
+                &["preview", "command"],
+                "--preview-command",
+                self.channel_cli.preview_command.clone(),
+            )
+            .map(CommandSpec::from)
             .or(self.channel.preview.as_ref().map(|p| p.command.clone()));
         if let Some(ref mut cmd) = channel_preview_command
             && cmd.shell.is_none()
         {
             cmd.shell = global_shell;
         }
-        let channel_preview_offset =
-            self.channel_cli.preview_offset.clone().or(
-                if let Some(preview) = &self.channel.preview {
-                    preview.offset.clone()
-                } else {
-                    None
-                },
-            );
-        let channel_preview_cached = self.channel_cli.cache_preview
-            || self.channel.preview.as_ref().is_some_and(|p| p.cached);
+        let channel_preview_offset = flags
+            .option(
+                &["preview", "offset"],
+                "--preview-offset",
+                self.channel_cli.preview_offset.clone(),
+            )
+            .or(if let Some(preview) = &self.channel.preview {
+                preview.offset.clone()
+            } else {
+                None
+            });
+        let channel_preview_cached =
+            flags.switch(
+                &["preview", "cached"],
+                "--cache-preview",
+                self.channel_cli.cache_preview,
+                true,
+            ) || self.channel.preview.as_ref().is_some_and(|p| p.cached);
 
         // Channel > base config fields
-        let remote_show_channel_descriptions = self
-            .channel
-            .ui
+        let remote_show_channel_descriptions = channel_ui
             .as_ref()
             .and_then(|ui| ui.remote_control.as_ref())
             .is_none_or(|rc| {
@@ -183,154 +340,183 @@ impl ConfigLayers {
                         .remote_control
                         .show_channel_descriptions
             });
-        let remote_sort_alphabetically = self
-            .channel
-            .ui
+        let remote_sort_alphabetically = channel_ui
             .as_ref()
             .and_then(|ui| ui.remote_control.as_ref())
             .is_none_or(|rc| {
                 rc.sort_alphabetically
                     && self.base_config.ui.remote_control.sort_alphabetically
             });
-        let theme_overrides = self
-            .channel
-            .ui
+        let theme_overrides = channel_ui
             .as_ref()
             .map(|ui| ui.theme_overrides.clone())
             .unwrap_or_default()
             .merge(self.base_config.ui.theme_overrides.clone());
 
-        let help_panel_show_categories = self
-            .channel
-            .ui
+        let help_panel_show_categories = channel_ui
             .as_ref()
             .and_then(|ui| ui.help_panel.as_ref())
             .is_none_or(|hp| hp.show_categories)
             && self.base_config.ui.help_panel.show_categories;
-        let status_bar_separator_open = self
-            .channel
-            .ui
+        let status_bar_separator_open = channel_ui
             .as_ref()
             .and_then(|ui| ui.status_bar.as_ref())
             .map_or_else(
                 || self.base_config.ui.status_bar.separator_open.clone(),
                 |sb| sb.separator_open.clone(),
             );
-        let status_bar_separator_close = self
-            .channel
-            .ui
+        let status_bar_separator_close = channel_ui
             .as_ref()
             .and_then(|ui| ui.status_bar.as_ref())
             .map_or_else(
                 || self.base_config.ui.status_bar.separator_close.clone(),
                 |sb| sb.separator_close.clone(),
             );
-        let input_bar_position = self.channel_cli.input_position.unwrap_or(
-            self.channel
-                .ui
-                .as_ref()
-                .and_then(|ui| ui.input_bar.as_ref())
-                .map_or(self.base_config.ui.input_bar.position, |ib| {
-                    ib.position
-                }),
-        );
+        let input_bar_position = flags
+            .option(
+                &["ui", "input_bar", "position"],
+                "--input-position",
+                self.channel_cli.input_position,
+            )
+            .unwrap_or(
+                channel_ui
+                    .as_ref()
+                    .and_then(|ui| ui.input_bar.as_ref())
+                    .map_or(self.base_config.ui.input_bar.position, |ib| {
+                        ib.position
+                    }),
+            );
 
         // CLI > channel > base config fields
-        let ui_scale = self.channel_cli.ui_scale.unwrap_or(
-            self.channel
-                .ui
-                .as_ref()
-                .and_then(|ui| ui.ui_scale)
-                .unwrap_or(self.base_config.ui.ui_scale),
-        );
-        let layout = self.channel_cli.layout.unwrap_or(
-            self.channel
-                .ui
-                .as_ref()
-                .and_then(|ui| ui.orientation)
-                .unwrap_or(self.base_config.ui.orientation),
-        );
-        let input_bar_header = self
-            .channel_cli
-            .input_header
-            .clone()
+        let ui_scale = flags
+            .option(
+                &["ui", "ui_scale"],
+                "--ui-scale",
+                self.channel_cli.ui_scale,
+            )
+            .unwrap_or(
+                channel_ui
+                    .as_ref()
+                    .and_then(|ui| ui.ui_scale)
+                    .unwrap_or(self.base_config.ui.ui_scale),
+            );
+        let layout = flags
+            .option(
+                &["ui", "orientation"],
+                "--layout",
+                self.channel_cli.layout,
+            )
+            .unwrap_or(
+                channel_ui
+                    .as_ref()
+                    .and_then(|ui| ui.orientation)
+                    .unwrap_or(self.base_config.ui.orientation),
+            );
+        let input_bar_header = flags
+            .option(
+                &["ui", "input_bar", "header"],
+                "--input-header",
+                self.channel_cli.input_header.clone(),
+            )
             .or_else(|| {
-                self.channel.ui.as_ref()?.input_bar.as_ref()?.header.clone()
+                channel_ui.as_ref()?.input_bar.as_ref()?.header.clone()
             })
             .or_else(|| self.base_config.ui.input_bar.header.clone());
-        let input_bar_prompt = self
-            .channel_cli
-            .input_prompt
-            .clone()
+        let input_bar_prompt = flags
+            .option(
+                &["ui", "input_bar", "prompt"],
+                "--input-prompt",
+                self.channel_cli.input_prompt.clone(),
+            )
             .or_else(|| {
-                self.channel.ui.as_ref()?.input_bar.as_ref()?.prompt.clone()
+                channel_ui.as_ref()?.input_bar.as_ref()?.prompt.clone()
             })
             .or_else(|| self.base_config.ui.input_bar.prompt.clone());
-        let input_bar_border_type = self
-            .channel_cli
-            .input_border
+        let input_bar_border_type = flags
+            .option(
+                &["ui", "input_bar", "border_type"],
+                "--input-border",
+                self.channel_cli.input_border,
+            )
             .or_else(|| {
-                Some(self.channel.ui.as_ref()?.input_bar.as_ref()?.border_type)
+                Some(channel_ui.as_ref()?.input_bar.as_ref()?.border_type)
             })
             .unwrap_or(self.base_config.ui.input_bar.border_type);
-        let input_bar_padding = self
-            .channel_cli
-            .input_padding
-            .or_else(|| {
-                Some(self.channel.ui.as_ref()?.input_bar.as_ref()?.padding)
-            })
+        let input_bar_padding = flags
+            .option(
+                &["ui", "input_bar", "padding"],
+                "--input-padding",
+                self.channel_cli.input_padding,
+            )
+            .or_else(|| Some(channel_ui.as_ref()?.input_bar.as_ref()?.padding))
             .unwrap_or(self.base_config.ui.input_bar.padding);
         let status_bar_disabled = self.global_cli.no_status_bar;
-        let status_bar_hidden = if status_bar_disabled {
+        let status_bar_hidden_path = ["ui", "status_bar", "hidden"];
+        let status_bar_hidden = if flags.switch(
+            &status_bar_hidden_path,
+            "--no-status-bar",
+            status_bar_disabled,
+            true,
+        ) {
             true // --no-status-bar always wins
-        } else if self.channel_cli.show_status_bar {
+        } else if flags.switch(
+            &status_bar_hidden_path,
+            "--show-status-bar",
+            self.channel_cli.show_status_bar,
+            false,
+        ) {
             false // --show-status-bar forces visible
         } else {
-            self.channel_cli.hide_status_bar
-                || self
-                    .channel
-                    .ui
-                    .as_ref()
-                    .and_then(|ui| ui.status_bar.as_ref())
-                    .is_some_and(|sb| sb.hidden)
+            flags.switch(
+                &status_bar_hidden_path,
+                "--hide-status-bar",
+                self.channel_cli.hide_status_bar,
+                true,
+            ) || channel_ui
+                .as_ref()
+                .and_then(|ui| ui.status_bar.as_ref())
+                .is_some_and(|sb| sb.hidden)
                 || self.base_config.ui.status_bar.hidden
         };
-        let results_panel_border_type = self
-            .channel_cli
-            .results_border
+        let results_panel_border_type = flags
+            .option(
+                &["ui", "results_panel", "border_type"],
+                "--results-border",
+                self.channel_cli.results_border,
+            )
             .or_else(|| {
-                Some(
-                    self.channel
-                        .ui
-                        .as_ref()?
-                        .results_panel
-                        .as_ref()?
-                        .border_type,
-                )
+                Some(channel_ui.as_ref()?.results_panel.as_ref()?.border_type)
             })
             .unwrap_or(self.base_config.ui.results_panel.border_type);
-        let results_panel_padding = self
-            .channel_cli
-            .results_padding
+        let results_panel_padding = flags
+            .option(
+                &["ui", "results_panel", "padding"],
+                "--results-padding",
+                self.channel_cli.results_padding,
+            )
             .or_else(|| {
-                Some(self.channel.ui.as_ref()?.results_panel.as_ref()?.padding)
+                Some(channel_ui.as_ref()?.results_panel.as_ref()?.padding)
             })
             .unwrap_or(self.base_config.ui.results_panel.padding);
-        let preview_panel_size = self
-            .channel_cli
-            .preview_size
+        let preview_panel_size = flags
+            .option(
+                &["ui", "preview_panel", "size"],
+                "--preview-size",
+                self.channel_cli.preview_size,
+            )
             .or_else(|| {
-                Some(self.channel.ui.as_ref()?.preview_panel.as_ref()?.size)
+                Some(channel_ui.as_ref()?.preview_panel.as_ref()?.size)
             })
             .unwrap_or(self.base_config.ui.preview_panel.size);
-        let preview_panel_header = self
-            .channel_cli
-            .preview_header
-            .clone()
+        let preview_panel_header = flags
+            .option(
+                &["ui", "preview_panel", "header"],
+                "--preview-header",
+                self.channel_cli.preview_header.clone(),
+            )
             .or_else(|| {
                 Some(
-                    self.channel
-                        .ui
+                    channel_ui
                         .as_ref()?
                         .preview_panel
                         .as_ref()?
@@ -340,56 +526,58 @@ impl ConfigLayers {
                 )
             })
             .or_else(|| self.base_config.ui.preview_panel.header.clone());
-        let preview_panel_footer = self
-            .channel_cli
-            .preview_footer
-            .clone()
+        let preview_panel_footer = flags
+            .option(
+                &["ui", "preview_panel", "footer"],
+                "--preview-footer",
+                self.channel_cli.preview_footer.clone(),
+            )
             .or_else(|| {
-                self.channel
-                    .ui
-                    .as_ref()?
-                    .preview_panel
-                    .as_ref()?
-                    .footer
-                    .clone()
+                channel_ui.as_ref()?.preview_panel.as_ref()?.footer.clone()
             })
             .or_else(|| self.base_config.ui.preview_panel.footer.clone());
-        let preview_panel_scrollbar = !self.channel_cli.hide_preview_scrollbar
-            && self
-                .channel
-                .ui
-                .as_ref()
-                .and_then(|ui| ui.preview_panel.as_ref())
-                .map_or(self.base_config.ui.preview_panel.scrollbar, |pp| {
-                    pp.scrollbar
-                });
-        let preview_panel_border_type = self
-            .channel_cli
-            .preview_border
+        let preview_panel_scrollbar = !flags.switch(
+            &["ui", "preview_panel", "scrollbar"],
+            "--hide-preview-scrollbar",
+            self.channel_cli.hide_preview_scrollbar,
+            false,
+        ) && channel_ui
+            .as_ref()
+            .and_then(|ui| ui.preview_panel.as_ref())
+            .map_or(self.base_config.ui.preview_panel.scrollbar, |pp| {
+                pp.scrollbar
+            });
+        let preview_panel_border_type = flags
+            .option(
+                &["ui", "preview_panel", "border_type"],
+                "--preview-border",
+                self.channel_cli.preview_border,
+            )
             .or_else(|| {
-                Some(
-                    self.channel
-                        .ui
-                        .as_ref()?
-                        .preview_panel
-                        .as_ref()?
-                        .border_type,
-                )
+                Some(channel_ui.as_ref()?.preview_panel.as_ref()?.border_type)
             })
             .unwrap_or(self.base_config.ui.preview_panel.border_type);
-        let preview_panel_padding = self
-            .channel_cli
-            .preview_padding
+        let preview_panel_padding = flags
+            .option(
+                &["ui", "preview_panel", "padding"],
+                "--preview-padding",
+                self.channel_cli.preview_padding,
+            )
             .or_else(|| {
-                Some(self.channel.ui.as_ref()?.preview_panel.as_ref()?.padding)
+                Some(channel_ui.as_ref()?.preview_panel.as_ref()?.padding)
             })
             .unwrap_or(self.base_config.ui.preview_panel.padding);
 
         // If the CLI sets word-wrap to true, we respect that
-        let preview_panel_word_wrap = if self.channel_cli.preview_word_wrap {
+        let preview_panel_word_wrap = if flags.switch(
+            &["ui", "preview_panel", "word_wrap"],
+            "--preview-word-wrap",
+            self.channel_cli.preview_word_wrap,
+            true,
+        ) {
             true
         // Otherwise, we check the channel UI config
-        } else if let Some(ui) = self.channel.ui.as_ref()
+        } else if let Some(ui) = channel_ui.as_ref()
             && let Some(panel) = ui.preview_panel.as_ref()
         {
             panel.word_wrap
@@ -400,47 +588,92 @@ impl ConfigLayers {
 
         let preview_panel_disabled =
             self.global_cli.no_preview || self.channel_cli.no_preview;
-        let preview_panel_hidden = if preview_panel_disabled {
+        let preview_panel_hidden_path = ["ui", "preview_panel", "hidden"];
+        let preview_panel_hidden = if flags.switch(
+            &preview_panel_hidden_path,
+            "--no-preview",
+            preview_panel_disabled,
+            true,
+        ) {
             true // --no-preview always wins
-        } else if self.channel_cli.show_preview {
+        } else if flags.switch(
+            &preview_panel_hidden_path,
+            "--show-preview",
+            self.channel_cli.show_preview,
+            false,
+        ) {
             false // --show-preview forces visible
         } else {
-            self.channel_cli.hide_preview
-                || self
-                    .channel
-                    .ui
-                    .as_ref()
-                    .and_then(|ui| ui.preview_panel.as_ref())
-                    .is_some_and(|pp| pp.hidden)
+            flags.switch(
+                &preview_panel_hidden_path,
+                "--hide-preview",
+                self.channel_cli.hide_preview,
+                true,
+            ) || channel_ui
+                .as_ref()
+                .and_then(|ui| ui.preview_panel.as_ref())
+                .is_some_and(|pp| pp.hidden)
                 || self.base_config.ui.preview_panel.hidden
         };
-        let help_panel_hidden = if self.channel_cli.show_help_panel {
+        let help_panel_hidden_path = ["ui", "help_panel", "hidden"];
+        let help_panel_hidden = if flags.switch(
+            &help_panel_hidden_path,
+            "--show-help-panel",
+            self.channel_cli.show_help_panel,
+            false,
+        ) {
             false
         } else {
-            self.channel_cli.hide_help_panel
-                || self
-                    .channel
-                    .ui
-                    .as_ref()
-                    .and_then(|ui| ui.help_panel.as_ref())
-                    .is_some_and(|hp| hp.hidden)
+            flags.switch(
+                &help_panel_hidden_path,
+                "--hide-help-panel",
+                self.channel_cli.hide_help_panel,
+                true,
+            ) || channel_ui
+                .as_ref()
+                .and_then(|ui| ui.help_panel.as_ref())
+                .is_some_and(|hp| hp.hidden)
                 || self.base_config.ui.help_panel.hidden
         };
-        let help_panel_disabled = self.global_cli.no_help_panel
-            || self.channel.ui.as_ref().is_some_and(|ui| {
+        let help_panel_disabled =
+            flags.switch(
+                &["ui", "help_panel", "disabled"],
+                "--no-help-panel",
+                self.global_cli.no_help_panel,
+                true,
+            ) || channel_ui.as_ref().is_some_and(|ui| {
                 ui.help_panel.as_ref().is_some_and(|hp| hp.disabled)
-            })
-            || self.base_config.ui.help_panel.disabled;
-        let remote_disabled = self.global_cli.no_remote
-            || self
-                .channel
-                .ui
-                .as_ref()
-                .and_then(|ui| ui.remote_control.as_ref())
-                .is_some_and(|rc| rc.disabled);
-        let global_history = self.global_cli.global_history
-            || self.channel.history.global_mode.unwrap_or_default()
-            || self.base_config.application.global_history;
+            }) || self.base_config.ui.help_panel.disabled;
+        let remote_disabled = flags.switch(
+            &["ui", "remote_control", "disabled"],
+            "--no-remote",
+            self.global_cli.no_remote,
+            true,
+        ) || channel_ui
+            .as_ref()
+            .and_then(|ui| ui.remote_control.as_ref())
+            .is_some_and(|rc| rc.disabled);
+        let global_history =
+            flags.switch(
+                &["global_history"],
+                "--global-history",
+                self.global_cli.global_history,
+                true,
+            ) || self.channel.history.global_mode.unwrap_or_default()
+                || self.base_config.application.global_history;
+        // --no-history keeps both the history and the frecency data as is
+        let incognito = flags.switch(
+            &["history", "enabled"],
+            "--no-history",
+            self.global_cli.no_history,
+            false,
+        );
+        if incognito {
+            flags.set(&["source", "frecency"], "--no-history", false);
+        }
+        let channel_history = self.channel.history.enabled.unwrap_or(true);
+        let history_exclude =
+            self.base_config.application.history_exclude.clone();
 
         // Do we have any channel-specific keybindings?
         let mut channel_keybindings = Keybindings::default();
@@ -448,13 +681,18 @@ impl ConfigLayers {
             channel_keybindings = channel_bindings.bindings.clone();
         }
         if let Some(cli_bindings) = &self.channel_cli.keybindings {
+            flags.keybindings(cli_bindings);
             channel_keybindings =
                 merge_keybindings(channel_keybindings, cli_bindings);
         }
 
         // Validate that all external actions referenced in channel keybindings exist
         if let Some(channel_bindings) = &self.channel.keybindings {
-            for (_, actions) in channel_bindings.bindings.iter() {
+            for actions in channel_bindings
+                .bindings
+                .values()
+                .chain(channel_bindings.bindings.sequences.values())
+            {
                 for action in actions.as_slice() {
                     if let Action::ExternalAction(custom_with_prefix) = action
                         && !channel_actions.contains_key(
@@ -476,30 +714,45 @@ impl ConfigLayers {
             self.base_config.keybindings.clone(),
             channel_keybindings,
         );
+        let vim_input_maps = self
+            .base_config
+            .vim
+            .enabled
+            .then(|| VimInputMaps::new(&input_map, &self.base_config.vim));
 
         MergedConfig {
             // General
             data_dir,
             config_file,
+            profile,
+            included_paths,
             cable_dir,
             tick_rate,
+            key_sequence_timeout,
+            keyboard_enhancements,
             default_channel,
             history_size,
             global_history,
+            incognito,
+            history_exclude,
             frecency_max_entries,
+            frecency,
             working_directory,
             autocomplete_prompt,
             shell: global_shell,
             // matcher configuration
-            exact_match,
+            match_mode,
+            case_sensitivity,
             select_1,
             take_1,
             take_1_fast,
             input,
             no_sort,
+            sort,
 
             // Bindings
             input_map,
+            vim_input_maps,
 
             // UI
             ui_scale,
@@ -566,14 +819,104 @@ impl ConfigLayers {
             channel_preview_cached,
             // actions
             channel_actions,
+            // history
+            channel_history,
             // frecency
             channel_frecency,
+            channel_frecency_key,
+            channel_frecency_namespace,
             // stdin
             is_stdin: self.channel.metadata.name == "stdin",
         }
     }
 }
 
+/// Overrides the settings of `table` that `overrides` sets as well, leaving
+/// out the ones `table` doesn't set.
+fn override_existing(table: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (table.get_mut(&key), value) {
+            (Some(Value::Table(table)), Value::Table(overrides)) => {
+                override_existing(table, overrides);
+            }
+            (Some(existing), value) => *existing = value,
+            (None, _) => {}
+        }
+    }
+}
+
+/// Records the settings command line flags override while the configuration
+/// layers are merged, see `ConfigLayers::annotate_flags`.
+struct FlagRecorder<'a>(Option<&'a mut AnnotatedConfig>);
+
+impl FlagRecorder<'_> {
+    /// Records `value` as the setting at `path`, given by `flag`.
+    fn set(
+        &mut self,
+        path: &[&str],
+        flag: &'static str,
+        value: impl Serialize,
+    ) {
+        if let Some(config) = self.0.as_deref_mut()
+            && let Ok(value) = Value::try_from(value)
+        {
+            config.set(path, value, &Source::Cli(flag));
+        }
+    }
+
+    /// Returns `value`, recording it as the setting at `path` if `flag` was
+    /// given.
+    fn option<T: Serialize>(
+        &mut self,
+        path: &[&str],
+        flag: &'static str,
+        value: Option<T>,
+    ) -> Option<T> {
+        if let Some(value) = &value {
+            self.set(path, flag, value);
+        }
+        value
+    }
+
+    /// Returns whether `flag` was given, recording `value` as the setting at
+    /// `path` if so.
+    fn switch(
+        &mut self,
+        path: &[&str],
+        flag: &'static str,
+        given: bool,
+        value: impl Serialize,
+    ) -> bool {
+        if given {
+            self.set(path, flag, value);
+        }
+        given
+    }
+
+    /// Records the keybindings given with `--keybindings`.
+    fn keybindings(&mut self, keybindings: &Keybindings) {
+        let keys = keybindings.keys.iter().map(|(k, a)| (k.to_string(), a));
+        let sequences = keybindings
+            .sequences
+            .iter()
+            .map(|(s, a)| (s.to_string(), a));
+        for (key, actions) in keys.chain(sequences) {
+            // `--expect` keys aren't bound to configurable actions
+            let names: Vec<_> = actions
+                .as_slice()
+                .iter()
+                .filter_map(Action::config_name)
+                .collect();
+            let path = ["keybindings", key.as_str()];
+            match names.as_slice() {
+                [] => {}
+                [name] => self.set(&path, "--keybindings", name),
+                _ => self.set(&path, "--keybindings", &names),
+            }
+        }
+    }
+}
+
 /// The final merged configuration used by the application, combining
 /// settings from the base config, channel prototype, and CLI options.
 #[allow(clippy::struct_excessive_bools)]
@@ -582,27 +925,43 @@ pub struct MergedConfig {
     // General
     pub data_dir: PathBuf,
     pub config_file: Option<PathBuf>,
+    /// The profile of the configuration in use
+    pub profile: Option<String>,
+    /// Files included by the configuration file and directories searched
+    /// for them
+    pub included_paths: Vec<PathBuf>,
     pub cable_dir: PathBuf,
     pub tick_rate: u64,
+    pub key_sequence_timeout: u64,
+    pub keyboard_enhancements: bool,
     pub default_channel: String,
     pub history_size: usize,
     pub global_history: bool,
+    /// Whether nothing should be saved to the history or frecency data
+    pub incognito: bool,
+    /// Queries and entries never saved to the history or frecency data
+    pub history_exclude: ExcludePatterns,
     pub frecency_max_entries: usize,
+    pub frecency: FrecencyConfig,
     pub working_directory: Option<PathBuf>,
     pub autocomplete_prompt: Option<String>,
     /// Global shell for command execution (from base config).
     /// Already applied to `channel_source_command` and `channel_preview_command`.
     pub shell: Option<Shell>,
     // matcher configuration
-    pub exact_match: bool,
+    pub match_mode: MatchingMode,
+    pub case_sensitivity: CaseSensitivity,
     pub select_1: bool,
     pub take_1: bool,
     pub take_1_fast: bool,
     pub input: Option<String>,
     pub no_sort: bool,
+    pub sort: Option<Vec<SortCriterion>>,
 
     // Bindings
     pub input_map: InputMap,
+    /// Set when vim mode is enabled.
+    pub vim_input_maps: Option<VimInputMaps>,
 
     // UI
     pub ui_scale: u16,
@@ -667,8 +1026,84 @@ pub struct MergedConfig {
     pub channel_preview_offset: Option<Template>,
     pub channel_preview_cached: bool,
     pub channel_actions: FxHashMap<String, ActionSpec>,
+    /// Whether queries typed in the current channel are saved to the history
+    pub channel_history: bool,
     /// Whether frecency is enabled for the current channel (per-channel override)
     pub channel_frecency: bool,
+    /// Template mapping the channel's entries to their frecency key
+    pub channel_frecency_key: Option<Template>,
+    /// Name of the frecency table used by the current channel
+    pub channel_frecency_namespace: String,
     /// Whether the current channel reads from stdin directly
     pub is_stdin: bool,
 }
+
+impl MergedConfig {
+    /// The input map keys should be looked up in for the given vim mode.
+    ///
+    /// `None` stands for "vim mode disabled or not applicable".
+    pub fn input_map_for(&self, vim_mode: Option<VimMode>) -> &InputMap {
+        match (vim_mode, &self.vim_input_maps) {
+            (Some(VimMode::Insert), Some(maps)) => &maps.insert,
+            (Some(VimMode::Normal | VimMode::Visual), Some(maps)) => {
+                &maps.normal
+            }
+            _ => &self.input_map,
+        }
+    }
+
+    /// Merges channel shortcuts (or any other global keybindings) into the
+    /// input maps.
+    pub fn merge_global_shortcuts(&mut self, shortcuts: &Keybindings) {
+        self.input_map.merge_globals_with(shortcuts);
+        if let Some(vim) = &mut self.vim_input_maps {
+            vim.normal.merge_globals_with(shortcuts);
+            vim.insert.merge_globals_with(shortcuts);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_overrides_apply_over_channel_ui() {
+        let channel = toml::from_str::<ChannelPrototype>(
+            r#"
+            [metadata]
+            name = "test"
+
+            [source]
+            command = "echo 1"
+
+            [ui]
+            ui_scale = 80
+            layout = "portrait"
+
+            [ui.preview_panel]
+            size = 40
+            "#,
+        )
+        .unwrap();
+        let env_overrides = toml::from_str(
+            r#"
+            [ui]
+            ui_scale = 60
+            orientation = "landscape"
+            "#,
+        )
+        .unwrap();
+        let merged = ConfigLayers::new(
+            Config::default(),
+            channel,
+            PostProcessedCli::default(),
+        )
+        .with_env_overrides(env_overrides)
+        .merge();
+
+        assert_eq!(merged.ui_scale, 60);
+        assert_eq!(merged.layout, Orientation::Landscape);
+        assert_eq!(merged.preview_panel_size, 40);
+    }
+}
@@ -2,8 +2,11 @@ use crate::{
     cable::CABLE_DIR_NAME, channels::prototypes::DEFAULT_PROTOTYPE_NAME,
     history::DEFAULT_HISTORY_SIZE, utils::shell::Shell,
 };
+use actions::GlobalActionSpec;
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
+use rustc_hash::FxHashMap;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use shell_integration::ShellIntegrationConfig;
 use std::{
@@ -11,23 +14,37 @@ use std::{
     hash::Hash,
     path::{Path, PathBuf},
 };
+use toml::Table;
 use tracing::{debug, warn};
+use user_config::{UserConfig, merge_tables};
 
+pub use exclude::ExcludePatterns;
+pub use frecency::FrecencyConfig;
 pub use keybindings::{Keybindings, merge_keybindings};
 pub use themes::Theme;
 pub use ui::UiConfig;
+pub use vim::VimConfig;
 
 mod themes;
 
+pub mod actions;
+pub mod env_overrides;
+pub mod exclude;
+pub mod frecency;
 pub mod keybindings;
 pub mod layers;
+pub mod provenance;
 pub mod shell_integration;
 pub mod ui;
+pub mod user_config;
+pub mod vim;
+pub mod watcher;
 
-const DEFAULT_CONFIG: &str = include_str!("../../.config/config.toml");
+/// The default configuration file, with comments describing each setting.
+pub const DEFAULT_CONFIG: &str = include_str!("../../.config/config.toml");
 
 #[allow(dead_code, clippy::module_name_repetitions)]
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct AppConfig {
     #[serde(default = "get_data_dir")]
@@ -36,6 +53,15 @@ pub struct AppConfig {
     pub cable_dir: PathBuf,
     #[serde(default = "default_tick_rate")]
     pub tick_rate: u64,
+    /// How long (in milliseconds) to wait for the next key of a multi-key
+    /// binding before handling the keys pressed so far on their own
+    #[serde(default = "default_key_sequence_timeout")]
+    pub key_sequence_timeout: u64,
+    /// Whether to enable the kitty keyboard protocol on terminals that
+    /// support it, which makes combinations such as `ctrl-shift-p`,
+    /// `shift-enter` or `ctrl-i` (as opposed to `tab`) distinguishable
+    #[serde(default)]
+    pub keyboard_enhancements: bool,
     /// The default channel to use when no channel is specified
     #[serde(default = "default_channel")]
     pub default_channel: String,
@@ -45,6 +71,10 @@ pub struct AppConfig {
     /// Whether to use global history (all channels) or channel-specific history (default)
     #[serde(default = "default_global_history")]
     pub global_history: bool,
+    /// Queries and entries matching any of these patterns are never saved to
+    /// the history or the frecency data
+    #[serde(default)]
+    pub history_exclude: ExcludePatterns,
     /// Maximum number of frecency entries to keep per channel
     #[serde(default = "default_frecency_max_entries")]
     pub frecency_max_entries: usize,
@@ -61,15 +91,24 @@ impl Default for AppConfig {
             data_dir: get_data_dir(),
             cable_dir: default_cable_dir(),
             tick_rate: default_tick_rate(),
+            key_sequence_timeout: default_key_sequence_timeout(),
+            keyboard_enhancements: false,
             default_channel: default_channel(),
             history_size: default_history_size(),
             global_history: default_global_history(),
+            history_exclude: ExcludePatterns::default(),
             frecency_max_entries: default_frecency_max_entries(),
             shell: None,
         }
     }
 }
 
+const DEFAULT_KEY_SEQUENCE_TIMEOUT: u64 = 1000;
+
+fn default_key_sequence_timeout() -> u64 {
+    DEFAULT_KEY_SEQUENCE_TIMEOUT
+}
+
 fn default_channel() -> String {
     DEFAULT_PROTOTYPE_NAME.to_string()
 }
@@ -92,15 +131,18 @@ impl Hash for AppConfig {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.data_dir.hash(state);
         self.tick_rate.hash(state);
+        self.key_sequence_timeout.hash(state);
+        self.keyboard_enhancements.hash(state);
         self.history_size.hash(state);
         self.global_history.hash(state);
+        self.history_exclude.hash(state);
         self.frecency_max_entries.hash(state);
         self.shell.hash(state);
     }
 }
 
 #[allow(dead_code)]
-#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, JsonSchema)]
 pub struct Config {
     /// General application configuration
     #[allow(clippy::struct_field_names)]
@@ -115,10 +157,25 @@ pub struct Config {
     /// Shell integration configuration
     #[serde(default)]
     pub shell_integration: ShellIntegrationConfig,
+    /// Actions available in every channel (unless filtered)
+    #[serde(default)]
+    pub actions: FxHashMap<String, GlobalActionSpec>,
+    /// Vim-style modal editing configuration
+    #[serde(default)]
+    pub vim: VimConfig,
+    /// Frecency ranking configuration
+    #[serde(default)]
+    pub frecency: FrecencyConfig,
+    /// Files included by the configuration file and directories searched
+    /// for them
+    #[serde(skip)]
+    pub included_paths: Vec<PathBuf>,
 }
 
 const PROJECT_NAME: &str = "television";
 pub const CONFIG_FILE_NAME: &str = "config.toml";
+/// Directory of the config folder user themes are looked up in.
+pub const THEMES_DIR_NAME: &str = "themes";
 
 pub struct ConfigEnv {
     _data_dir: PathBuf,
@@ -167,56 +224,71 @@ impl Config {
     pub fn new(
         config_env: &ConfigEnv,
         custom_config_file: Option<&Path>,
+        profile: Option<&str>,
+    ) -> Result<Self> {
+        Self::with_overrides(
+            config_env,
+            custom_config_file,
+            profile,
+            &Table::new(),
+        )
+    }
+
+    /// Like `new`, with `overrides` (see `env_overrides`) applied on top of
+    /// the user's configuration.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn with_overrides(
+        config_env: &ConfigEnv,
+        custom_config_file: Option<&Path>,
+        profile: Option<&str>,
+        overrides: &Table,
     ) -> Result<Self> {
         // Load the default_config values as base defaults
         let default_config: Config = default_config_from_file()?;
 
-        // if a config file exists, load it and merge it with the default configuration
-        if config_env.config_dir.join(CONFIG_FILE_NAME).is_file()
-            || custom_config_file.is_some()
-        {
-            let config_file = if let Some(path) = custom_config_file {
-                debug!("Using custom configuration file at: {:?}", path);
-                path.to_path_buf()
-            } else {
-                let config_file = config_env.config_dir.join(CONFIG_FILE_NAME);
-                debug!(
-                    "Using default configuration file at: {:?}",
-                    config_file
+        let config_file = if let Some(path) = custom_config_file {
+            debug!("Using custom configuration file at: {:?}", path);
+            path.to_path_buf()
+        } else {
+            let config_file = config_env.config_dir.join(CONFIG_FILE_NAME);
+            if !config_file.is_file() {
+                warn!(
+                    "No config file found at {:?}, creating default configuration file at that location.",
+                    config_env.config_dir
                 );
-                config_file
-            };
-
-            let user_cfg: Config = Self::load_user_config(&config_file)?;
-            debug!("Loaded user configuration: {:?}", user_cfg);
+                // create the default configuration file in the user's config directory
+                std::fs::write(&config_file, DEFAULT_CONFIG)?;
+            }
+            debug!("Using default configuration file at: {:?}", config_file);
+            config_file
+        };
 
-            // merge the user configuration with the default configuration
-            let final_cfg =
-                Self::merge_with_default(&default_config, user_cfg);
+        // load the user configuration and merge it with the default one
+        let user_cfg: Config =
+            Self::load_user_config(&config_file, profile, overrides)?;
+        debug!("Loaded user configuration: {:?}", user_cfg);
 
-            Ok(final_cfg)
-        } else {
-            // otherwise, create the default configuration file
-            warn!(
-                "No config file found at {:?}, creating default configuration file at that location.",
-                config_env.config_dir
-            );
-            // create the default configuration file in the user's config directory
-            std::fs::write(
-                config_env.config_dir.join(CONFIG_FILE_NAME),
-                DEFAULT_CONFIG,
-            )?;
-            Ok(default_config)
-        }
+        Ok(Self::merge_with_default(&default_config, user_cfg))
     }
 
-    fn load_user_config(config_file: &Path) -> Result<Self> {
-        let contents = std::fs::read_to_string(config_file)?;
-        let user_cfg: Config = toml::from_str(&contents).context(format!(
-            "Error parsing configuration file: {}\n{}",
-            config_file.display(),
-            USER_CONFIG_ERROR_MSG,
-        ))?;
+    /// Loads the user's configuration file along with the files it includes,
+    /// with `profile` and then `overrides` applied on top of them.
+    fn load_user_config(
+        config_file: &Path,
+        profile: Option<&str>,
+        overrides: &Table,
+    ) -> Result<Self> {
+        let user_config = UserConfig::load(config_file, profile)?;
+        let mut settings = user_config.merged();
+        merge_tables(&mut settings, overrides.clone());
+        let mut user_cfg = Config::deserialize(toml::Value::Table(settings))
+            .with_context(|| match profile {
+            Some(profile) => {
+                format!("Error parsing profile `{profile}`")
+            }
+            None => "Error parsing configuration".to_string(),
+        })?;
+        user_cfg.included_paths = user_config.included_paths;
         Ok(user_cfg)
     }
 
@@ -251,6 +323,10 @@ impl Config {
             keybindings: new.keybindings,
             ui: new.ui,
             shell_integration: new.shell_integration,
+            actions: new.actions,
+            vim: default.vim.merge_with(&new.vim),
+            frecency: new.frecency,
+            included_paths: new.included_paths,
         }
     }
 
@@ -309,6 +385,29 @@ pub fn get_config_dir() -> PathBuf {
     }
 }
 
+/// Describes a TOML parse error on a single line, e.g.
+/// `config.toml:12: invalid type: integer, expected a string`.
+///
+/// `toml` renders its errors with a snippet of the offending input spanning
+/// several lines, which doesn't fit in a notification.
+pub fn describe_toml_error(
+    file: &Path,
+    content: &str,
+    error: &toml::de::Error,
+) -> String {
+    let file_name = file.file_name().unwrap_or(file.as_os_str());
+    match error.span() {
+        Some(span) => {
+            let line = content
+                .get(..span.start)
+                .map_or(0, |before| before.matches('\n').count())
+                + 1;
+            format!("{}:{line}: {}", file_name.display(), error.message())
+        }
+        None => format!("{}: {}", file_name.display(), error.message()),
+    }
+}
+
 fn default_cable_dir() -> PathBuf {
     get_config_dir().join(CABLE_DIR_NAME)
 }
@@ -346,6 +445,20 @@ mod tests {
         assert!(config_dir.is_absolute());
     }
 
+    #[test]
+    fn test_describe_toml_error() {
+        let content = "[ui]\nui_scale = \"big\"\n";
+        let error = toml::from_str::<Config>(content).unwrap_err();
+        assert_eq!(
+            describe_toml_error(
+                Path::new("/tmp/config.toml"),
+                content,
+                &error
+            ),
+            "config.toml:2: invalid type: string \"big\", expected u16"
+        );
+    }
+
     #[test]
     fn test_load_user_config() {
         let dir = tempdir().unwrap();
@@ -354,7 +467,9 @@ mod tests {
         let mut file = File::create(&config_file).unwrap();
         file.write_all(DEFAULT_CONFIG.as_bytes()).unwrap();
 
-        let config = Config::load_user_config(&config_file).unwrap();
+        let config =
+            Config::load_user_config(&config_file, None, &Table::new())
+                .unwrap();
         assert_eq!(config.application.data_dir, get_data_dir());
         assert_eq!(config, toml::from_str(DEFAULT_CONFIG).unwrap());
     }
@@ -369,7 +484,7 @@ mod tests {
             _data_dir: get_data_dir(),
             config_dir: config_dir.to_path_buf(),
         };
-        let config = Config::new(&config_env, None).unwrap();
+        let config = Config::new(&config_env, None, None).unwrap();
         let default_config: Config = toml::from_str(DEFAULT_CONFIG).unwrap();
 
         assert_eq!(config.application, default_config.application);
@@ -421,7 +536,7 @@ mod tests {
             _data_dir: get_data_dir(),
             config_dir: config_dir.to_path_buf(),
         };
-        let config = Config::new(&config_env, None).unwrap();
+        let config = Config::new(&config_env, None, None).unwrap();
 
         let mut default_config: Config =
             toml::from_str(DEFAULT_CONFIG).unwrap();
@@ -471,7 +586,7 @@ mod tests {
             _data_dir: get_data_dir(),
             config_dir: config_dir.to_path_buf(),
         };
-        let config = Config::new(&config_env, None).unwrap();
+        let config = Config::new(&config_env, None, None).unwrap();
 
         // Verify that input_prompt was loaded from user config
         assert_eq!(config.ui.input_bar.prompt, Some("❯".to_string()));
@@ -495,7 +610,7 @@ mod tests {
             config_dir: config_dir.to_path_buf(),
         };
 
-        let config = Config::new(&config_env, None).unwrap();
+        let config = Config::new(&config_env, None, None).unwrap();
 
         assert_eq!(
             config.shell_integration.commands.iter().collect::<Vec<_>>(),
@@ -503,6 +618,113 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_user_global_actions_are_loaded() {
+        let user_config = r#"
+            tick_rate = 100
+
+            [actions.edit]
+            description = "Open in editor"
+            command = "vim {}"
+            mode = "execute"
+            channels = ["files"]
+        "#;
+
+        let dir = tempdir().unwrap();
+        let config_dir = dir.path();
+        let config_file = config_dir.join(CONFIG_FILE_NAME);
+        let mut file = File::create(&config_file).unwrap();
+        file.write_all(user_config.as_bytes()).unwrap();
+
+        let config_env = ConfigEnv {
+            _data_dir: get_data_dir(),
+            config_dir: config_dir.to_path_buf(),
+        };
+
+        let config = Config::new(&config_env, None, None).unwrap();
+
+        assert_eq!(config.application.tick_rate, 100);
+        let edit = config.actions.get("edit").unwrap();
+        assert_eq!(edit.spec.description.as_deref(), Some("Open in editor"));
+        assert_eq!(edit.channels, vec!["files"]);
+    }
+
+    #[test]
+    fn test_user_vim_config_is_merged_with_default() {
+        use crate::{action::Action, event::Key};
+
+        let user_config = r#"
+            [vim]
+            enabled = true
+
+            [vim.normal_keybindings]
+            j = "select_prev_entry"
+        "#;
+
+        let dir = tempdir().unwrap();
+        let config_dir = dir.path();
+        let config_file = config_dir.join(CONFIG_FILE_NAME);
+        let mut file = File::create(&config_file).unwrap();
+        file.write_all(user_config.as_bytes()).unwrap();
+
+        let config_env = ConfigEnv {
+            _data_dir: get_data_dir(),
+            config_dir: config_dir.to_path_buf(),
+        };
+
+        let config = Config::new(&config_env, None, None).unwrap();
+
+        assert!(config.vim.enabled);
+        let normal = &config.vim.normal_keybindings;
+        assert_eq!(
+            normal.get(&Key::Char('j')),
+            Some(&Action::SelectPrevEntry.into())
+        );
+        assert_eq!(
+            normal.get(&Key::Char('k')),
+            Some(&Action::SelectPrevEntry.into())
+        );
+        assert_eq!(
+            normal.get(&Key::Char('G')),
+            Some(&Action::SelectLastEntry.into())
+        );
+        assert_eq!(
+            config.vim.insert_keybindings.get(&Key::Esc),
+            Some(&Action::EnterNormalMode.into())
+        );
+    }
+
+    #[test]
+    fn test_user_frecency_config() {
+        let user_config = r#"
+            [frecency]
+            decay = [{ hours = 1, weight = 50 }, { hours = 48, weight = 20 }]
+            blend = "weighted"
+        "#;
+
+        let dir = tempdir().unwrap();
+        let config_dir = dir.path();
+        let config_file = config_dir.join(CONFIG_FILE_NAME);
+        let mut file = File::create(&config_file).unwrap();
+        file.write_all(user_config.as_bytes()).unwrap();
+
+        let config_env = ConfigEnv {
+            _data_dir: get_data_dir(),
+            config_dir: config_dir.to_path_buf(),
+        };
+
+        let config = Config::new(&config_env, None, None).unwrap();
+
+        let frecency = &config.frecency;
+        assert_eq!(frecency.blend, frecency::FrecencyBlend::Weighted);
+        assert_eq!(frecency.recency_weight(0), 50);
+        assert_eq!(frecency.recency_weight(2), 20);
+        assert_eq!(frecency.recency_weight(100), 10);
+        // unset fields keep their defaults
+        assert_eq!(frecency.max_count, 20);
+        assert_eq!(frecency.max_score(), 50 * 20);
+    }
+
     #[test]
     fn test_shell_integration_keybindings_are_overwritten_by_user() {
         let user_config = r#"
@@ -522,7 +744,7 @@ mod tests {
             config_dir: config_dir.to_path_buf(),
         };
 
-        let config = Config::new(&config_env, None).unwrap();
+        let config = Config::new(&config_env, None, None).unwrap();
 
         let expected: rustc_hash::FxHashMap<String, Key> = [
             (
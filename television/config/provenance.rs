@@ -0,0 +1,381 @@
+use serde_json::json;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter, Write},
+    path::PathBuf,
+};
+use toml::{Table, Value};
+
+/// Longest `key = value` line the source comments are aligned after.
+const MAX_ALIGNED_WIDTH: usize = 48;
+
+/// Where a configuration value comes from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// tv's default configuration
+    Default,
+    /// The user's configuration file
+    UserConfig(PathBuf),
+    /// A channel file of the cable directory
+    Channel(PathBuf),
+    /// The builtin channel with that name
+    BuiltinChannel(String),
+    /// The profile with that name
+    Profile(String),
+    /// The environment variable with that name
+    Env(String),
+    /// A command line flag
+    Cli(&'static str),
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::UserConfig(path) | Source::Channel(path) => {
+                write!(f, "{}", path.display())
+            }
+            Source::BuiltinChannel(name) => {
+                write!(f, "builtin channel {name}")
+            }
+            Source::Profile(name) => write!(f, "profile {name}"),
+            Source::Env(name) => write!(f, "{name}"),
+            Source::Cli(flag) => write!(f, "{flag}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    Table(BTreeMap<String, Node>),
+    Value(Value, Source),
+}
+
+impl Node {
+    fn new(value: Value, source: &Source) -> Self {
+        match value {
+            Value::Table(table) => Node::Table(
+                table
+                    .into_iter()
+                    .map(|(key, value)| (key, Node::new(value, source)))
+                    .collect(),
+            ),
+            value => Node::Value(value, source.clone()),
+        }
+    }
+}
+
+/// A configuration laid out like the configuration file, each value
+/// annotated with the layer it comes from.
+///
+/// Layers are merged from the lowest to the highest precedence, e.g. the
+/// defaults, then the user's configuration file, a channel and the command
+/// line flags.
+#[derive(Debug, Default)]
+pub struct AnnotatedConfig(BTreeMap<String, Node>);
+
+impl AnnotatedConfig {
+    /// Merges `table` into the configuration: tables are merged key by key,
+    /// any other value replaces the existing one.
+    pub fn merge(&mut self, table: Table, source: &Source) {
+        merge_into(&mut self.0, table, source);
+    }
+
+    /// Sets the value at `path`, replacing whatever was there.
+    pub fn set(&mut self, path: &[&str], value: Value, source: &Source) {
+        let Some((last, parents)) = path.split_last() else {
+            return;
+        };
+        let mut table = &mut self.0;
+        for key in parents {
+            let node = table
+                .entry((*key).to_string())
+                .or_insert_with(|| Node::Table(BTreeMap::new()));
+            if let Node::Value(..) = node {
+                *node = Node::Table(BTreeMap::new());
+            }
+            let Node::Table(child) = node else {
+                unreachable!()
+            };
+            table = child;
+        }
+        table.insert((*last).to_string(), Node::new(value, source));
+    }
+
+    /// Removes the value or table at `path`.
+    pub fn remove(&mut self, path: &[&str]) {
+        let Some((last, parents)) = path.split_last() else {
+            return;
+        };
+        let mut table = &mut self.0;
+        for key in parents {
+            match table.get_mut(*key) {
+                Some(Node::Table(child)) => table = child,
+                _ => return,
+            }
+        }
+        table.remove(*last);
+    }
+
+    /// The value at `path` and where it comes from, if it's set.
+    pub fn get(&self, path: &[&str]) -> Option<(&Value, &Source)> {
+        let (last, parents) = path.split_last()?;
+        let mut table = &self.0;
+        for key in parents {
+            match table.get(*key)? {
+                Node::Table(child) => table = child,
+                Node::Value(..) => return None,
+            }
+        }
+        match table.get(*last)? {
+            Node::Value(value, source) => Some((value, source)),
+            Node::Table(_) => None,
+        }
+    }
+
+    /// Merges a channel file, laid out like the configuration file: its
+    /// `[ui]`, `[keybindings]` and `[actions]` sections override the
+    /// configuration's and its other sections are kept as they are.
+    pub fn merge_channel(&mut self, mut channel: Table, source: &Source) {
+        let name = channel
+            .get("metadata")
+            .and_then(|metadata| metadata.get("name"))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        if let Some(Value::Table(mut ui)) = channel.remove("ui") {
+            // the theme can only be set in the configuration file
+            ui.remove("theme");
+            if let Some(layout) =
+                ui.remove("layout").or_else(|| ui.remove("orientation"))
+            {
+                ui.insert("orientation".to_string(), layout);
+            }
+            self.merge(
+                Table::from_iter([("ui".to_string(), ui.into())]),
+                source,
+            );
+        }
+
+        // global actions limited to other channels don't apply, and the
+        // channel's actions replace global actions with the same name
+        if let Some(Node::Table(actions)) = self.0.get_mut("actions") {
+            actions.retain(|_, action| applies_to(action, &name));
+        }
+        if let Some(Value::Table(actions)) = channel.remove("actions") {
+            for (action_name, action) in actions {
+                self.set(&["actions", &action_name], action, source);
+            }
+        }
+
+        self.merge(channel, source);
+    }
+
+    /// Renders the configuration as TOML, each value followed by a comment
+    /// naming its source.
+    pub fn to_toml(&self) -> String {
+        let mut out = String::new();
+        write_table(&mut out, &mut Vec::new(), &self.0);
+        out
+    }
+
+    /// Renders the configuration as JSON, each value replaced by a
+    /// `{"value": ..., "source": ...}` object.
+    pub fn to_json(&self) -> serde_json::Value {
+        table_to_json(&self.0)
+    }
+}
+
+fn merge_into(
+    tree: &mut BTreeMap<String, Node>,
+    table: Table,
+    source: &Source,
+) {
+    for (key, value) in table {
+        match (tree.get_mut(&key), value) {
+            (Some(Node::Table(child)), Value::Table(table)) => {
+                merge_into(child, table, source);
+            }
+            (_, value) => {
+                tree.insert(key, Node::new(value, source));
+            }
+        }
+    }
+}
+
+/// Whether a global action applies to the channel `name`, see
+/// `GlobalActionSpec::applies_to`.
+fn applies_to(action: &Node, name: &str) -> bool {
+    let Node::Table(action) = action else {
+        return true;
+    };
+    match action.get("channels") {
+        Some(Node::Value(Value::Array(channels), _)) => {
+            channels.is_empty()
+                || channels
+                    .iter()
+                    .any(|channel| channel.as_str() == Some(name))
+        }
+        _ => true,
+    }
+}
+
+fn write_table(
+    out: &mut String,
+    path: &mut Vec<String>,
+    table: &BTreeMap<String, Node>,
+) {
+    let values: Vec<_> = table
+        .iter()
+        .filter_map(|(key, node)| match node {
+            Node::Value(value, source) => {
+                Some((format!("{} = {value}", format_key(key)), source))
+            }
+            Node::Table(_) => None,
+        })
+        .collect();
+    let is_empty = table.is_empty();
+
+    if !path.is_empty() && (!values.is_empty() || is_empty) {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        let header: Vec<_> = path.iter().map(|key| format_key(key)).collect();
+        let _ = writeln!(out, "[{}]", header.join("."));
+    }
+    let width = values
+        .iter()
+        .map(|(line, _)| line.chars().count())
+        .filter(|width| *width <= MAX_ALIGNED_WIDTH)
+        .max()
+        .unwrap_or(0);
+    for (line, source) in values {
+        let _ = writeln!(out, "{line:<width$}  # {source}");
+    }
+
+    for (key, node) in table {
+        if let Node::Table(child) = node {
+            path.push(key.clone());
+            write_table(out, path, child);
+            path.pop();
+        }
+    }
+}
+
+/// Quotes `key` unless it's a valid bare TOML key.
+fn format_key(key: &str) -> String {
+    if !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+fn table_to_json(table: &BTreeMap<String, Node>) -> serde_json::Value {
+    table
+        .iter()
+        .map(|(key, node)| {
+            let value = match node {
+                Node::Table(child) => table_to_json(child),
+                Node::Value(value, source) => json!({
+                    "value": value,
+                    "source": source.to_string(),
+                }),
+            };
+            (key.clone(), value)
+        })
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(toml: &str) -> Table {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn test_annotated_config() {
+        let user = Source::UserConfig(PathBuf::from("config.toml"));
+        let channel = Source::Channel(PathBuf::from("cable/files.toml"));
+        let mut config = AnnotatedConfig::default();
+        config.merge(
+            table(
+                r#"
+                tick_rate = 50
+                [ui]
+                ui_scale = 100
+                orientation = "landscape"
+                [actions.edit]
+                command = "vim {}"
+                channels = ["text"]
+                [actions.copy]
+                command = "wl-copy {}"
+                "#,
+            ),
+            &Source::Default,
+        );
+        config.merge(table("[ui]\nui_scale = 80\n"), &user);
+        config.merge_channel(
+            table(
+                r#"
+                [metadata]
+                name = "files"
+                [ui]
+                layout = "portrait"
+                theme = "nord"
+                "#,
+            ),
+            &channel,
+        );
+        config.set(
+            &["ui", "preview_panel", "size"],
+            Value::Integer(30),
+            &Source::Cli("--preview-size"),
+        );
+
+        assert_eq!(
+            config.get(&["tick_rate"]),
+            Some((&Value::Integer(50), &Source::Default))
+        );
+        assert_eq!(config.get(&["ui", "ui_scale"]).unwrap().1, &user);
+        assert_eq!(
+            config.get(&["ui", "orientation"]),
+            Some((&Value::String("portrait".into()), &channel))
+        );
+        assert!(config.get(&["ui", "theme"]).is_none());
+        // `edit` only applies to the `text` channel
+        assert!(config.get(&["actions", "edit", "command"]).is_none());
+        assert!(config.get(&["actions", "copy", "command"]).is_some());
+
+        assert_eq!(
+            config.to_toml(),
+            r#"tick_rate = 50  # default
+
+[actions.copy]
+command = "wl-copy {}"  # default
+
+[metadata]
+name = "files"  # cable/files.toml
+
+[ui]
+orientation = "portrait"  # cable/files.toml
+ui_scale = 80             # config.toml
+
+[ui.preview_panel]
+size = 30  # --preview-size
+"#
+        );
+        assert_eq!(
+            config.to_json()["ui"]["preview_panel"]["size"],
+            json!({"value": 30, "source": "--preview-size"})
+        );
+    }
+}
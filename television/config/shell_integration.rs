@@ -2,7 +2,9 @@ use std::hash::Hash;
 
 use crate::{event::Key, utils::hashmaps};
 use rustc_hash::FxHashMap;
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 #[derive(Clone, Debug, Serialize, PartialEq)]
 #[serde(default)]
@@ -46,6 +48,33 @@ impl<'de> Deserialize<'de> for ShellIntegrationConfig {
     }
 }
 
+impl JsonSchema for ShellIntegrationConfig {
+    fn schema_name() -> Cow<'static, str> {
+        "ShellIntegrationConfig".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "object",
+            "properties": {
+                "channel_triggers": {
+                    "description": "Maps a channel to the commands that trigger it, e.g. `dirs = [\"cd\", \"ls\"]`",
+                    "type": "object",
+                    "additionalProperties": generator.subschema_for::<Vec<String>>(),
+                },
+                "fallback_channel": {
+                    "description": "Channel used when no trigger matches the command line",
+                    "type": "string",
+                },
+                "keybindings": {
+                    "type": "object",
+                    "additionalProperties": generator.subschema_for::<Key>(),
+                },
+            },
+        })
+    }
+}
+
 const DEFAULT_FALLBACK_CHANNEL: &str = "files";
 
 static DEFAULT_CHANNEL_TRIGGERS: &[(&str, &[&str])] = &[
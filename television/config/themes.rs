@@ -8,7 +8,7 @@ use crate::screen::colors::{
 use ratatui::style::Color as RatatuiColor;
 use serde::Deserialize;
 
-use super::get_config_dir;
+use super::{THEMES_DIR_NAME, describe_toml_error, get_config_dir};
 
 pub mod builtin;
 
@@ -117,17 +117,35 @@ pub struct Theme {
 
 impl Theme {
     pub fn from_name(name: &str) -> Self {
-        Self::from_path(
-            &get_config_dir()
-                .join("themes")
-                .join(name)
-                .with_extension("toml"),
-        )
-        .unwrap_or_else(|_| {
+        Self::try_from_name(name).unwrap_or_else(|_| {
             Self::from_builtin(name).unwrap_or_else(|_| Self::default())
         })
     }
 
+    /// Loads the theme called `name` from the user's `themes` directory, or
+    /// from the builtin themes if there's no such file there.
+    ///
+    /// Unlike [`Theme::from_name`], a user theme that can't be read or parsed
+    /// is reported rather than silently replaced by the builtin one.
+    pub fn try_from_name(name: &str) -> Result<Self, String> {
+        let path = get_config_dir()
+            .join(THEMES_DIR_NAME)
+            .join(name)
+            .with_extension("toml");
+        if !path.is_file() {
+            return Self::from_builtin(name).map_err(|e| e.to_string());
+        }
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            format!("Failed to read theme {}: {e}", path.display())
+        })?;
+        toml::from_str(&content).map_err(|e| {
+            format!(
+                "Failed to parse theme {}",
+                describe_toml_error(&path, &content, &e)
+            )
+        })
+    }
+
     pub fn from_builtin(
         name: &str,
     ) -> Result<Self, Box<dyn std::error::Error>> {
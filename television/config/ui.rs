@@ -3,13 +3,16 @@ use crate::{
     config::themes::DEFAULT_THEME,
     screen::layout::{InputPosition, Orientation},
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 pub const DEFAULT_UI_SCALE: u16 = 100;
 pub const DEFAULT_PREVIEW_SIZE: u16 = 50;
 pub const DEFAULT_PROMPT: &str = ">";
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Hash)]
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Hash, JsonSchema,
+)]
 #[serde(default)]
 pub struct InputBarConfig {
     pub position: InputPosition,
@@ -37,7 +40,9 @@ fn default_prompt() -> Option<String> {
     Some(String::from(DEFAULT_PROMPT))
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Hash, Default)]
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Hash, Default, JsonSchema,
+)]
 #[serde(default)]
 pub struct StatusBarConfig {
     pub separator_open: String,
@@ -45,14 +50,18 @@ pub struct StatusBarConfig {
     pub hidden: bool,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Hash, Default)]
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Hash, Default, JsonSchema,
+)]
 #[serde(default)]
 pub struct ResultsPanelConfig {
     pub border_type: BorderType,
     pub padding: Padding,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Hash)]
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Hash, JsonSchema,
+)]
 #[serde(default)]
 pub struct PreviewPanelConfig {
     pub size: u16,
@@ -80,7 +89,9 @@ impl Default for PreviewPanelConfig {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Hash)]
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Hash, JsonSchema,
+)]
 #[serde(default)]
 pub struct HelpPanelConfig {
     pub show_categories: bool,
@@ -103,7 +114,9 @@ impl Default for HelpPanelConfig {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Hash)]
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Hash, JsonSchema,
+)]
 #[serde(default)]
 pub struct RemoteControlConfig {
     pub show_channel_descriptions: bool,
@@ -124,7 +137,15 @@ impl Default for RemoteControlConfig {
 /// Theme color overrides that can be specified in the configuration file
 /// to customize the appearance of the selected theme
 #[derive(
-    Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Default,
+    Clone,
+    Debug,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    JsonSchema,
 )]
 #[serde(default)]
 pub struct ThemeOverrides {
@@ -194,7 +215,9 @@ impl ThemeOverrides {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Hash)]
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Hash, JsonSchema,
+)]
 #[serde(default)]
 pub struct UiConfig {
     pub ui_scale: u16,
@@ -232,7 +255,16 @@ impl Default for UiConfig {
 }
 
 #[derive(
-    Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Hash, Default, Eq,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Hash,
+    Default,
+    Eq,
+    JsonSchema,
 )]
 #[serde(rename_all = "snake_case")]
 pub enum BorderType {
@@ -268,7 +300,16 @@ impl From<crate::cli::args::BorderType> for BorderType {
 }
 
 #[derive(
-    Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Hash, Default, Eq,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Hash,
+    Default,
+    Eq,
+    JsonSchema,
 )]
 #[serde(default)]
 pub struct Padding {
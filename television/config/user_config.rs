@@ -0,0 +1,494 @@
+use super::{Config, USER_CONFIG_ERROR_MSG, provenance::Source};
+use crate::utils::paths::expand_tilde;
+use anyhow::{Context, Result, bail};
+use schemars::{Schema, schema_for};
+use serde::Deserialize;
+use serde_json::json;
+use std::{
+    collections::BTreeMap,
+    ffi::OsStr,
+    fmt::{self, Display, Formatter},
+    fs,
+    path::{Path, PathBuf},
+};
+use toml::{Table, Value, de::DeTable};
+
+/// Files to read before the configuration file, e.g.
+/// `include = ["~/dotfiles/tv/base.toml", "conf.d/*.toml"]`.
+const INCLUDE_KEY: &str = "include";
+/// Named sets of settings overriding the rest of the configuration, e.g.
+/// `[profiles.work]`.
+const PROFILES_KEY: &str = "profiles";
+
+/// Environment variable selecting a profile when `--profile` isn't given.
+pub const PROFILE_ENV_VAR: &str = "TV_PROFILE";
+
+/// The JSON Schema of the configuration file.
+///
+/// `include` and `profiles` aren't part of [`Config`] since they are resolved
+/// before the settings are read, so they are added by hand. A profile takes
+/// the same settings as the rest of the file.
+pub fn json_schema() -> Schema {
+    let mut schema = schema_for!(Config);
+    if let Some(properties) = schema
+        .get_mut("properties")
+        .and_then(serde_json::Value::as_object_mut)
+    {
+        // these defaults depend on the machine, or don't serialize to what
+        // the configuration file accepts (keys)
+        for key in ["data_dir", "cable_dir", "shell_integration"] {
+            if let Some(property) =
+                properties.get_mut(key).and_then(|p| p.as_object_mut())
+            {
+                property.remove("default");
+            }
+        }
+        properties.insert(
+            INCLUDE_KEY.to_string(),
+            json!({
+                "description": "Files to read before this one, relative to it (file names may contain `*`)",
+                "type": "array",
+                "items": { "type": "string" },
+            }),
+        );
+        properties.insert(
+            PROFILES_KEY.to_string(),
+            json!({
+                "description": "Named sets of settings, selected with `--profile` or `TV_PROFILE`",
+                "type": "object",
+                "additionalProperties": { "$ref": "#" },
+            }),
+        );
+    }
+    schema
+}
+
+/// The profile to use: the one given on the command line, or else the one
+/// set in the environment.
+pub fn resolve_profile(cli_profile: Option<&str>) -> Option<String> {
+    cli_profile.map(str::to_string).or_else(|| {
+        std::env::var(PROFILE_ENV_VAR)
+            .ok()
+            .filter(|profile| !profile.is_empty())
+    })
+}
+
+/// Attached to errors about a configuration file, which may be the
+/// configuration file itself or one of the files it includes.
+#[derive(Debug)]
+pub struct ConfigFileContext(pub PathBuf);
+
+impl Display for ConfigFileContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Error parsing configuration file: {}\n{USER_CONFIG_ERROR_MSG}",
+            self.0.display()
+        )
+    }
+}
+
+/// The user's configuration, made of the configuration file, the files it
+/// includes and the selected profile.
+#[derive(Debug, Default)]
+pub struct UserConfig {
+    /// The settings of each file and of the profile, from the lowest to the
+    /// highest precedence.
+    pub layers: Vec<(Source, Table)>,
+    /// The included files and the directories searched for them, which
+    /// should be watched for changes along with the configuration file.
+    pub included_paths: Vec<PathBuf>,
+    /// The profiles defined across all files.
+    profiles: BTreeMap<String, Table>,
+}
+
+impl UserConfig {
+    /// Reads `config_file` and the files it includes, and applies `profile`
+    /// on top of them.
+    ///
+    /// Included files are read in order, files matching a wildcard pattern
+    /// in alphabetical order, and the file including them overrides them.
+    pub fn load(config_file: &Path, profile: Option<&str>) -> Result<Self> {
+        let mut config = Self::default();
+        config.read_file(config_file, &mut Vec::new())?;
+        // the configuration file itself is watched anyway
+        config.included_paths.retain(|path| path != config_file);
+
+        if let Some(name) = profile {
+            let Some(table) = config.profiles.remove(name) else {
+                let available: Vec<_> =
+                    config.profiles.keys().map(String::as_str).collect();
+                if available.is_empty() {
+                    bail!(
+                        "Unknown profile `{name}`: the configuration doesn't define any profile"
+                    );
+                }
+                bail!(
+                    "Unknown profile `{name}`, available profiles: {}",
+                    available.join(", ")
+                );
+            };
+            config
+                .layers
+                .push((Source::Profile(name.to_string()), table));
+        }
+        Ok(config)
+    }
+
+    /// All layers merged into a single configuration table.
+    pub fn merged(&self) -> Table {
+        let mut merged = Table::new();
+        for (_, table) in &self.layers {
+            merge_tables(&mut merged, table.clone());
+        }
+        merged
+    }
+
+    fn read_file(
+        &mut self,
+        path: &Path,
+        parents: &mut Vec<PathBuf>,
+    ) -> Result<()> {
+        let canonical = path.canonicalize().with_context(|| {
+            format!("Failed to read configuration file {}", path.display())
+        })?;
+        if parents.contains(&canonical) {
+            bail!("{} includes itself", path.display());
+        }
+        let content = fs::read_to_string(path).with_context(|| {
+            format!("Failed to read configuration file {}", path.display())
+        })?;
+        let ConfigFile {
+            settings,
+            includes,
+            profiles,
+        } = parse_config_file(&content)
+            .with_context(|| ConfigFileContext(path.to_path_buf()))?;
+        self.included_paths.push(path.to_path_buf());
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        parents.push(canonical);
+        for pattern in &includes {
+            for file in
+                self.resolve_include(pattern, dir).with_context(|| {
+                    format!("Invalid include in {}", path.display())
+                })?
+            {
+                self.read_file(&file, parents)?;
+            }
+        }
+        parents.pop();
+
+        self.layers
+            .push((Source::UserConfig(path.to_path_buf()), settings));
+        for (name, profile) in profiles {
+            merge_tables(self.profiles.entry(name).or_default(), profile);
+        }
+        Ok(())
+    }
+
+    /// The files matching `pattern`, relative to `dir` unless absolute.
+    ///
+    /// Wildcards (`*` and `?`) are only supported in the file name, and a
+    /// pattern without any must name an existing file.
+    fn resolve_include(
+        &mut self,
+        pattern: &str,
+        dir: &Path,
+    ) -> Result<Vec<PathBuf>> {
+        let path = dir.join(expand_tilde(pattern));
+        let (Some(parent), Some(file_pattern)) =
+            (path.parent(), path.file_name().and_then(OsStr::to_str))
+        else {
+            bail!("`{pattern}` isn't a file");
+        };
+        if !is_wildcard(file_pattern) {
+            if !path.is_file() {
+                bail!("{} doesn't exist", path.display());
+            }
+            return Ok(vec![path]);
+        }
+        if parent.to_str().is_some_and(is_wildcard) {
+            bail!("`{pattern}`: wildcards are only supported in file names");
+        }
+
+        // watch the directory so that new files are picked up
+        self.included_paths.push(parent.to_path_buf());
+        let Ok(entries) = fs::read_dir(parent) else {
+            return Ok(Vec::new());
+        };
+        let mut files: Vec<_> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|file| {
+                file.is_file()
+                    && file.file_name().and_then(OsStr::to_str).is_some_and(
+                        |name| {
+                            // like shells, wildcards don't match hidden files
+                            (!name.starts_with('.')
+                                || file_pattern.starts_with('.'))
+                                && matches_wildcard(file_pattern, name)
+                        },
+                    )
+            })
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+}
+
+/// A configuration file split into its settings, the files it includes and
+/// the profiles it defines.
+struct ConfigFile {
+    settings: Table,
+    includes: Vec<String>,
+    profiles: BTreeMap<String, Table>,
+}
+
+fn parse_config_file(content: &str) -> Result<ConfigFile, toml::de::Error> {
+    let mut table: Table = toml::from_str(content)?;
+    let includes = table
+        .remove(INCLUDE_KEY)
+        .map(Vec::<String>::deserialize)
+        .transpose()?
+        .unwrap_or_default();
+    let profiles = table
+        .remove(PROFILES_KEY)
+        .map(BTreeMap::<String, Table>::deserialize)
+        .transpose()?
+        .unwrap_or_default();
+
+    // check the settings from the document rather than from `table` so that
+    // errors point to the offending line
+    let mut document = DeTable::parse(content)?;
+    document.get_mut().remove(INCLUDE_KEY);
+    document.get_mut().remove(PROFILES_KEY);
+    Config::deserialize(toml::de::Deserializer::from(document)).map_err(
+        |mut e| {
+            e.set_input(Some(content));
+            e
+        },
+    )?;
+
+    Ok(ConfigFile {
+        settings: table,
+        includes,
+        profiles,
+    })
+}
+
+/// Merges `overlay` into `base`.
+///
+/// Tables, e.g. `[keybindings]` or `[shell_integration.channel_triggers]`,
+/// are merged key by key while any other value replaces the existing one.
+/// Actions are replaced as a whole rather than field by field.
+pub fn merge_tables(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(actions)), Value::Table(overlay))
+                if key == "actions" =>
+            {
+                actions.extend(overlay);
+            }
+            (Some(Value::Table(table)), Value::Table(overlay)) => {
+                deep_merge(table, overlay);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn deep_merge(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(table)), Value::Table(overlay)) => {
+                deep_merge(table, overlay);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn is_wildcard(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// Whether `name` matches `pattern`, where `*` matches any number of
+/// characters and `?` a single one.
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    fn matches(pattern: &[char], name: &[char]) -> bool {
+        match pattern.split_first() {
+            None => name.is_empty(),
+            Some(('*', rest)) => {
+                (0..=name.len()).any(|i| matches(rest, &name[i..]))
+            }
+            Some(('?', rest)) => !name.is_empty() && matches(rest, &name[1..]),
+            Some((c, rest)) => {
+                name.first() == Some(c) && matches(rest, &name[1..])
+            }
+        }
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    matches(&pattern, &name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::describe_toml_error;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_matches_wildcard() {
+        assert!(matches_wildcard("*.toml", "work.toml"));
+        assert!(matches_wildcard("*.toml", ".toml"));
+        assert!(matches_wildcard("0?-*.toml", "01-base.toml"));
+        assert!(!matches_wildcard("*.toml", "work.toml.bak"));
+        assert!(!matches_wildcard("0?-*.toml", "1-base.toml"));
+    }
+
+    #[test]
+    fn test_includes_and_profiles() {
+        let dir = tempdir().unwrap();
+        let conf_d = dir.path().join("conf.d");
+        fs::create_dir(&conf_d).unwrap();
+        fs::write(
+            conf_d.join("10-team.toml"),
+            r#"
+            tick_rate = 100
+            [keybindings]
+            ctrl-j = "select_next_entry"
+            [actions.edit]
+            description = "Edit"
+            command = "vim {}"
+            [profiles.work]
+            default_channel = "git-repos"
+            "#,
+        )
+        .unwrap();
+        fs::write(
+            conf_d.join("20-personal.toml"),
+            r#"
+            [keybindings]
+            ctrl-k = "select_prev_entry"
+            [actions.edit]
+            command = "hx {}"
+            "#,
+        )
+        .unwrap();
+        fs::write(conf_d.join(".hidden.toml"), "tick_rate = 1").unwrap();
+        fs::write(conf_d.join("notes.txt"), "").unwrap();
+        let config_file = dir.path().join("config.toml");
+        fs::write(
+            &config_file,
+            r#"
+            include = ["conf.d/*.toml"]
+            tick_rate = 60
+            [profiles.work.ui]
+            ui_scale = 80
+            "#,
+        )
+        .unwrap();
+
+        let config = UserConfig::load(&config_file, None).unwrap();
+        let sources: Vec<_> = config
+            .layers
+            .iter()
+            .map(|(source, _)| source.to_string())
+            .collect();
+        assert_eq!(
+            sources,
+            [
+                conf_d.join("10-team.toml").display().to_string(),
+                conf_d.join("20-personal.toml").display().to_string(),
+                config_file.display().to_string(),
+            ]
+        );
+        assert_eq!(
+            config.included_paths,
+            [
+                conf_d.clone(),
+                conf_d.join("10-team.toml"),
+                conf_d.join("20-personal.toml"),
+            ]
+        );
+        let merged = config.merged();
+        // the including file wins
+        assert_eq!(merged["tick_rate"].as_integer(), Some(60));
+        // keybindings are merged, actions replaced
+        assert_eq!(merged["keybindings"].as_table().unwrap().len(), 2);
+        assert_eq!(
+            merged["actions"]["edit"].as_table().unwrap().keys().len(),
+            1
+        );
+        assert!(!merged.contains_key("include"));
+        assert!(!merged.contains_key("profiles"));
+
+        // profiles are merged across files and override everything else
+        let config = UserConfig::load(&config_file, Some("work")).unwrap();
+        let merged = config.merged();
+        assert_eq!(merged["default_channel"].as_str(), Some("git-repos"));
+        assert_eq!(merged["ui"]["ui_scale"].as_integer(), Some(80));
+        assert_eq!(merged["tick_rate"].as_integer(), Some(60));
+
+        let error = UserConfig::load(&config_file, Some("home")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unknown profile `home`, available profiles: work"
+        );
+    }
+
+    #[test]
+    fn test_invalid_includes() {
+        let dir = tempdir().unwrap();
+        let config_file = dir.path().join("config.toml");
+
+        fs::write(&config_file, "include = [\"missing.toml\"]").unwrap();
+        assert!(UserConfig::load(&config_file, None).is_err());
+
+        // a pattern matching no file is fine
+        fs::write(&config_file, "include = [\"conf.d/*.toml\"]").unwrap();
+        assert!(UserConfig::load(&config_file, None).is_ok());
+
+        fs::write(&config_file, "include = [\"config.toml\"]").unwrap();
+        let error = UserConfig::load(&config_file, None).unwrap_err();
+        assert!(error.to_string().ends_with("includes itself"));
+
+        // errors point to the included file
+        fs::write(dir.path().join("ui.toml"), "[ui]\nui_scale = \"big\"")
+            .unwrap();
+        fs::write(&config_file, "include = [\"ui.toml\"]").unwrap();
+        let error = UserConfig::load(&config_file, None).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<ConfigFileContext>().unwrap().0,
+            dir.path().join("ui.toml")
+        );
+        let toml_error = error.downcast_ref::<toml::de::Error>().unwrap();
+        assert_eq!(
+            describe_toml_error(
+                &dir.path().join("ui.toml"),
+                "[ui]\nui_scale = \"big\"",
+                toml_error
+            ),
+            "ui.toml:2: invalid type: string \"big\", expected u16"
+        );
+    }
+
+    #[test]
+    fn test_json_schema() {
+        let schema = json_schema();
+        let properties = schema.get("properties").unwrap();
+        assert!(properties.get(INCLUDE_KEY).is_some());
+        assert_eq!(
+            properties[PROFILES_KEY]["additionalProperties"],
+            json!({ "$ref": "#" })
+        );
+        assert!(properties["data_dir"].get("default").is_none());
+        assert_eq!(properties["tick_rate"]["default"], json!(50));
+    }
+}
@@ -0,0 +1,50 @@
+use crate::config::{Keybindings, merge_keybindings};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Configuration of the optional vim-style modal editing.
+///
+/// When enabled, the input starts in insert mode and `esc` switches to
+/// normal mode where printable keys no longer edit the query and are
+/// instead looked up in `normal_keybindings`.
+///
+/// # Example
+/// ```toml
+/// [vim]
+/// enabled = true
+///
+/// [vim.normal_keybindings]
+/// "g g" = "select_first_entry"
+/// "shift-g" = "select_last_entry"
+///
+/// [vim.insert_keybindings]
+/// esc = "enter_normal_mode"
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct VimConfig {
+    pub enabled: bool,
+    /// Bindings active in normal and visual mode, taking precedence over
+    /// the regular keybindings.
+    pub normal_keybindings: Keybindings,
+    /// Bindings active in insert mode, taking precedence over the regular
+    /// keybindings.
+    pub insert_keybindings: Keybindings,
+}
+
+impl VimConfig {
+    /// Merge the user's vim configuration on top of the default one.
+    pub fn merge_with(&self, other: &VimConfig) -> VimConfig {
+        VimConfig {
+            enabled: other.enabled,
+            normal_keybindings: merge_keybindings(
+                self.normal_keybindings.clone(),
+                &other.normal_keybindings,
+            ),
+            insert_keybindings: merge_keybindings(
+                self.insert_keybindings.clone(),
+                &other.insert_keybindings,
+            ),
+        }
+    }
+}
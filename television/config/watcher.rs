@@ -0,0 +1,142 @@
+use crate::action::Action;
+use rustc_hash::FxHashMap;
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+use tokio::{
+    sync::mpsc::UnboundedSender,
+    task::{JoinHandle, spawn_blocking},
+};
+use tracing::debug;
+use walkdir::WalkDir;
+
+/// How often the watched files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Modification time and size of each watched file.
+type Snapshot = FxHashMap<PathBuf, (Option<SystemTime>, u64)>;
+
+/// Watches the configuration file, the themes directory and the cable
+/// directory so that tv can reload them while it's running.
+///
+/// Files are polled rather than watched with OS notifications: there are only
+/// a handful of them and this keeps the behavior identical on every platform.
+pub struct ConfigWatcher {
+    paths: Vec<PathBuf>,
+    snapshot: Snapshot,
+}
+
+impl ConfigWatcher {
+    /// Starts watching `paths`, which may be files or directories, and may
+    /// not exist yet.
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let snapshot = snapshot(&paths);
+        Self { paths, snapshot }
+    }
+
+    /// Whether a watched file was created, modified or removed since the
+    /// last call.
+    pub fn changed(&mut self) -> bool {
+        let snapshot = snapshot(&self.paths);
+        if snapshot == self.snapshot {
+            return false;
+        }
+        self.snapshot = snapshot;
+        true
+    }
+
+    /// Watches `paths` in the background, sending [`Action::ReloadConfig`]
+    /// every time a watched file changes.
+    ///
+    /// Walking the directories blocks, so it's kept off the async runtime.
+    pub fn spawn(
+        paths: Vec<PathBuf>,
+        action_tx: UnboundedSender<Action>,
+    ) -> JoinHandle<()> {
+        debug!("Watching {:?} for configuration changes", paths);
+        tokio::spawn(async move {
+            let Ok(mut watcher) =
+                spawn_blocking(move || Self::new(paths)).await
+            else {
+                return;
+            };
+            let mut timer = tokio::time::interval(POLL_INTERVAL);
+            timer.set_missed_tick_behavior(
+                tokio::time::MissedTickBehavior::Skip,
+            );
+            loop {
+                timer.tick().await;
+                let Ok((polled, changed)) = spawn_blocking(move || {
+                    let changed = watcher.changed();
+                    (watcher, changed)
+                })
+                .await
+                else {
+                    break;
+                };
+                watcher = polled;
+                if changed {
+                    debug!("Configuration changed, reloading");
+                    if action_tx.send(Action::ReloadConfig).is_err() {
+                        break;
+                    }
+                }
+            }
+        })
+    }
+}
+
+fn snapshot(paths: &[PathBuf]) -> Snapshot {
+    paths
+        .iter()
+        .flat_map(|path| WalkDir::new(path).into_iter().flatten())
+        .filter(|entry| entry.file_type().is_file() && is_toml(entry.path()))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some((
+                entry.into_path(),
+                (metadata.modified().ok(), metadata.len()),
+            ))
+        })
+        .collect()
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "toml")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_watcher_detects_changes() {
+        let dir = tempdir().unwrap();
+        let cable_dir = dir.path().join("cable");
+        std::fs::create_dir(&cable_dir).unwrap();
+        let config_file = dir.path().join("config.toml");
+        let mut watcher =
+            ConfigWatcher::new(vec![config_file.clone(), cable_dir.clone()]);
+        assert!(!watcher.changed());
+
+        // files created after the watcher
+        std::fs::write(&config_file, "").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+        std::fs::write(cable_dir.join("files.toml"), "").unwrap();
+        assert!(watcher.changed());
+
+        // only TOML files are watched
+        std::fs::write(cable_dir.join("notes.txt"), "").unwrap();
+        assert!(!watcher.changed());
+
+        std::fs::write(cable_dir.join("files.toml"), "[metadata]").unwrap();
+        assert!(watcher.changed());
+        std::fs::remove_file(cable_dir.join("files.toml")).unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+    }
+}
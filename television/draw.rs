@@ -4,17 +4,31 @@ use crate::{
         action_picker::ActionEntry, entry::Entry, remote_control::CableEntry,
     },
     config::layers::MergedConfig,
+    event::Key,
+    matcher::config::{CaseSensitivity, MatchingMode},
+    notifications::Notifications,
     picker::Picker,
     previewer::state::PreviewState,
     screen::{
-        action_picker::draw_action_picker, colors::Colorscheme,
-        help_panel::draw_help_panel, input::draw_input_box, layout::Layout,
+        action_input_popup::draw_action_input_popup,
+        action_output_popup::draw_action_output_popup,
+        action_picker::draw_action_picker,
+        colors::Colorscheme,
+        confirm_action_popup::draw_confirm_action_popup,
+        help_panel::draw_help_panel,
+        input::draw_input_box,
+        layout::Layout,
         missing_requirements_popup::draw_missing_requirements_popup,
+        notifications::{draw_notification_history_popup, draw_notifications},
         preview::draw_preview_content_block,
-        remote_control::draw_remote_control, results::draw_results_list,
+        remote_control::draw_remote_control,
+        results::draw_results_list,
         status_bar,
     },
-    television::{MissingRequirementsPopup, Mode},
+    television::{
+        ActionInputPopup, ActionOutputPopup, ConfirmActionPopup,
+        MissingRequirementsPopup, Mode, NotificationHistoryPopup, VimMode,
+    },
     utils::metadata::AppMetadata,
 };
 use anyhow::Result;
@@ -90,6 +104,18 @@ pub struct TvState {
     pub channel_state: ChannelState,
     pub preview_state: PreviewState,
     pub missing_requirements_popup: Option<MissingRequirementsPopup>,
+    pub action_output_popup: Option<ActionOutputPopup>,
+    pub confirm_action_popup: Option<ConfirmActionPopup>,
+    pub action_input_popup: Option<ActionInputPopup>,
+    pub notifications: Notifications,
+    pub notification_history_popup: Option<NotificationHistoryPopup>,
+    /// Keys typed so far of a multi-key binding
+    pub pending_keys: Vec<Key>,
+    /// Current editing mode, `None` unless vim mode is enabled
+    pub vim_mode: Option<VimMode>,
+    pub matching_mode: MatchingMode,
+    pub case_sensitivity: CaseSensitivity,
+    pub no_sort: bool,
 }
 
 impl TvState {
@@ -103,6 +129,16 @@ impl TvState {
         channel_state: ChannelState,
         preview_state: PreviewState,
         missing_requirements_popup: Option<MissingRequirementsPopup>,
+        action_output_popup: Option<ActionOutputPopup>,
+        confirm_action_popup: Option<ConfirmActionPopup>,
+        action_input_popup: Option<ActionInputPopup>,
+        notifications: Notifications,
+        notification_history_popup: Option<NotificationHistoryPopup>,
+        pending_keys: Vec<Key>,
+        vim_mode: Option<VimMode>,
+        matching_mode: MatchingMode,
+        case_sensitivity: CaseSensitivity,
+        no_sort: bool,
     ) -> Self {
         Self {
             mode,
@@ -113,6 +149,16 @@ impl TvState {
             channel_state,
             preview_state,
             missing_requirements_popup,
+            action_output_popup,
+            confirm_action_popup,
+            action_input_popup,
+            notifications,
+            notification_history_popup,
+            pending_keys,
+            vim_mode,
+            matching_mode,
+            case_sensitivity,
+            no_sort,
         }
     }
 }
@@ -274,11 +320,19 @@ pub fn draw(ctx: Ctx, f: &mut Frame<'_>, area: Rect) -> Result<Layout> {
     }
 
     // action picker
-    let show_action_picker = matches!(ctx.tv_state.mode, Mode::ActionPicker);
+    let show_action_picker = matches!(
+        ctx.tv_state.mode,
+        Mode::ActionPicker | Mode::CommandPalette | Mode::HistoryPicker
+    );
     if show_action_picker {
         draw_action_picker(
             f,
             layout.action_picker.unwrap(),
+            match ctx.tv_state.mode {
+                Mode::CommandPalette => " Commands ",
+                Mode::HistoryPicker => " History ",
+                _ => " Actions ",
+            },
             &ctx.tv_state.ap_picker.entries,
             &mut ctx.tv_state.ap_picker.relative_state.clone(),
             &mut ctx.tv_state.ap_picker.input.clone(),
@@ -290,6 +344,18 @@ pub fn draw(ctx: Ctx, f: &mut Frame<'_>, area: Rect) -> Result<Layout> {
         draw_missing_requirements_popup(f, area, popup, &ctx.colorscheme);
     }
 
+    if let Some(popup) = &ctx.tv_state.action_output_popup {
+        draw_action_output_popup(f, area, popup, &ctx.colorscheme);
+    }
+
+    if let Some(popup) = &ctx.tv_state.confirm_action_popup {
+        draw_confirm_action_popup(f, area, popup, &ctx.colorscheme);
+    }
+
+    if let Some(popup) = &ctx.tv_state.action_input_popup {
+        draw_action_input_popup(f, area, popup, &ctx.colorscheme)?;
+    }
+
     // floating help panel (rendered last to appear on top)
     if let Some(help_area) = layout.help_panel {
         draw_help_panel(
@@ -301,5 +367,25 @@ pub fn draw(ctx: Ctx, f: &mut Frame<'_>, area: Rect) -> Result<Layout> {
         );
     }
 
+    // toasts over the preview (or the results when the preview is hidden)
+    draw_notifications(
+        f,
+        layout.preview_window.unwrap_or(layout.results),
+        &ctx.tv_state.notifications,
+        ctx.instant,
+        &ctx.colorscheme,
+    );
+
+    if let Some(popup) = &ctx.tv_state.notification_history_popup {
+        draw_notification_history_popup(
+            f,
+            area,
+            popup,
+            &ctx.tv_state.notifications,
+            ctx.instant,
+            &ctx.colorscheme,
+        );
+    }
+
     Ok(layout)
 }
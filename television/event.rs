@@ -5,8 +5,10 @@ use crossterm::event::{
     },
     KeyEvent, KeyEventKind, KeyModifiers, MouseEvent,
 };
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
 use serde::{Deserialize, Serialize};
 use std::{
+    borrow::Cow,
     fmt::Display,
     future::Future,
     pin::Pin,
@@ -17,11 +19,13 @@ use std::{
 use tokio::{signal, sync::mpsc};
 use tracing::{debug, trace, warn};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Event<I> {
     Closed,
     Input(I),
     Mouse(MouseEvent),
+    /// Text pasted in the terminal (with bracketed paste enabled).
+    Paste(String),
     FocusLost,
     FocusGained,
     Resize(u16, u16),
@@ -68,6 +72,269 @@ pub enum Key {
     Null,
     Esc,
     Tab,
+    /// Any combination of modifiers and key that has no dedicated variant
+    /// above (e.g. `shift-up`, `ctrl-alt-x` or `ctrl-shift-p`).
+    ///
+    /// Always build it through [`Key::new`] so that each key has a single
+    /// canonical representation.
+    Modified(Modifiers, KeyCode),
+}
+
+/// A set of keyboard modifiers.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    Serialize,
+    PartialEq,
+    PartialOrd,
+    Eq,
+    Hash,
+    Ord,
+)]
+pub struct Modifiers(u8);
+
+impl Modifiers {
+    pub const NONE: Self = Self(0);
+    pub const CTRL: Self = Self(1);
+    pub const ALT: Self = Self(1 << 1);
+    pub const SHIFT: Self = Self(1 << 2);
+    pub const SUPER: Self = Self(1 << 3);
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    #[must_use]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    #[must_use]
+    pub const fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+}
+
+impl From<KeyModifiers> for Modifiers {
+    fn from(modifiers: KeyModifiers) -> Self {
+        [
+            (KeyModifiers::CONTROL, Modifiers::CTRL),
+            (KeyModifiers::ALT, Modifiers::ALT),
+            (KeyModifiers::SHIFT, Modifiers::SHIFT),
+            (KeyModifiers::SUPER, Modifiers::SUPER),
+        ]
+        .into_iter()
+        .filter(|(raw, _)| modifiers.contains(*raw))
+        .fold(Modifiers::NONE, |acc, (_, m)| acc.union(m))
+    }
+}
+
+impl Display for Modifiers {
+    /// Writes the modifiers as dash-terminated prefixes, e.g. `ctrl-shift-`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (modifier, name) in [
+            (Modifiers::CTRL, "ctrl"),
+            (Modifiers::ALT, "alt"),
+            (Modifiers::SHIFT, "shift"),
+            (Modifiers::SUPER, "super"),
+        ] {
+            if self.contains(modifier) {
+                write!(f, "{name}-")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A key without its modifiers.
+#[derive(
+    Debug, Clone, Copy, Serialize, PartialEq, PartialOrd, Eq, Hash, Ord,
+)]
+pub enum KeyCode {
+    Backspace,
+    Enter,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Tab,
+    BackTab,
+    Delete,
+    Insert,
+    F(u8),
+    Char(char),
+    Esc,
+}
+
+impl Display for KeyCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyCode::Backspace => write!(f, "backspace"),
+            KeyCode::Enter => write!(f, "enter"),
+            KeyCode::Left => write!(f, "left"),
+            KeyCode::Right => write!(f, "right"),
+            KeyCode::Up => write!(f, "up"),
+            KeyCode::Down => write!(f, "down"),
+            KeyCode::Home => write!(f, "home"),
+            KeyCode::End => write!(f, "end"),
+            KeyCode::PageUp => write!(f, "pageup"),
+            KeyCode::PageDown => write!(f, "pagedown"),
+            KeyCode::Tab => write!(f, "tab"),
+            KeyCode::BackTab => write!(f, "backtab"),
+            KeyCode::Delete => write!(f, "delete"),
+            KeyCode::Insert => write!(f, "insert"),
+            KeyCode::F(k) => write!(f, "f{k}"),
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Esc => write!(f, "esc"),
+        }
+    }
+}
+
+impl Key {
+    /// Builds the canonical [`Key`] for a key code pressed with the given
+    /// modifiers.
+    ///
+    /// Combinations that have a dedicated variant (`Ctrl(c)`, `AltEnter`,
+    /// ...) map to that variant and everything else to [`Key::Modified`].
+    /// Shift on its own is dropped for printable keys since the character
+    /// already reflects it (`shift-a` is reported as `Char('A')`), while
+    /// `ctrl-shift-a` keeps the shift modifier and a lowercase character.
+    pub fn new(code: KeyCode, modifiers: Modifiers) -> Self {
+        let (code, modifiers) = Self::normalize(code, modifiers);
+        match (code, modifiers) {
+            (code, Modifiers::NONE) => match code {
+                KeyCode::Backspace => Key::Backspace,
+                KeyCode::Enter => Key::Enter,
+                KeyCode::Left => Key::Left,
+                KeyCode::Right => Key::Right,
+                KeyCode::Up => Key::Up,
+                KeyCode::Down => Key::Down,
+                KeyCode::Home => Key::Home,
+                KeyCode::End => Key::End,
+                KeyCode::PageUp => Key::PageUp,
+                KeyCode::PageDown => Key::PageDown,
+                KeyCode::Tab => Key::Tab,
+                KeyCode::BackTab => Key::BackTab,
+                KeyCode::Delete => Key::Delete,
+                KeyCode::Insert => Key::Insert,
+                KeyCode::F(k) => Key::F(k),
+                KeyCode::Char(c) => Key::Char(c),
+                KeyCode::Esc => Key::Esc,
+            },
+            (KeyCode::Char(' '), Modifiers::CTRL) => Key::CtrlSpace,
+            (KeyCode::Char(c), Modifiers::CTRL) => Key::Ctrl(c),
+            (KeyCode::Backspace, Modifiers::CTRL) => Key::CtrlBackspace,
+            (KeyCode::Enter, Modifiers::CTRL) => Key::CtrlEnter,
+            (KeyCode::Left, Modifiers::CTRL) => Key::CtrlLeft,
+            (KeyCode::Right, Modifiers::CTRL) => Key::CtrlRight,
+            (KeyCode::Up, Modifiers::CTRL) => Key::CtrlUp,
+            (KeyCode::Down, Modifiers::CTRL) => Key::CtrlDown,
+            (KeyCode::Delete, Modifiers::CTRL) => Key::CtrlDelete,
+            (KeyCode::Char(' '), Modifiers::ALT) => Key::AltSpace,
+            (KeyCode::Char(c), Modifiers::ALT) => Key::Alt(c),
+            (KeyCode::Enter, Modifiers::ALT) => Key::AltEnter,
+            (KeyCode::Backspace, Modifiers::ALT) => Key::AltBackspace,
+            (KeyCode::Delete, Modifiers::ALT) => Key::AltDelete,
+            (KeyCode::Up, Modifiers::ALT) => Key::AltUp,
+            (KeyCode::Down, Modifiers::ALT) => Key::AltDown,
+            (KeyCode::Left, Modifiers::ALT) => Key::AltLeft,
+            (KeyCode::Right, Modifiers::ALT) => Key::AltRight,
+            (code, modifiers) => Key::Modified(modifiers, code),
+        }
+    }
+
+    fn normalize(code: KeyCode, modifiers: Modifiers) -> (KeyCode, Modifiers) {
+        let shift = modifiers.contains(Modifiers::SHIFT);
+        let others = modifiers.difference(Modifiers::SHIFT);
+        match code {
+            // `backtab` already implies shift
+            KeyCode::BackTab => (KeyCode::BackTab, others),
+            KeyCode::Tab if shift => (KeyCode::BackTab, others),
+            // shift on its own doesn't change what these keys do, and
+            // terminals are inconsistent about reporting it
+            KeyCode::Backspace | KeyCode::Delete if others.is_empty() => {
+                (code, Modifiers::NONE)
+            }
+            // the reported character (e.g. `A` or `!`) already accounts for
+            // shift on its own
+            KeyCode::Char(c) if others.is_empty() => {
+                (KeyCode::Char(c), Modifiers::NONE)
+            }
+            KeyCode::Char(c) if c.is_alphabetic() => {
+                if shift || c.is_uppercase() {
+                    (
+                        KeyCode::Char(to_lowercase(c)),
+                        others.union(Modifiers::SHIFT),
+                    )
+                } else {
+                    (KeyCode::Char(c), others)
+                }
+            }
+            // the shifted symbol (e.g. `!`) is already what was reported
+            KeyCode::Char(c) => (KeyCode::Char(c), others),
+            code => (code, modifiers),
+        }
+    }
+
+    /// The key code and modifiers making up this key, or `None` for
+    /// [`Key::Null`].
+    pub fn parts(self) -> Option<(KeyCode, Modifiers)> {
+        let parts = match self {
+            Key::Backspace => (KeyCode::Backspace, Modifiers::NONE),
+            Key::Enter => (KeyCode::Enter, Modifiers::NONE),
+            Key::Left => (KeyCode::Left, Modifiers::NONE),
+            Key::Right => (KeyCode::Right, Modifiers::NONE),
+            Key::Up => (KeyCode::Up, Modifiers::NONE),
+            Key::Down => (KeyCode::Down, Modifiers::NONE),
+            Key::CtrlSpace => (KeyCode::Char(' '), Modifiers::CTRL),
+            Key::CtrlBackspace => (KeyCode::Backspace, Modifiers::CTRL),
+            Key::CtrlEnter => (KeyCode::Enter, Modifiers::CTRL),
+            Key::CtrlLeft => (KeyCode::Left, Modifiers::CTRL),
+            Key::CtrlRight => (KeyCode::Right, Modifiers::CTRL),
+            Key::CtrlUp => (KeyCode::Up, Modifiers::CTRL),
+            Key::CtrlDown => (KeyCode::Down, Modifiers::CTRL),
+            Key::CtrlDelete => (KeyCode::Delete, Modifiers::CTRL),
+            Key::AltSpace => (KeyCode::Char(' '), Modifiers::ALT),
+            Key::AltEnter => (KeyCode::Enter, Modifiers::ALT),
+            Key::AltBackspace => (KeyCode::Backspace, Modifiers::ALT),
+            Key::AltDelete => (KeyCode::Delete, Modifiers::ALT),
+            Key::AltUp => (KeyCode::Up, Modifiers::ALT),
+            Key::AltDown => (KeyCode::Down, Modifiers::ALT),
+            Key::AltLeft => (KeyCode::Left, Modifiers::ALT),
+            Key::AltRight => (KeyCode::Right, Modifiers::ALT),
+            Key::Home => (KeyCode::Home, Modifiers::NONE),
+            Key::End => (KeyCode::End, Modifiers::NONE),
+            Key::PageUp => (KeyCode::PageUp, Modifiers::NONE),
+            Key::PageDown => (KeyCode::PageDown, Modifiers::NONE),
+            Key::BackTab => (KeyCode::BackTab, Modifiers::NONE),
+            Key::Delete => (KeyCode::Delete, Modifiers::NONE),
+            Key::Insert => (KeyCode::Insert, Modifiers::NONE),
+            Key::F(k) => (KeyCode::F(k), Modifiers::NONE),
+            Key::Char(c) => (KeyCode::Char(c), Modifiers::NONE),
+            Key::Alt(c) => (KeyCode::Char(c), Modifiers::ALT),
+            Key::Ctrl(c) => (KeyCode::Char(c), Modifiers::CTRL),
+            Key::Esc => (KeyCode::Esc, Modifiers::NONE),
+            Key::Tab => (KeyCode::Tab, Modifiers::NONE),
+            Key::Modified(modifiers, code) => (code, modifiers),
+            Key::Null => return None,
+        };
+        Some(parts)
+    }
+}
+
+fn to_lowercase(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
 }
 
 impl<'de> Deserialize<'de> for Key {
@@ -80,6 +347,19 @@ impl<'de> Deserialize<'de> for Key {
     }
 }
 
+impl JsonSchema for Key {
+    fn schema_name() -> Cow<'static, str> {
+        "Key".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "description": "A key, optionally with modifiers, e.g. `esc`, `ctrl-k` or `alt-shift-up`",
+        })
+    }
+}
+
 impl Display for Key {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -119,6 +399,7 @@ impl Display for Key {
             Key::Null => write!(f, "null"),
             Key::Esc => write!(f, "esc"),
             Key::Tab => write!(f, "tab"),
+            Key::Modified(modifiers, code) => write!(f, "{modifiers}{code}"),
         }
     }
 }
@@ -243,12 +524,15 @@ impl EventLoop {
                         let maybe_event = crossterm::event::read();
                         match maybe_event {
                             Ok(crossterm::event::Event::Key(key)) => {
-                                let key = convert_raw_event_to_key(key);
+                                let key = Key::from_event(key);
                                 tx.send(Event::Input(key)).unwrap_or_else(|_| warn!("Unable to send {:?} event", key));
                             },
                             Ok(crossterm::event::Event::Mouse(mouse)) => {
                                 tx.send(Event::Mouse(mouse)).unwrap_or_else(|_| warn!("Unable to send Mouse event"));
                             },
+                            Ok(crossterm::event::Event::Paste(text)) => {
+                                tx.send(Event::Paste(text)).unwrap_or_else(|_| warn!("Unable to send Paste event"));
+                            },
                             Ok(crossterm::event::Event::FocusLost) => {
                                 tx.send(Event::FocusLost).unwrap_or_else(|_| warn!("Unable to send FocusLost event"));
                             },
@@ -311,6 +595,9 @@ fn flush_resize_events(first_resize: (u16, u16)) -> ((u16, u16), (u16, u16)) {
 /// - Key release events are ignored (return `Key::Null`)
 /// - Special keys are mapped directly (e.g., `Enter` → `Key::Enter`)
 /// - Function keys preserve their number (e.g., `F1` → `Key::F(1)`)
+/// - Special keys pressed with a combination of modifiers that has no
+///   dedicated variant are mapped to the plain key (e.g., `Shift+Enter` →
+///   `Key::Enter`), see [`Key::from_event`] to keep these modifiers
 ///
 /// # Examples
 ///
@@ -325,68 +612,54 @@ fn flush_resize_events(first_resize: (u16, u16)) -> ((u16, u16), (u16, u16)) {
 /// assert_eq!(convert_raw_event_to_key(event), Key::Enter);
 /// ```
 pub fn convert_raw_event_to_key(event: KeyEvent) -> Key {
-    trace!("Raw event: {:?}", event);
-    if event.kind == KeyEventKind::Release {
-        return Key::Null;
+    let key = Key::from_event(event);
+    key.fallback().unwrap_or(key)
+}
+
+impl Key {
+    /// Builds the key for a crossterm `KeyEvent`, keeping all of its
+    /// modifiers (e.g., `Shift+Enter` → `Key::Modified(SHIFT, Enter)`).
+    ///
+    /// Returns `Key::Null` for key releases and unsupported keys.
+    pub fn from_event(event: KeyEvent) -> Self {
+        trace!("Raw event: {:?}", event);
+        if event.kind == KeyEventKind::Release {
+            return Key::Null;
+        }
+        let code = match event.code {
+            Backspace => KeyCode::Backspace,
+            Delete => KeyCode::Delete,
+            Enter => KeyCode::Enter,
+            Up => KeyCode::Up,
+            Down => KeyCode::Down,
+            Left => KeyCode::Left,
+            Right => KeyCode::Right,
+            Home => KeyCode::Home,
+            End => KeyCode::End,
+            PageUp => KeyCode::PageUp,
+            PageDown => KeyCode::PageDown,
+            Tab => KeyCode::Tab,
+            BackTab => KeyCode::BackTab,
+            Insert => KeyCode::Insert,
+            F(k) => KeyCode::F(k),
+            Esc => KeyCode::Esc,
+            Char(c) => KeyCode::Char(c),
+            _ => return Key::Null,
+        };
+        Key::new(code, event.modifiers.into())
     }
-    match event.code {
-        Backspace => match event.modifiers {
-            KeyModifiers::CONTROL => Key::CtrlBackspace,
-            KeyModifiers::ALT => Key::AltBackspace,
-            _ => Key::Backspace,
-        },
-        Delete => match event.modifiers {
-            KeyModifiers::CONTROL => Key::CtrlDelete,
-            KeyModifiers::ALT => Key::AltDelete,
-            _ => Key::Delete,
-        },
-        Enter => match event.modifiers {
-            KeyModifiers::CONTROL => Key::CtrlEnter,
-            KeyModifiers::ALT => Key::AltEnter,
-            _ => Key::Enter,
-        },
-        Up => match event.modifiers {
-            KeyModifiers::CONTROL => Key::CtrlUp,
-            KeyModifiers::ALT => Key::AltUp,
-            _ => Key::Up,
-        },
-        Down => match event.modifiers {
-            KeyModifiers::CONTROL => Key::CtrlDown,
-            KeyModifiers::ALT => Key::AltDown,
-            _ => Key::Down,
-        },
-        Left => match event.modifiers {
-            KeyModifiers::CONTROL => Key::CtrlLeft,
-            KeyModifiers::ALT => Key::AltLeft,
-            _ => Key::Left,
-        },
-        Right => match event.modifiers {
-            KeyModifiers::CONTROL => Key::CtrlRight,
-            KeyModifiers::ALT => Key::AltRight,
-            _ => Key::Right,
-        },
-        Home => Key::Home,
-        End => Key::End,
-        PageUp => Key::PageUp,
-        PageDown => Key::PageDown,
-        Tab => Key::Tab,
-        BackTab => Key::BackTab,
-        Insert => Key::Insert,
-        F(k) => Key::F(k),
-        Esc => Key::Esc,
-        Char(' ') => match event.modifiers {
-            KeyModifiers::NONE | KeyModifiers::SHIFT => Key::Char(' '),
-            KeyModifiers::CONTROL => Key::CtrlSpace,
-            KeyModifiers::ALT => Key::AltSpace,
-            _ => Key::Null,
-        },
-        Char(c) => match event.modifiers {
-            KeyModifiers::NONE | KeyModifiers::SHIFT => Key::Char(c),
-            KeyModifiers::CONTROL => Key::Ctrl(c),
-            KeyModifiers::ALT => Key::Alt(c),
-            _ => Key::Null,
-        },
-        _ => Key::Null,
+
+    /// The key to use instead of this one when it isn't bound.
+    ///
+    /// Special keys pressed with modifiers that have no dedicated variant
+    /// fall back to the plain key, so that e.g. `shift-enter` still confirms
+    /// the selection unless it is bound to something else.
+    pub fn fallback(self) -> Option<Self> {
+        match self {
+            Key::Modified(_, KeyCode::Char(_)) => None,
+            Key::Modified(_, code) => Some(Key::new(code, Modifiers::NONE)),
+            _ => None,
+        }
     }
 }
 
@@ -579,4 +852,147 @@ mod tests {
 
         assert_eq!(convert_raw_event_to_key(event), Key::Up);
     }
+
+    #[test]
+    fn test_convert_raw_event_to_key_with_multiple_modifiers() {
+        let key =
+            |code, modifiers| Key::from_event(KeyEvent::new(code, modifiers));
+
+        assert_eq!(
+            key(
+                KeyCode::Char('p'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            ),
+            Key::Modified(
+                Modifiers::CTRL.union(Modifiers::SHIFT),
+                super::KeyCode::Char('p')
+            )
+        );
+        // terminals report the shifted character, with or without the
+        // shift modifier
+        assert_eq!(
+            key(
+                KeyCode::Char('P'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            ),
+            key(
+                KeyCode::Char('p'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            ),
+        );
+        assert_eq!(
+            key(KeyCode::Char('P'), KeyModifiers::CONTROL),
+            key(
+                KeyCode::Char('p'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            ),
+        );
+        assert_eq!(
+            key(KeyCode::Char('!'), KeyModifiers::SHIFT),
+            Key::Char('!')
+        );
+        assert_eq!(
+            key(
+                KeyCode::Char('x'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            ),
+            Key::Modified(
+                Modifiers::CTRL.union(Modifiers::ALT),
+                super::KeyCode::Char('x')
+            )
+        );
+        assert_eq!(
+            key(KeyCode::Up, KeyModifiers::SHIFT),
+            Key::Modified(Modifiers::SHIFT, super::KeyCode::Up)
+        );
+        assert_eq!(
+            key(KeyCode::Tab, KeyModifiers::CONTROL),
+            Key::Modified(Modifiers::CTRL, super::KeyCode::Tab)
+        );
+        assert_eq!(key(KeyCode::Tab, KeyModifiers::SHIFT), Key::BackTab);
+        assert_eq!(key(KeyCode::BackTab, KeyModifiers::SHIFT), Key::BackTab);
+        assert_eq!(
+            key(KeyCode::Char('i'), KeyModifiers::CONTROL),
+            Key::Ctrl('i')
+        );
+        assert_eq!(
+            key(KeyCode::Char('k'), KeyModifiers::SUPER),
+            Key::Modified(Modifiers::SUPER, super::KeyCode::Char('k'))
+        );
+    }
+
+    #[test]
+    fn test_key_display_round_trip() {
+        for raw in [
+            "a",
+            "G",
+            "ctrl-a",
+            "alt-enter",
+            "ctrl-del",
+            "shift-enter",
+            "shift-up",
+            "ctrl-tab",
+            "ctrl-shift-p",
+            "ctrl-alt-x",
+            "super-k",
+            "ctrl-space",
+            "f5",
+            "backtab",
+        ] {
+            let key = Key::from_str(raw).unwrap();
+            assert_eq!(key.to_string(), raw);
+            assert_eq!(Key::from_str(&key.to_string()).unwrap(), key);
+        }
+    }
+
+    #[test]
+    fn test_key_fallback() {
+        let key =
+            |code, modifiers| Key::from_event(KeyEvent::new(code, modifiers));
+
+        assert_eq!(
+            key(KeyCode::Enter, KeyModifiers::SHIFT).fallback(),
+            Some(Key::Enter)
+        );
+        assert_eq!(
+            key(KeyCode::Up, KeyModifiers::CONTROL | KeyModifiers::SHIFT)
+                .fallback(),
+            Some(Key::Up)
+        );
+        assert_eq!(
+            convert_raw_event_to_key(KeyEvent::new(
+                KeyCode::Down,
+                KeyModifiers::SHIFT
+            )),
+            Key::Down
+        );
+        // keys with a dedicated variant and characters don't fall back
+        assert_eq!(
+            key(KeyCode::Enter, KeyModifiers::CONTROL).fallback(),
+            None
+        );
+        assert_eq!(
+            key(
+                KeyCode::Char('p'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            )
+            .fallback(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_key_new_matches_parts() {
+        for key in [
+            Key::Ctrl('a'),
+            Key::CtrlUp,
+            Key::AltSpace,
+            Key::Char('G'),
+            Key::Modified(Modifiers::SHIFT, super::KeyCode::Enter),
+        ] {
+            let (code, modifiers) = key.parts().unwrap();
+            assert_eq!(Key::new(code, modifiers), key);
+        }
+        assert_eq!(Key::Null.parts(), None);
+    }
 }
@@ -4,11 +4,15 @@
 //! entries rank higher than never-selected entries, with more recently selected
 //! entries ranking higher within frecency items.
 
-use anyhow::{Context, Result};
-use parking_lot::RwLock;
+use crate::{
+    channels::prototypes::Template, config::FrecencyConfig, storage::JsonStore,
+};
+use anyhow::Result;
+use parking_lot::{Mutex, RwLock};
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
-use std::path::{Path, PathBuf};
+use std::borrow::Cow;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::debug;
@@ -22,6 +26,9 @@ pub struct FrecencyRecord {
     pub last_access: u64,
     /// Number of times this entry has been selected.
     pub access_count: u32,
+    /// Pinned entries rank above all others and are never pruned.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
 }
 
 impl FrecencyRecord {
@@ -31,36 +38,48 @@ impl FrecencyRecord {
             raw,
             last_access: current_timestamp(),
             access_count: 1,
+            pinned: false,
         }
     }
 
     /// Record a new access, updating timestamp and incrementing count.
     pub fn record_access(&mut self) {
-        self.last_access = current_timestamp();
+        self.record_access_at(current_timestamp());
+    }
+
+    fn record_access_at(&mut self, timestamp: u64) {
+        self.last_access = self.last_access.max(timestamp);
         self.access_count = self.access_count.saturating_add(1);
     }
 
     /// Calculate the frecency score for this record.
     ///
-    /// Uses Mozilla-style time-decay buckets combined with access count.
-    /// Higher scores indicate more relevant items.
-    pub fn score(&self, now: u64) -> u64 {
+    /// Combines time-decay buckets (Mozilla-style by default) with the
+    /// access count. Higher scores indicate more relevant items, and pinned
+    /// items score higher than any unpinned one.
+    pub fn score(&self, config: &FrecencyConfig, now: u64) -> u64 {
         let age_hours = now.saturating_sub(self.last_access) / 3600;
-
-        // Time-decay buckets (Mozilla-style)
-        let recency_weight = match age_hours {
-            0..=4 => 100,    // Last 4 hours
-            5..=24 => 70,    // Last day
-            25..=168 => 50,  // Last week
-            169..=720 => 30, // Last month
-            _ => 10,         // Older
-        };
+        let recency_weight = config.recency_weight(age_hours);
 
         // Cap access count contribution to prevent runaway scores
-        let count_factor = u64::from(self.access_count).min(20);
+        let count_factor = u64::from(self.access_count.min(config.max_count));
 
-        // Max score: 100 * 20 = 2000
-        recency_weight * count_factor
+        let score = recency_weight.saturating_mul(count_factor);
+        if self.pinned {
+            score.saturating_add(config.max_score() + 1)
+        } else {
+            score
+        }
+    }
+
+    /// Merge a record for the same entry, e.g. from another machine.
+    ///
+    /// Access counts aren't summed so that merging the same data twice
+    /// doesn't inflate them.
+    fn merge(&mut self, other: &FrecencyRecord) {
+        self.last_access = self.last_access.max(other.last_access);
+        self.access_count = self.access_count.max(other.access_count);
+        self.pinned |= other.pinned;
     }
 }
 
@@ -71,14 +90,160 @@ pub struct FrecencyData {
     pub channels: FxHashMap<String, FxHashMap<String, FrecencyRecord>>,
 }
 
+impl FrecencyData {
+    /// Record an access, pruning the channel's entries if it goes over
+    /// `max_entries`.
+    fn record_access(
+        &mut self,
+        access: &Access,
+        max_entries: usize,
+        config: &FrecencyConfig,
+    ) {
+        let entries = self.channels.entry(access.channel.clone()).or_default();
+        if let Some(record) = entries.get_mut(&access.raw) {
+            record.record_access_at(access.timestamp);
+        } else {
+            entries.insert(
+                access.raw.clone(),
+                FrecencyRecord {
+                    raw: access.raw.clone(),
+                    last_access: access.timestamp,
+                    access_count: 1,
+                    pinned: false,
+                },
+            );
+            if entries.len() > max_entries {
+                prune_channel_entries(entries, max_entries, config);
+            }
+        }
+    }
+
+    /// The records of a channel, highest scores first.
+    pub fn ranked(
+        &self,
+        channel_name: &str,
+        config: &FrecencyConfig,
+        now: u64,
+    ) -> Vec<&FrecencyRecord> {
+        let mut records: Vec<_> = self
+            .channels
+            .get(channel_name)
+            .map(|entries| entries.values().collect())
+            .unwrap_or_default();
+        records.sort_by(|r1, r2| {
+            r2.score(config, now)
+                .cmp(&r1.score(config, now))
+                .then_with(|| r2.last_access.cmp(&r1.last_access))
+                .then_with(|| r1.raw.cmp(&r2.raw))
+        });
+        records
+    }
+
+    /// Remove an entry, returning whether it existed.
+    pub fn remove(&mut self, channel_name: &str, raw: &str) -> bool {
+        let Some(entries) = self.channels.get_mut(channel_name) else {
+            return false;
+        };
+        let removed = entries.remove(raw).is_some();
+        if entries.is_empty() {
+            self.channels.remove(channel_name);
+        }
+        removed
+    }
+
+    /// Pin or unpin an entry.
+    ///
+    /// Pinning an entry that was never selected creates a record for it,
+    /// which is removed again when it gets unpinned.
+    pub fn set_pinned(&mut self, channel_name: &str, raw: &str, pinned: bool) {
+        let entries =
+            self.channels.entry(channel_name.to_string()).or_default();
+        match entries.get_mut(raw) {
+            Some(record) if !pinned && record.access_count == 0 => {
+                entries.remove(raw);
+            }
+            Some(record) => record.pinned = pinned,
+            None if pinned => {
+                entries.insert(
+                    raw.to_string(),
+                    FrecencyRecord {
+                        raw: raw.to_string(),
+                        last_access: current_timestamp(),
+                        access_count: 0,
+                        pinned: true,
+                    },
+                );
+            }
+            None => {}
+        }
+        if entries.is_empty() {
+            self.channels.remove(channel_name);
+        }
+    }
+
+    /// Remove the unpinned entries matching `predicate`, in a single channel
+    /// or in all of them. Returns the number of removed entries.
+    pub fn prune(
+        &mut self,
+        channel_name: Option<&str>,
+        predicate: impl Fn(&FrecencyRecord) -> bool,
+    ) -> usize {
+        let mut removed = 0;
+        for (name, entries) in &mut self.channels {
+            if channel_name.is_some_and(|c| c != name) {
+                continue;
+            }
+            let before = entries.len();
+            entries.retain(|_, record| record.pinned || !predicate(record));
+            removed += before - entries.len();
+        }
+        self.channels.retain(|_, entries| !entries.is_empty());
+        removed
+    }
+
+    /// Merge data from another source, e.g. an export from another machine.
+    pub fn merge(
+        &mut self,
+        other: FrecencyData,
+        max_entries: usize,
+        config: &FrecencyConfig,
+    ) {
+        for (channel_name, records) in other.channels {
+            let entries = self.channels.entry(channel_name).or_default();
+            for (raw, record) in records {
+                match entries.get_mut(&raw) {
+                    Some(existing) => existing.merge(&record),
+                    None => {
+                        entries.insert(raw, record);
+                    }
+                }
+            }
+            prune_channel_entries(entries, max_entries, config);
+        }
+    }
+}
+
+/// An access recorded since the data was last saved.
+#[derive(Debug, Clone)]
+struct Access {
+    channel: String,
+    raw: String,
+    timestamp: u64,
+}
+
 /// Thread-safe frecency manager.
 pub struct Frecency {
     /// The frecency data, protected by a read-write lock.
     data: RwLock<FrecencyData>,
-    /// Path to the persistence file.
-    file_path: PathBuf,
+    /// The persistence file, shared with other tv instances.
+    store: JsonStore,
+    /// Accesses not saved yet, which are replayed onto the latest content of
+    /// the file when saving.
+    pending: Mutex<Vec<Access>>,
     /// Maximum number of entries to keep per channel.
     max_entries_per_channel: usize,
+    /// How scores are computed and used to sort results.
+    config: FrecencyConfig,
 }
 
 /// A handle to the frecency manager, shareable across threads.
@@ -129,6 +294,51 @@ impl FrecencyCache {
 }
 pub type FrecencyCacheHandle = Arc<FrecencyCache>;
 
+/// The frecency table a channel ranks its results with and records
+/// selections into.
+#[derive(Clone)]
+pub struct ChannelFrecency {
+    pub handle: FrecencyHandle,
+    /// The name of the table, the channel's name unless it shares its table
+    /// with other channels (see `source.frecency_namespace`).
+    pub namespace: String,
+    /// Template mapping an entry to its key in the table, the raw entry
+    /// being used when unset.
+    pub key: Option<Template>,
+}
+
+impl ChannelFrecency {
+    pub fn new(handle: FrecencyHandle, namespace: String) -> Self {
+        Self {
+            handle,
+            namespace,
+            key: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_key(mut self, key: Option<Template>) -> Self {
+        self.key = key;
+        self
+    }
+
+    /// The key of an entry in the table, or `None` if the key template
+    /// can't be applied to it.
+    pub fn key<'a>(&self, raw: &'a str) -> Option<Cow<'a, str>> {
+        match &self.key {
+            Some(template) => template.format(raw).ok().map(Cow::Owned),
+            None => Some(Cow::Borrowed(raw)),
+        }
+    }
+
+    /// Record that an entry was selected.
+    pub fn record_access(&self, raw: &str) {
+        if let Some(key) = self.key(raw) {
+            self.handle.record_access(&self.namespace, &key);
+        }
+    }
+}
+
 const FRECENCY_FILE_NAME: &str = "frecency.json";
 
 impl Frecency {
@@ -140,11 +350,23 @@ impl Frecency {
     pub fn new(max_entries_per_channel: usize, data_dir: &Path) -> Self {
         Self {
             data: RwLock::new(FrecencyData::default()),
-            file_path: data_dir.join(FRECENCY_FILE_NAME),
+            store: JsonStore::new(data_dir.join(FRECENCY_FILE_NAME)),
+            pending: Mutex::new(Vec::new()),
             max_entries_per_channel,
+            config: FrecencyConfig::default(),
         }
     }
 
+    #[must_use]
+    pub fn with_config(mut self, config: FrecencyConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn config(&self) -> &FrecencyConfig {
+        &self.config
+    }
+
     /// Initialize the frecency manager by loading data from disk.
     pub fn init(&self) -> Result<()> {
         self.load_from_file()
@@ -152,42 +374,49 @@ impl Frecency {
 
     /// Load frecency data from file.
     fn load_from_file(&self) -> Result<()> {
-        if !self.file_path.exists() {
-            debug!(
-                "Frecency file does not exist at {:?}, starting fresh",
-                self.file_path
-            );
-            return Ok(());
-        }
-
-        let contents = std::fs::read_to_string(&self.file_path)
-            .context("Failed to read frecency file")?;
-
-        let data: FrecencyData = serde_json::from_str(&contents)
-            .context("Failed to parse frecency file")?;
-
+        let data: FrecencyData = self.store.load()?;
         debug!("Loaded frecency data with {} channels", data.channels.len());
-
         *self.data.write() = data;
         Ok(())
     }
 
-    /// Save frecency data to file.
+    /// Save the accesses recorded by this instance to file.
+    ///
+    /// The accesses are merged into the latest content of the file, so that
+    /// concurrent tv instances don't overwrite each other's data, and the
+    /// in-memory data is refreshed with the result.
     pub fn save_to_file(&self) -> Result<()> {
-        let data = self.data.read();
-        let contents = serde_json::to_string_pretty(&*data)
-            .context("Failed to serialize frecency data")?;
-
-        // Ensure parent directory exists
-        if let Some(parent) = self.file_path.parent() {
-            std::fs::create_dir_all(parent)
-                .context("Failed to create frecency directory")?;
+        let mut pending = self.pending.lock();
+        if pending.is_empty() {
+            return Ok(());
         }
+        let data = self.store.update(|data: &mut FrecencyData| {
+            for access in pending.iter() {
+                data.record_access(
+                    access,
+                    self.max_entries_per_channel,
+                    &self.config,
+                );
+            }
+        })?;
+        *self.data.write() = data;
+        pending.clear();
+        Ok(())
+    }
 
-        std::fs::write(&self.file_path, contents)
-            .context("Failed to write frecency file")?;
+    /// A copy of the frecency data.
+    pub fn data(&self) -> FrecencyData {
+        self.data.read().clone()
+    }
 
-        debug!("Saved frecency data to {:?}", self.file_path);
+    /// Apply `f` to the data saved on disk, e.g. to edit it from the command
+    /// line, and refresh the in-memory data with the result.
+    pub fn update_file(
+        &self,
+        f: impl FnOnce(&mut FrecencyData),
+    ) -> Result<()> {
+        let data = self.store.update(f)?;
+        *self.data.write() = data;
         Ok(())
     }
 
@@ -197,29 +426,21 @@ impl Frecency {
     /// * `channel_name` - The name of the channel
     /// * `raw` - The raw entry string (canonical key)
     pub fn record_access(&self, channel_name: &str, raw: &str) {
-        let mut data = self.data.write();
-        let channel_entries =
-            data.channels.entry(channel_name.to_string()).or_default();
-
-        if let Some(record) = channel_entries.get_mut(raw) {
-            record.record_access();
-            debug!(
-                "Updated frecency for '{}' in channel '{}': count={}",
-                raw, channel_name, record.access_count
-            );
-        } else {
-            channel_entries
-                .insert(raw.to_string(), FrecencyRecord::new(raw.to_string()));
-            debug!(
-                "Created frecency record for '{}' in channel '{}'",
-                raw, channel_name
-            );
-
-            // Prune if over limit
-            if channel_entries.len() > self.max_entries_per_channel {
-                self.prune_channel_entries(channel_entries);
-            }
-        }
+        let access = Access {
+            channel: channel_name.to_string(),
+            raw: raw.to_string(),
+            timestamp: current_timestamp(),
+        };
+        self.data.write().record_access(
+            &access,
+            self.max_entries_per_channel,
+            &self.config,
+        );
+        debug!(
+            "Recorded frecency for '{}' in channel '{}'",
+            raw, channel_name
+        );
+        self.pending.lock().push(access);
     }
 
     /// Get the frecency score for an entry, if it exists.
@@ -241,7 +462,7 @@ impl Frecency {
         data.channels
             .get(channel_name)
             .and_then(|entries| entries.get(raw))
-            .map(|record| record.score(now))
+            .map(|record| record.score(&self.config, now))
     }
 
     pub fn get_channel_scores(&self, channel_name: &str) -> FrecencyScores {
@@ -253,7 +474,9 @@ impl Frecency {
             .map(|entries| {
                 entries
                     .iter()
-                    .map(|(key, record)| (key.clone(), record.score(now)))
+                    .map(|(key, record)| {
+                        (key.clone(), record.score(&self.config, now))
+                    })
                     .collect()
             })
             .unwrap_or_default();
@@ -267,40 +490,37 @@ impl Frecency {
         cache.refresh(self);
         cache
     }
+}
 
-    /// Prune the oldest entries from a channel to stay within limits.
-    fn prune_channel_entries(
-        &self,
-        entries: &mut FxHashMap<String, FrecencyRecord>,
-    ) {
-        // Calculate how many to remove
-        let excess =
-            entries.len().saturating_sub(self.max_entries_per_channel);
-        if excess == 0 {
-            return;
-        }
-
-        // Find the entries with the lowest scores
-        let now = current_timestamp();
-        let mut scores: Vec<_> = entries
-            .iter()
-            .map(|(key, record)| (key.clone(), record.score(now)))
-            .collect();
+/// Prune the lowest-scored entries of a channel to stay within limits.
+fn prune_channel_entries(
+    entries: &mut FxHashMap<String, FrecencyRecord>,
+    max_entries: usize,
+    config: &FrecencyConfig,
+) {
+    // Calculate how many to remove
+    let excess = entries.len().saturating_sub(max_entries);
+    if excess == 0 {
+        return;
+    }
 
-        // Sort by score ascending (lowest first)
-        scores.sort_by_key(|(_, score)| *score);
+    // Find the entries with the lowest scores
+    let now = current_timestamp();
+    let mut scores: Vec<_> = entries
+        .iter()
+        .filter(|(_, record)| !record.pinned)
+        .map(|(key, record)| (key.clone(), record.score(config, now)))
+        .collect();
 
-        // Remove the lowest-scored entries
-        for (key, _) in scores.into_iter().take(excess) {
-            entries.remove(&key);
-        }
+    // Sort by score ascending (lowest first)
+    scores.sort_by_key(|(_, score)| *score);
 
-        debug!(
-            "Pruned {} frecency entries, {} remaining",
-            excess,
-            entries.len()
-        );
+    // Remove the lowest-scored entries
+    for (key, _) in scores.into_iter().take(excess) {
+        entries.remove(&key);
     }
+
+    debug!("Pruned frecency entries, {} remaining", entries.len());
 }
 
 /// Get the current Unix timestamp in seconds.
@@ -344,10 +564,11 @@ mod tests {
             raw: "test".to_string(),
             last_access: now,
             access_count: 5,
+            pinned: false,
         };
 
         // Recent (within 4 hours): 100 * 5 = 500
-        let score = record.score(now);
+        let score = record.score(&FrecencyConfig::default(), now);
         assert_eq!(score, 500);
     }
 
@@ -359,10 +580,11 @@ mod tests {
             raw: "test".to_string(),
             last_access: one_week_ago,
             access_count: 10,
+            pinned: false,
         };
 
         // Within last week: 50 * 10 = 500
-        let score = record.score(now);
+        let score = record.score(&FrecencyConfig::default(), now);
         assert_eq!(score, 500);
     }
 
@@ -374,10 +596,11 @@ mod tests {
             raw: "test".to_string(),
             last_access: one_year_ago,
             access_count: 20,
+            pinned: false,
         };
 
         // Very old: 10 * 20 = 200
-        let score = record.score(now);
+        let score = record.score(&FrecencyConfig::default(), now);
         assert_eq!(score, 200);
     }
 
@@ -388,10 +611,11 @@ mod tests {
             raw: "test".to_string(),
             last_access: now,
             access_count: 100, // Should be capped to 20
+            pinned: false,
         };
 
         // Recent with capped count: 100 * 20 = 2000
-        let score = record.score(now);
+        let score = record.score(&FrecencyConfig::default(), now);
         assert_eq!(score, 2000);
     }
 
@@ -499,6 +723,73 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_frecency_concurrent_instances() {
+        let dir = tempdir().unwrap();
+        let first = Frecency::new(100, dir.path());
+        let second = Frecency::new(100, dir.path());
+        first.init().unwrap();
+        second.init().unwrap();
+
+        first.record_access("files", "a.rs");
+        second.record_access("files", "a.rs");
+        second.record_access("files", "b.rs");
+        first.save_to_file().unwrap();
+        second.save_to_file().unwrap();
+        // nothing left to save, the file must not be rewritten with `first`'s
+        // stale data
+        first.save_to_file().unwrap();
+
+        let frecency = Frecency::new(100, dir.path());
+        frecency.init().unwrap();
+        let data = frecency.data.read();
+        let entries = data.channels.get("files").unwrap();
+        assert_eq!(entries["a.rs"].access_count, 2);
+        assert_eq!(entries["b.rs"].access_count, 1);
+        // saving refreshes the in-memory data with other instances' accesses
+        assert_eq!(
+            second.data.read().channels["files"]["a.rs"].access_count,
+            2
+        );
+    }
+
+    #[test]
+    fn test_frecency_data_editing() {
+        let dir = tempdir().unwrap();
+        let frecency = Frecency::new(100, dir.path());
+        frecency.record_access("files", "a");
+        frecency.record_access("files", "b");
+        frecency.record_access("files", "b");
+        frecency.save_to_file().unwrap();
+
+        frecency
+            .update_file(|data| {
+                data.set_pinned("files", "a", true);
+                data.set_pinned("files", "c", true);
+            })
+            .unwrap();
+        let now = current_timestamp();
+        let data = frecency.data();
+        let ranked: Vec<_> = data
+            .ranked("files", frecency.config(), now)
+            .iter()
+            .map(|r| r.raw.as_str())
+            .collect();
+        assert_eq!(ranked, ["a", "c", "b"]);
+
+        // pinned entries are never pruned, and unpinning an entry that was
+        // never selected forgets it
+        let mut data = frecency.data();
+        assert_eq!(data.prune(None, |_| true), 1);
+        data.set_pinned("files", "c", false);
+        assert_eq!(data.ranked("files", frecency.config(), now).len(), 1);
+
+        // merging the same data twice doesn't inflate counts
+        let mut merged = frecency.data();
+        merged.merge(frecency.data(), 100, frecency.config());
+        assert_eq!(merged.channels["files"]["b"].access_count, 2);
+    }
+
     #[test]
     fn test_frecency_pruning() {
         let dir = tempdir().unwrap();
@@ -1,7 +1,8 @@
+use crate::storage::JsonStore;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::{
-    path::{Path, PathBuf},
+    path::Path,
     time::{SystemTime, UNIX_EPOCH},
 };
 use tracing::debug;
@@ -20,6 +21,9 @@ pub struct HistoryEntry {
     pub channel: String,
     /// The timestamp of the entry
     pub timestamp: u64,
+    /// The raw entries that were picked with this query
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub selected: Vec<String>,
 }
 
 impl PartialEq for HistoryEntry {
@@ -40,8 +44,15 @@ impl HistoryEntry {
             query: entry,
             channel,
             timestamp,
+            selected: Vec::new(),
         }
     }
+
+    #[must_use]
+    pub fn with_selected(mut self, selected: Vec<String>) -> Self {
+        self.selected = selected;
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -49,7 +60,10 @@ pub struct History {
     entries: Vec<HistoryEntry>,
     current_index: Option<usize>,
     max_size: usize,
-    file_path: PathBuf,
+    /// The persistence file, shared with other tv instances.
+    store: JsonStore,
+    /// Entries added since the history was last saved.
+    pending: Vec<HistoryEntry>,
     current_channel: String,
     global_mode: bool,
 }
@@ -61,13 +75,12 @@ impl History {
         global_mode: bool,
         data_dir: &Path,
     ) -> Self {
-        let file_path = data_dir.join(HISTORY_FILE_NAME);
-
         Self {
             entries: Vec::with_capacity(max_size),
             current_index: None,
             max_size,
-            file_path,
+            store: JsonStore::new(data_dir.join(HISTORY_FILE_NAME)),
+            pending: Vec::new(),
             current_channel: channel_name.to_string(),
             global_mode,
         }
@@ -84,6 +97,20 @@ impl History {
 
     /// Add a new history entry, if it's not a duplicate.
     pub fn add_entry(&mut self, query: String, channel: String) -> Result<()> {
+        self.add_selection(query, channel, Vec::new())
+    }
+
+    /// Add a new history entry recording the entries that were picked with
+    /// `query`.
+    ///
+    /// Picking again with the same query as the last entry only updates that
+    /// entry's selection.
+    pub fn add_selection(
+        &mut self,
+        query: String,
+        channel: String,
+        selected: Vec<String>,
+    ) -> Result<()> {
         if self.max_size == 0 {
             return Ok(());
         }
@@ -93,10 +120,15 @@ impl History {
         }
 
         // Don't add duplicate consecutive queries
-        if let Some(last_entry) = self.entries.last()
+        if let Some(last_entry) = self.entries.last_mut()
             && last_entry.query == query
             && last_entry.channel == channel
         {
+            if !selected.is_empty() && last_entry.selected != selected {
+                *last_entry =
+                    HistoryEntry::new(query, channel).with_selected(selected);
+                self.pending.push(last_entry.clone());
+            }
             return Ok(());
         }
 
@@ -105,8 +137,10 @@ impl History {
             self.entries.drain(0..=self.entries.len() - self.max_size);
         }
 
-        let history_entry = HistoryEntry::new(query, channel);
-        self.entries.push(history_entry);
+        let history_entry =
+            HistoryEntry::new(query, channel).with_selected(selected);
+        self.entries.push(history_entry.clone());
+        self.pending.push(history_entry);
 
         // Reset current index when adding new entry
         self.current_index = None;
@@ -167,19 +201,7 @@ impl History {
     }
 
     fn load_from_file(&mut self) -> Result<()> {
-        if !self.file_path.exists() {
-            debug!("History file not found: {}", self.file_path.display());
-            return Ok(());
-        }
-
-        let content = std::fs::read_to_string(&self.file_path)?;
-        if content.trim().is_empty() {
-            debug!("History file is empty: {}", self.file_path.display());
-            return Ok(());
-        }
-
-        let mut loaded_entries: Vec<HistoryEntry> =
-            serde_json::from_str(&content)?;
+        let mut loaded_entries: Vec<HistoryEntry> = self.store.load()?;
 
         // Keep only the most recent entries if file is too large
         if loaded_entries.len() > self.max_size {
@@ -190,17 +212,44 @@ impl History {
         Ok(())
     }
 
-    pub fn save_to_file(&self) -> Result<()> {
+    /// Save the entries added by this instance to file.
+    ///
+    /// The entries are merged into the latest content of the file, so that
+    /// concurrent tv instances don't overwrite each other's history, and the
+    /// in-memory history is refreshed with the result.
+    pub fn save_to_file(&mut self) -> Result<()> {
         if self.max_size == 0 {
             debug!("History is disabled, not saving to file.");
             return Ok(());
         }
-        if let Some(parent) = self.file_path.parent() {
-            std::fs::create_dir_all(parent)?;
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let pending = std::mem::take(&mut self.pending);
+        let result =
+            self.update_file(|entries| merge_entries(entries, &pending));
+        if result.is_err() {
+            self.pending = pending;
         }
+        result
+    }
 
-        let json_content = serde_json::to_string_pretty(&self.entries)?;
-        std::fs::write(&self.file_path, json_content)?;
+    /// Apply `f` to the entries saved on disk, e.g. to edit them from the
+    /// command line, and refresh the in-memory history with the result.
+    pub fn update_file(
+        &mut self,
+        f: impl FnOnce(&mut Vec<HistoryEntry>),
+    ) -> Result<()> {
+        let max_size = self.max_size;
+        self.entries =
+            self.store.update(|entries: &mut Vec<HistoryEntry>| {
+                f(entries);
+                if max_size > 0 && entries.len() > max_size {
+                    entries.drain(0..entries.len() - max_size);
+                }
+            })?;
+        self.current_index = None;
         Ok(())
     }
 
@@ -231,6 +280,23 @@ impl History {
     }
 }
 
+/// Merge `other` into `entries` in chronological order, collapsing
+/// consecutive duplicates into the most recent of them.
+pub fn merge_entries(entries: &mut Vec<HistoryEntry>, other: &[HistoryEntry]) {
+    entries.extend_from_slice(other);
+    // the sort is stable, so entries made within the same second keep their
+    // order
+    entries.sort_by_key(|entry| entry.timestamp);
+    entries.dedup_by(|later, earlier| {
+        let duplicate = later == earlier;
+        if duplicate {
+            // keep the latest selection
+            std::mem::swap(later, earlier);
+        }
+        duplicate
+    });
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -529,6 +595,63 @@ mod tests {
         assert!(hist.is_empty());
     }
 
+    /// A history file that can't be parsed is moved aside rather than
+    /// preventing tv from starting.
+    #[test]
+    fn init_from_corrupt_file() {
+        let dir = tempdir().expect("failed to create tempdir");
+        fs::write(dir.path().join("history.json"), "[{\"query\": ").unwrap();
+
+        let mut hist = History::new(10, "files", false, dir.path());
+        hist.init().unwrap();
+
+        assert!(hist.is_empty());
+        assert!(dir.path().join("history.json.corrupt").exists());
+    }
+
+    /// Instances sharing a history file keep each other's entries.
+    #[test]
+    fn save_merges_concurrent_instances() {
+        let dir = setup_history_file(&[]);
+        let mut first = History::new(10, "files", true, dir.path());
+        let mut second = History::new(10, "files", true, dir.path());
+        first.init().unwrap();
+        second.init().unwrap();
+
+        first.add_entry("file1".into(), "files".into()).unwrap();
+        second.add_entry("dir1".into(), "dirs".into()).unwrap();
+        first.save_to_file().unwrap();
+        second.save_to_file().unwrap();
+        // nothing new to save, the file must be left untouched
+        first.save_to_file().unwrap();
+
+        assert_entries(&second, &["file1", "dir1"]);
+        let mut hist = History::new(10, "files", true, dir.path());
+        hist.init().unwrap();
+        assert_entries(&hist, &["file1", "dir1"]);
+    }
+
+    /// The entries picked with a query are saved with it, and picking again
+    /// with the same query replaces them.
+    #[test]
+    fn add_selection_records_picked_entries() {
+        let dir = setup_history_file(&[]);
+        let mut hist = History::new(10, "files", false, dir.path());
+        hist.init().unwrap();
+
+        hist.add_selection("main".into(), "files".into(), vec!["a.rs".into()])
+            .unwrap();
+        hist.save_to_file().unwrap();
+        hist.add_selection("main".into(), "files".into(), vec!["b.rs".into()])
+            .unwrap();
+        hist.save_to_file().unwrap();
+
+        let mut reloaded = History::new(10, "files", false, dir.path());
+        reloaded.init().unwrap();
+        assert_entries(&reloaded, &["main"]);
+        assert_eq!(reloaded.get_entries()[0].selected, vec!["b.rs"]);
+    }
+
     /// Test that empty queries are ignored.
     #[test]
     fn add_entry_ignores_empty_queries() {
@@ -2,12 +2,15 @@ use std::hash::Hash;
 
 use crate::{
     action::{Action, Actions},
-    config::{Keybindings, merge_keybindings},
+    config::{
+        Keybindings, VimConfig, keybindings::KeySequence, merge_keybindings,
+    },
     event::Key,
     television::Mode,
     utils::hashmaps::invert_hashmap,
 };
 use rustc_hash::FxHashMap;
+use std::time::{Duration, Instant};
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct InputMap {
@@ -29,8 +32,8 @@ impl Hash for InputMap {
 
 impl InputMap {
     pub fn new(global: Keybindings, channel: Keybindings) -> Self {
-        let merged = merge_keybindings(global.clone(), &channel);
-        let actions_keys = invert_hashmap(&merged);
+        let actions_keys =
+            invert_hashmap(&merge_keybindings(global.clone(), &channel));
         Self {
             global_keybindings: global,
             channel_keybindings: channel,
@@ -38,19 +41,50 @@ impl InputMap {
         }
     }
 
+    /// The bindings that shadow the start of a longer sequence, see
+    /// [`Keybindings::prefix_conflicts`].
+    pub fn prefix_conflicts(&self) -> Vec<(KeySequence, KeySequence)> {
+        merge_keybindings(
+            self.global_keybindings.clone(),
+            &self.channel_keybindings,
+        )
+        .prefix_conflicts()
+    }
+
+    /// Returns a copy of this input map where `layer` takes precedence over
+    /// both the global and the channel keybindings.
+    pub fn with_layer(&self, layer: &Keybindings) -> Self {
+        Self::new(
+            merge_keybindings(self.global_keybindings.clone(), layer),
+            merge_keybindings(self.channel_keybindings.clone(), layer),
+        )
+    }
+
     /// Gets all actions bound to a specific key for the current mode.
     ///
     /// - `Mode::Channel` checks both global and channel-specific keybindings.
-    /// - `Mode::RemoteControl` and `Mode::ActionPicker` only check global keybindings.
+    /// - `Mode::RemoteControl` and the picker modes only check global
+    ///   keybindings.
+    ///
+    /// Unbound keys fall back to the key given by [`Key::fallback`] (e.g.
+    /// `shift-enter` to `enter`).
     pub fn get_actions_for_key(
         &self,
         key: &Key,
         mode: &Mode,
     ) -> Option<&Actions> {
+        self.get_bound_actions(key, *mode).or_else(|| {
+            key.fallback()
+                .and_then(|fallback| self.get_bound_actions(&fallback, *mode))
+        })
+    }
+
+    fn get_bound_actions(&self, key: &Key, mode: Mode) -> Option<&Actions> {
         match mode {
-            Mode::RemoteControl | Mode::ActionPicker => {
-                self.global_keybindings.get(key)
-            }
+            Mode::RemoteControl
+            | Mode::ActionPicker
+            | Mode::CommandPalette
+            | Mode::HistoryPicker => self.global_keybindings.get(key),
             Mode::Channel => self
                 .channel_keybindings
                 .get(key)
@@ -58,6 +92,124 @@ impl InputMap {
         }
     }
 
+    /// Gets the actions bound to a multi-key sequence for the current mode.
+    ///
+    /// Follows the same precedence rules as `get_actions_for_key`.
+    pub fn get_actions_for_sequence(
+        &self,
+        sequence: &KeySequence,
+        mode: &Mode,
+    ) -> Option<&Actions> {
+        match mode {
+            Mode::RemoteControl
+            | Mode::ActionPicker
+            | Mode::CommandPalette
+            | Mode::HistoryPicker => {
+                self.global_keybindings.sequences.get(sequence)
+            }
+            Mode::Channel => {
+                self.channel_keybindings.sequences.get(sequence).or_else(
+                    || self.global_keybindings.sequences.get(sequence),
+                )
+            }
+        }
+    }
+
+    /// Whether `keys` is the beginning of a longer sequence binding in the
+    /// current mode.
+    fn is_sequence_prefix(&self, keys: &[Key], mode: Mode) -> bool {
+        let is_prefix = |keybindings: &Keybindings| {
+            keybindings.sequences.keys().any(|sequence| {
+                sequence.len() > keys.len() && sequence.starts_with(keys)
+            })
+        };
+        match mode {
+            Mode::RemoteControl
+            | Mode::ActionPicker
+            | Mode::CommandPalette
+            | Mode::HistoryPicker => is_prefix(&self.global_keybindings),
+            Mode::Channel => {
+                is_prefix(&self.channel_keybindings)
+                    || is_prefix(&self.global_keybindings)
+            }
+        }
+    }
+
+    /// Feeds a key press through the sequence bindings.
+    ///
+    /// Returns an empty list while `pending` is the beginning of a longer
+    /// sequence. When the sequence can't be completed anymore, the pending
+    /// keys are handed back to be handled one by one (the last key may start
+    /// a new sequence).
+    pub fn resolve_key(
+        &self,
+        pending: &mut PendingKeys,
+        key: Key,
+        mode: &Mode,
+    ) -> Vec<ResolvedInput> {
+        pending.keys.push(key);
+        if self.is_sequence_prefix(&pending.keys, *mode) {
+            pending.since = Some(Instant::now());
+            return Vec::new();
+        }
+        if pending.keys.len() > 1 {
+            let sequence = KeySequence(std::mem::take(&mut pending.keys));
+            pending.since = None;
+            if let Some(actions) =
+                self.get_actions_for_sequence(&sequence, mode)
+            {
+                return vec![ResolvedInput::Sequence(actions.clone())];
+            }
+            // dead end: flush what came before and start over from the
+            // last key
+            let (last, previous) = sequence.split_last().unwrap();
+            let mut resolved = self.resolve_keys(previous, *mode);
+            resolved.extend(self.resolve_key(pending, *last, mode));
+            return resolved;
+        }
+        pending.clear();
+        vec![ResolvedInput::Key(key)]
+    }
+
+    /// Flushes the pending keys if no key was pressed for `timeout`.
+    pub fn resolve_timeout(
+        &self,
+        pending: &mut PendingKeys,
+        timeout: Duration,
+        mode: &Mode,
+    ) -> Vec<ResolvedInput> {
+        if pending.since.is_none_or(|since| since.elapsed() < timeout) {
+            return Vec::new();
+        }
+        let keys = std::mem::take(&mut pending.keys);
+        pending.since = None;
+        self.resolve_keys(&keys, *mode)
+    }
+
+    /// Splits keys into the longest bound sequences, falling back to
+    /// individual keys.
+    fn resolve_keys(&self, keys: &[Key], mode: Mode) -> Vec<ResolvedInput> {
+        let mut resolved = Vec::new();
+        let mut start = 0;
+        while start < keys.len() {
+            let sequence = (start + 2..=keys.len()).rev().find_map(|end| {
+                self.get_actions_for_sequence(
+                    &KeySequence(keys[start..end].to_vec()),
+                    &mode,
+                )
+                .map(|actions| (end, actions))
+            });
+            if let Some((end, actions)) = sequence {
+                resolved.push(ResolvedInput::Sequence(actions.clone()));
+                start = end;
+            } else {
+                resolved.push(ResolvedInput::Key(keys[start]));
+                start += 1;
+            }
+        }
+        resolved
+    }
+
     /// Gets the key associated with a specific action.
     pub fn get_key_for_action(&self, action: &Action) -> Option<Key> {
         self.actions_keys
@@ -83,6 +235,56 @@ impl InputMap {
     }
 }
 
+/// Input maps used in place of the regular one in `Mode::Channel` when vim
+/// mode is enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VimInputMaps {
+    /// Used in normal and visual mode.
+    pub normal: InputMap,
+    /// Used in insert mode.
+    pub insert: InputMap,
+}
+
+impl VimInputMaps {
+    pub fn new(base: &InputMap, vim_config: &VimConfig) -> Self {
+        Self {
+            normal: base.with_layer(&vim_config.normal_keybindings),
+            insert: base.with_layer(&vim_config.insert_keybindings),
+        }
+    }
+}
+
+/// Keys typed so far while waiting for a multi-key binding to complete.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PendingKeys {
+    keys: Vec<Key>,
+    since: Option<Instant>,
+}
+
+impl PendingKeys {
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.since = None;
+    }
+}
+
+/// The outcome of feeding key presses to [`InputMap::resolve_key`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedInput {
+    /// A key that should be handled on its own.
+    Key(Key),
+    /// The actions bound to a completed key sequence.
+    Sequence(Actions),
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,6 +368,37 @@ mod tests {
         assert_eq!(esc_actions.as_slice(), &[Action::DeletePrevChar]);
     }
 
+    #[test]
+    fn test_modified_keys_fall_back_to_plain_keys() {
+        use crate::event::{KeyCode, Modifiers};
+
+        let shift_enter = Key::Modified(Modifiers::SHIFT, KeyCode::Enter);
+        let mut global_bindings = Keybindings::default();
+        global_bindings
+            .insert(Key::Enter, Actions::single(Action::ConfirmSelection));
+        let input_map =
+            InputMap::new(global_bindings.clone(), Keybindings::default());
+        assert_eq!(
+            input_map
+                .get_actions_for_key(&shift_enter, &Mode::Channel)
+                .unwrap()
+                .as_slice(),
+            &[Action::ConfirmSelection]
+        );
+
+        // unless the modified key is bound itself
+        global_bindings
+            .insert(shift_enter, Actions::single(Action::ToggleSelectionDown));
+        let input_map = InputMap::new(global_bindings, Keybindings::default());
+        assert_eq!(
+            input_map
+                .get_actions_for_key(&shift_enter, &Mode::Channel)
+                .unwrap()
+                .as_slice(),
+            &[Action::ToggleSelectionDown]
+        );
+    }
+
     #[test]
     fn test_input_map_constructor_with_intersection() {
         let mut global_bindings = Keybindings::default();
@@ -228,4 +461,122 @@ mod tests {
             &[Action::ExternalAction(String::from("custom_enter"))]
         );
     }
+
+    fn sequence_input_map() -> InputMap {
+        let global: Keybindings = toml::from_str(
+            r#"
+            "g" = "select_next_page"
+            "g g" = "select_prev_page"
+            "ctrl-x ctrl-e" = "reload_source"
+            "ctrl-x ctrl-e ctrl-e" = "quit"
+            "#,
+        )
+        .unwrap();
+        InputMap::new(global, Keybindings::default())
+    }
+
+    #[test]
+    fn test_resolve_key_sequence() {
+        let input_map = sequence_input_map();
+        let mut pending = PendingKeys::default();
+
+        assert!(
+            input_map
+                .resolve_key(&mut pending, Key::Char('g'), &Mode::Channel)
+                .is_empty()
+        );
+        assert_eq!(pending.keys(), &[Key::Char('g')]);
+        assert_eq!(
+            input_map.resolve_key(
+                &mut pending,
+                Key::Char('g'),
+                &Mode::Channel
+            ),
+            vec![ResolvedInput::Sequence(Action::SelectPrevPage.into())]
+        );
+        assert!(pending.is_empty());
+
+        // unbound keys are handed back right away
+        assert_eq!(
+            input_map.resolve_key(
+                &mut pending,
+                Key::Char('a'),
+                &Mode::Channel
+            ),
+            vec![ResolvedInput::Key(Key::Char('a'))]
+        );
+    }
+
+    #[test]
+    fn test_resolve_key_dead_end_flushes_pending_keys() {
+        let input_map = sequence_input_map();
+        let mut pending = PendingKeys::default();
+
+        // `g` followed by a key that doesn't continue the sequence
+        input_map.resolve_key(&mut pending, Key::Char('g'), &Mode::Channel);
+        assert_eq!(
+            input_map.resolve_key(
+                &mut pending,
+                Key::Char('a'),
+                &Mode::Channel
+            ),
+            vec![
+                ResolvedInput::Key(Key::Char('g')),
+                ResolvedInput::Key(Key::Char('a'))
+            ]
+        );
+        assert!(pending.is_empty());
+
+        // the key that broke the sequence can start a new one
+        input_map.resolve_key(&mut pending, Key::Ctrl('x'), &Mode::Channel);
+        assert_eq!(
+            input_map.resolve_key(
+                &mut pending,
+                Key::Char('g'),
+                &Mode::Channel
+            ),
+            vec![ResolvedInput::Key(Key::Ctrl('x'))]
+        );
+        assert_eq!(pending.keys(), &[Key::Char('g')]);
+    }
+
+    #[test]
+    fn test_resolve_timeout() {
+        let input_map = sequence_input_map();
+        let mut pending = PendingKeys::default();
+
+        input_map.resolve_key(&mut pending, Key::Ctrl('x'), &Mode::Channel);
+        input_map.resolve_key(&mut pending, Key::Ctrl('e'), &Mode::Channel);
+        assert!(
+            input_map
+                .resolve_timeout(
+                    &mut pending,
+                    Duration::from_secs(60),
+                    &Mode::Channel
+                )
+                .is_empty()
+        );
+
+        // a complete sequence that is also a prefix runs after the timeout
+        assert_eq!(
+            input_map.resolve_timeout(
+                &mut pending,
+                Duration::ZERO,
+                &Mode::Channel
+            ),
+            vec![ResolvedInput::Sequence(Action::ReloadSource.into())]
+        );
+        assert!(pending.is_empty());
+
+        // as does a single key shadowed by a sequence
+        input_map.resolve_key(&mut pending, Key::Char('g'), &Mode::Channel);
+        assert_eq!(
+            input_map.resolve_timeout(
+                &mut pending,
+                Duration::ZERO,
+                &Mode::Channel
+            ),
+            vec![ResolvedInput::Key(Key::Char('g'))]
+        );
+    }
 }
@@ -15,10 +15,12 @@ pub mod keymap;
 pub mod logging;
 pub mod matcher;
 pub mod mouse;
+pub mod notifications;
 pub mod picker;
 pub mod previewer;
 pub mod render;
 pub mod screen;
+pub mod storage;
 pub mod television;
 pub mod tui;
 pub mod utils;
@@ -7,18 +7,24 @@ use std::process::exit;
 use television::channels::prototypes::remove_enter_keybinding;
 use television::cli::ChannelCli;
 use television::config::layers::ConfigLayers;
-use television::config::shell_integration::ShellIntegrationConfig;
 use television::{
     app::App,
     cable::{Cable, load_cable},
     channels::prototypes::ChannelPrototype,
     cli::{
         args::{Cli, Command},
+        channels::handle_channels_command,
+        config::handle_config_command,
+        data::{handle_frecency_command, handle_history_command},
         guess_channel_from_prompt, list_channels, post_process,
     },
-    config::{Config, ConfigEnv},
+    config::{
+        Config, ConfigEnv, env_overrides::merged_env_overrides,
+        keybindings::describe_prefix_conflict, user_config::resolve_profile,
+    },
     errors::os_error_exit,
     gh::update_local_channels,
+    notifications::Severity,
     television::Mode,
     utils::clipboard::CLIPBOARD,
     utils::paths::expand_tilde,
@@ -43,12 +49,25 @@ async fn main() -> Result<()> {
 
     let raw_cli = Cli::parse();
 
+    // `tv config` reads the configuration layers itself, and `tv config init`
+    // must work even when the configuration file is broken
+    if let Some(Command::Config { command }) = &raw_cli.command {
+        handle_config_command(command, &raw_cli)?;
+        exit(0);
+    }
+
     // Load configuration first so the cable directory it points to (which may differ
     // from the default) is honored when resolving channel-vs-path ambiguity in post_process.
     debug!("Loading configuration...");
     let config_file = raw_cli.config_file.as_deref().map(expand_tilde);
-    let base_config =
-        Config::new(&ConfigEnv::init()?, config_file.as_deref())?;
+    let profile = resolve_profile(raw_cli.profile.as_deref());
+    let env_overrides = merged_env_overrides()?;
+    let base_config = Config::with_overrides(
+        &ConfigEnv::init()?,
+        config_file.as_deref(),
+        profile.as_deref(),
+        &env_overrides,
+    )?;
 
     let cable_dir = raw_cli
         .cable_dir
@@ -56,8 +75,14 @@ async fn main() -> Result<()> {
         .map(expand_tilde)
         .unwrap_or_else(|| base_config.application.cable_dir.clone());
 
+    // `tv channels lint` reports the errors of each channel file itself
+    if let Some(Command::Channels { command }) = &raw_cli.command {
+        handle_channels_command(command, &cable_dir, &base_config)?;
+        exit(0);
+    }
+
     debug!("Loading cable channels...");
-    let cable = load_cable(&cable_dir);
+    let (cable, cable_errors) = load_cable(&cable_dir);
 
     let cli = post_process(raw_cli, readable_stdin, &cable);
     debug!("PostProcessedCli: {:?}", cli);
@@ -65,7 +90,10 @@ async fn main() -> Result<()> {
     // handle subcommands
     debug!("Handling subcommands...");
     if let Some(subcommand) = &cli.global.command {
-        handle_subcommand(subcommand, &cable, &base_config.shell_integration)?;
+        for error in &cable_errors {
+            eprintln!("{error}");
+        }
+        handle_subcommand(subcommand, &cable, &base_config)?;
     }
 
     // optionally change the working directory
@@ -80,12 +108,24 @@ async fn main() -> Result<()> {
         determine_channel(&cli.channel, &base_config, readable_stdin, &cable);
 
     let layered_config =
-        ConfigLayers::new(base_config, channel_prototype, cli.clone());
+        ConfigLayers::new(base_config, channel_prototype, cli.clone())
+            .with_env_overrides(env_overrides);
 
     CLIPBOARD.with(<_>::default);
 
     debug!("Creating application...");
     let mut app = App::new(layered_config, cable);
+    for error in cable_errors {
+        app.television.notify(Severity::Error, error);
+    }
+    for (prefix, sequence) in
+        app.television.merged_config.input_map.prefix_conflicts()
+    {
+        app.television.notify(
+            Severity::Warning,
+            describe_prefix_conflict(&prefix, &sequence),
+        );
+    }
 
     // If the user requested to show the remote control on startup, switch the
     // television into Remote Control mode before the application event loop
@@ -133,7 +173,7 @@ pub fn set_current_dir(path: &PathBuf) -> Result<()> {
 pub fn handle_subcommand(
     command: &Command,
     cable: &Cable,
-    shell_integration_config: &ShellIntegrationConfig,
+    config: &Config,
 ) -> Result<()> {
     match command {
         Command::ListChannels => {
@@ -148,7 +188,7 @@ pub fn handle_subcommand(
             let script = render_autocomplete_script_template(
                 target_shell,
                 completion_script(target_shell)?,
-                shell_integration_config,
+                &config.shell_integration,
             )?;
             let _ = writeln!(stdout().lock(), "{script}");
             exit(0);
@@ -169,6 +209,20 @@ pub fn handle_subcommand(
             update_local_channels(force)?;
             exit(0);
         }
+        Command::Frecency { command } => {
+            handle_frecency_command(command, config)?;
+            exit(0);
+        }
+        Command::History { command } => {
+            handle_history_command(command, config)?;
+            exit(0);
+        }
+        Command::Config { .. } => {
+            unreachable!("handled before loading the configuration")
+        }
+        Command::Channels { .. } => {
+            unreachable!("handled before loading the cable")
+        }
     }
 }
 
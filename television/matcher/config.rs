@@ -0,0 +1,153 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// How the query is matched against the entries.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchingMode {
+    /// Fuzzy matching, supporting fzf-like operators (`'`, `^`, `$`, `!`)
+    #[default]
+    Fuzzy,
+    /// Each word of the query must appear as is in the entry
+    Substring,
+    /// The query is a regular expression
+    Regex,
+    /// Fuzzy matching tuned for file paths, favoring matches in the file
+    /// name and shorter paths
+    Path,
+}
+
+impl MatchingMode {
+    /// The matching mode that follows this one when cycling through them.
+    pub fn next(self) -> Self {
+        match self {
+            MatchingMode::Fuzzy => MatchingMode::Substring,
+            MatchingMode::Substring => MatchingMode::Regex,
+            MatchingMode::Regex => MatchingMode::Path,
+            MatchingMode::Path => MatchingMode::Fuzzy,
+        }
+    }
+}
+
+impl Display for MatchingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchingMode::Fuzzy => write!(f, "fuzzy"),
+            MatchingMode::Substring => write!(f, "substring"),
+            MatchingMode::Regex => write!(f, "regex"),
+            MatchingMode::Path => write!(f, "path"),
+        }
+    }
+}
+
+/// Whether the query is matched case sensitively.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseSensitivity {
+    /// Case sensitive only if the query contains uppercase characters
+    #[default]
+    Smart,
+    Sensitive,
+    Insensitive,
+}
+
+impl CaseSensitivity {
+    /// The case sensitivity that follows this one when cycling through them.
+    pub fn next(self) -> Self {
+        match self {
+            CaseSensitivity::Smart => CaseSensitivity::Sensitive,
+            CaseSensitivity::Sensitive => CaseSensitivity::Insensitive,
+            CaseSensitivity::Insensitive => CaseSensitivity::Smart,
+        }
+    }
+
+    /// Whether a query should be matched case sensitively.
+    pub fn is_sensitive(self, query: &str) -> bool {
+        match self {
+            CaseSensitivity::Smart => query.chars().any(char::is_uppercase),
+            CaseSensitivity::Sensitive => true,
+            CaseSensitivity::Insensitive => false,
+        }
+    }
+
+    /// Whether a regular expression should be matched case sensitively.
+    ///
+    /// Unlike `is_sensitive`, escapes such as `\S` or `\p{Lu}` don't count
+    /// as uppercase characters in smart case.
+    pub fn is_sensitive_regex(self, pattern: &str) -> bool {
+        if self != CaseSensitivity::Smart {
+            return self.is_sensitive(pattern);
+        }
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                let escaped = chars.next();
+                // classes and code points, e.g. `\p{Lu}`, `\PL` or `\x1F`
+                let digits = match escaped {
+                    Some('p' | 'P') => 1,
+                    Some('x') => 2,
+                    Some('u') => 4,
+                    Some('U') => 8,
+                    _ => continue,
+                };
+                if chars.next_if_eq(&'{').is_some() {
+                    chars.by_ref().find(|&c| c == '}');
+                } else {
+                    for _ in 0..digits {
+                        chars.next_if(char::is_ascii_alphanumeric);
+                    }
+                }
+            } else if c.is_uppercase() {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl From<CaseSensitivity> for nucleo::pattern::CaseMatching {
+    fn from(case: CaseSensitivity) -> Self {
+        match case {
+            CaseSensitivity::Smart => nucleo::pattern::CaseMatching::Smart,
+            CaseSensitivity::Sensitive => {
+                nucleo::pattern::CaseMatching::Respect
+            }
+            CaseSensitivity::Insensitive => {
+                nucleo::pattern::CaseMatching::Ignore
+            }
+        }
+    }
+}
+
+impl Display for CaseSensitivity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaseSensitivity::Smart => write!(f, "smart case"),
+            CaseSensitivity::Sensitive => write!(f, "match case"),
+            CaseSensitivity::Insensitive => write!(f, "ignore case"),
+        }
+    }
+}
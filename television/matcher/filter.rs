@@ -0,0 +1,379 @@
+use super::{config::CaseSensitivity, lazy, query::Query};
+use lazy_regex::{Regex, RegexBuilder, regex::escape};
+use nucleo::{
+    Match, SortFn, SortStrategy, Utf32Str,
+    pattern::{Normalization, Pattern},
+};
+use rayon::prelude::*;
+use std::{
+    cmp::Ordering,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Number of items tested at once, the time spent scanning is checked
+/// between two chunks.
+const SCAN_CHUNK_SIZE: u32 = 16 * 1024;
+
+/// How the matcher sorts its results.
+///
+/// This mirrors the `SortStrategy` handed over to `Nucleo`, which takes
+/// ownership of it, so that filtered results can be sorted the same way.
+pub(super) enum ResultOrder<I> {
+    Index,
+    Score,
+    Custom(Arc<SortFn<I>>),
+}
+
+impl<I> ResultOrder<I>
+where
+    I: Sync + Send + 'static,
+{
+    /// Split a sort strategy into the one given to `Nucleo` and its mirror.
+    pub(super) fn split(strategy: SortStrategy<I>) -> (SortStrategy<I>, Self) {
+        match strategy {
+            SortStrategy::Index => (SortStrategy::Index, Self::Index),
+            SortStrategy::Score => (SortStrategy::Score, Self::Score),
+            SortStrategy::Custom(sort_fn) => {
+                let sort_fn = Arc::new(sort_fn);
+                let shared = Arc::clone(&sort_fn);
+                (
+                    SortStrategy::Custom(Box::new(move |m1, i1, m2, i2| {
+                        shared(m1, i1, m2, i2)
+                    })),
+                    Self::Custom(sort_fn),
+                )
+            }
+        }
+    }
+
+    pub(super) fn compare(
+        &self,
+        snapshot: &nucleo::Snapshot<I>,
+        reverse: bool,
+        m1: Match,
+        m2: Match,
+    ) -> Ordering {
+        let by_index = if reverse {
+            m2.idx.cmp(&m1.idx)
+        } else {
+            m1.idx.cmp(&m2.idx)
+        };
+        let len = |m: Match| {
+            snapshot
+                .get_item(m.idx)
+                .map_or(0, |item| item.matcher_columns[0].len())
+        };
+        match self {
+            ResultOrder::Index => by_index,
+            ResultOrder::Score => m2
+                .score
+                .cmp(&m1.score)
+                .then_with(|| len(m1).cmp(&len(m2)))
+                .then(by_index),
+            ResultOrder::Custom(sort_fn) => {
+                match (snapshot.get_item(m1.idx), snapshot.get_item(m2.idx)) {
+                    (Some(i1), Some(i2)) => {
+                        sort_fn(&m1, i1, &m2, i2).then(by_index)
+                    }
+                    _ => by_index,
+                }
+            }
+        }
+    }
+}
+
+enum FilterKind {
+    /// `None` matches every item
+    Regex(Option<Regex>),
+    /// An item matches if any of the patterns does
+    Alternatives {
+        patterns: Vec<Pattern>,
+        /// Whether the items are file paths (see `MatchingMode::Path`)
+        paths: bool,
+    },
+}
+
+/// Filters items for queries `Nucleo` can't express on its own: regular
+/// expressions and alternations.
+///
+/// `Nucleo` is then given an empty pattern and the items it holds are tested
+/// here, incrementally as they are loaded and a chunk at a time so that each
+/// tick stays short.
+pub(super) struct ItemFilter {
+    kind: FilterKind,
+    matches: Vec<Match>,
+    /// Number of items already tested
+    scanned: u32,
+    /// Whether `matches` is sorted with the matcher's `ResultOrder`, rather
+    /// than kept in source order
+    sorted: bool,
+}
+
+impl ItemFilter {
+    pub(super) fn regex(
+        pattern: &str,
+        case_sensitivity: CaseSensitivity,
+    ) -> Self {
+        let build = |pattern: &str| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(
+                    !case_sensitivity.is_sensitive_regex(pattern),
+                )
+                .build()
+        };
+        // an incomplete regex (e.g. while still typing it) is matched
+        // literally rather than hiding every entry
+        let regex = build(pattern).or_else(|_| build(&escape(pattern))).ok();
+        Self::new(FilterKind::Regex(regex), false)
+    }
+
+    pub(super) fn alternatives(
+        query: &Query,
+        case_sensitivity: CaseSensitivity,
+        sorted: bool,
+        paths: bool,
+    ) -> Self {
+        let patterns = query
+            .alternatives
+            .iter()
+            .map(|alternative| {
+                Pattern::parse(
+                    &alternative.pattern(),
+                    case_sensitivity.into(),
+                    Normalization::Smart,
+                )
+            })
+            .collect();
+        Self::new(FilterKind::Alternatives { patterns, paths }, sorted)
+    }
+
+    fn new(kind: FilterKind, sorted: bool) -> Self {
+        Self {
+            kind,
+            matches: Vec::new(),
+            scanned: 0,
+            sorted,
+        }
+    }
+
+    pub(super) fn reset(&mut self) {
+        self.matches.clear();
+        self.scanned = 0;
+    }
+
+    pub(super) fn matched_item_count(&self) -> u32 {
+        u32::try_from(self.matches.len()).unwrap_or(u32::MAX)
+    }
+
+    fn score(
+        &self,
+        haystack: Utf32Str<'_>,
+        matcher: &mut nucleo::Matcher,
+    ) -> Option<u32> {
+        match &self.kind {
+            FilterKind::Regex(regex) => regex
+                .as_ref()
+                .is_none_or(|r| r.is_match(&haystack.to_string()))
+                .then_some(0),
+            FilterKind::Alternatives { patterns, .. } => patterns
+                .iter()
+                .filter_map(|pattern| pattern.score(haystack, matcher))
+                .max(),
+        }
+    }
+
+    fn config(&self) -> nucleo::Config {
+        match self.kind {
+            FilterKind::Alternatives { paths: true, .. } => {
+                nucleo::Config::DEFAULT.match_paths()
+            }
+            _ => nucleo::Config::DEFAULT,
+        }
+    }
+
+    /// Test the items added to the snapshot since the last scan, for
+    /// about `timeout` at most.
+    ///
+    /// Returns whether every item has been tested, the remaining ones are
+    /// tested by the next calls.
+    pub(super) fn scan<I>(
+        &mut self,
+        snapshot: &nucleo::Snapshot<I>,
+        order: &ResultOrder<I>,
+        reverse: bool,
+        timeout: Duration,
+    ) -> bool
+    where
+        I: Sync + Send + Clone + 'static,
+    {
+        let item_count = snapshot.item_count();
+        if item_count < self.scanned {
+            self.reset();
+        }
+        let start = Instant::now();
+        while self.scanned < item_count && start.elapsed() < timeout {
+            let end =
+                self.scanned.saturating_add(SCAN_CHUNK_SIZE).min(item_count);
+            let new_matches: Vec<Match> = (self.scanned..end)
+                .into_par_iter()
+                .map_init(
+                    || nucleo::Matcher::new(self.config()),
+                    |matcher, idx| {
+                        let item = snapshot.get_item(idx)?;
+                        self.score(item.matcher_columns[0].slice(..), matcher)
+                            .map(|score| Match { score, idx })
+                    },
+                )
+                .flatten()
+                .collect();
+            self.scanned = end;
+            self.insert(new_matches, |m1, m2| {
+                order.compare(snapshot, reverse, *m1, *m2)
+            });
+        }
+        self.scanned == item_count
+    }
+
+    /// Add new matches, merging them into the sorted matches rather than
+    /// sorting everything again.
+    fn insert(
+        &mut self,
+        mut new_matches: Vec<Match>,
+        compare: impl Fn(&Match, &Match) -> Ordering + Sync,
+    ) {
+        if !self.sorted {
+            self.matches.extend(new_matches);
+            return;
+        }
+        new_matches.par_sort_by(&compare);
+        let Some(first) = new_matches.first() else {
+            return;
+        };
+        if self
+            .matches
+            .last()
+            .is_none_or(|last| compare(last, first) == Ordering::Less)
+        {
+            self.matches.extend(new_matches);
+            return;
+        }
+        let matches = std::mem::take(&mut self.matches);
+        self.matches.reserve(matches.len() + new_matches.len());
+        let mut new_matches = new_matches.into_iter().peekable();
+        for m in matches {
+            while let Some(new_match) = new_matches
+                .next_if(|new_match| compare(new_match, &m) == Ordering::Less)
+            {
+                self.matches.push(new_match);
+            }
+            self.matches.push(m);
+        }
+        self.matches.extend(new_matches);
+    }
+
+    /// The index of the item displayed at `position` in the results.
+    pub(super) fn get(&self, position: u32, reverse: bool) -> Option<u32> {
+        let position = position as usize;
+        let position = if reverse && !self.sorted {
+            self.matches.len().checked_sub(position + 1)?
+        } else {
+            position
+        };
+        self.matches.get(position).map(|m| m.idx)
+    }
+
+    /// The indices of the characters of `haystack` matched by the filter.
+    ///
+    /// For alternations, these are the characters matched by the best
+    /// scoring alternative.
+    pub(super) fn match_indices(&self, haystack: Utf32Str<'_>) -> Vec<u32> {
+        match &self.kind {
+            FilterKind::Regex(None) => Vec::new(),
+            FilterKind::Regex(Some(regex)) => {
+                let haystack = haystack.to_string();
+                let ranges: Vec<_> =
+                    regex.find_iter(&haystack).map(|m| m.range()).collect();
+                haystack
+                    .char_indices()
+                    .enumerate()
+                    .filter(|(_, (offset, _))| {
+                        ranges.iter().any(|r| r.contains(offset))
+                    })
+                    .filter_map(|(i, _)| u32::try_from(i).ok())
+                    .collect()
+            }
+            FilterKind::Alternatives { patterns, paths } => {
+                let mut matcher = if *paths {
+                    lazy::PATH_MATCHER.lock()
+                } else {
+                    lazy::MATCHER.lock()
+                };
+                let Some(best) = patterns
+                    .iter()
+                    .filter_map(|pattern| {
+                        pattern
+                            .score(haystack, &mut matcher)
+                            .map(|score| (score, pattern))
+                    })
+                    // the first alternative wins ties
+                    .rev()
+                    .max_by_key(|(score, _)| *score)
+                    .map(|(_, pattern)| pattern)
+                else {
+                    return Vec::new();
+                };
+                let mut indices = Vec::new();
+                best.indices(haystack, &mut matcher, &mut indices);
+                indices.sort_unstable();
+                indices.dedup();
+                indices
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_merges_sorted_matches() {
+        let mut filter = ItemFilter::new(FilterKind::Regex(None), true);
+        let by_score = |m1: &Match, m2: &Match| {
+            m2.score.cmp(&m1.score).then(m1.idx.cmp(&m2.idx))
+        };
+        let matches = |scores: &[(u32, u32)]| {
+            scores
+                .iter()
+                .map(|&(idx, score)| Match { score, idx })
+                .collect::<Vec<_>>()
+        };
+        filter.insert(matches(&[(0, 3), (1, 7), (2, 5)]), by_score);
+        filter.insert(matches(&[(3, 6), (4, 1), (5, 9)]), by_score);
+        assert_eq!(
+            filter.matches.iter().map(|m| m.idx).collect::<Vec<_>>(),
+            [5, 1, 3, 2, 0, 4]
+        );
+    }
+
+    #[test]
+    fn test_regex_smart_case_ignores_escapes() {
+        let matches = |pattern: &str, haystack: &str| {
+            !ItemFilter::regex(pattern, CaseSensitivity::Smart)
+                .match_indices(Utf32Str::new(haystack, &mut Vec::new()))
+                .is_empty()
+        };
+        assert!(matches(r"foo\S+", "FOO1"));
+        assert!(matches(r"\bfoo\W\D", "FOO-x"));
+        assert!(matches(r"\x{4F}foo", "OFOO"));
+        // uppercase characters outside of escapes still count
+        assert!(!matches(r"Foo\S+", "FOO1"));
+        assert!(matches(r"Foo\S+", "Foo1"));
+
+        let smart = CaseSensitivity::Smart;
+        assert!(!smart.is_sensitive_regex(r"\p{Lu}oo \PL \x4F"));
+        assert!(smart.is_sensitive_regex(r"\p{Lu}Oo"));
+        assert!(smart.is_sensitive_regex(r"\\Foo"));
+    }
+}
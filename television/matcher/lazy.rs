@@ -1,4 +1,4 @@
-use nucleo::Matcher;
+use nucleo::{Config, Matcher};
 use parking_lot::Mutex;
 use std::ops::DerefMut;
 
@@ -91,3 +91,7 @@ impl<T> LazyMutex<T> {
 ///     .collect();
 /// ```
 pub static MATCHER: LazyMutex<Matcher> = LazyMutex::new(Matcher::default);
+
+/// Same as [`MATCHER`], configured for matching file paths.
+pub static PATH_MATCHER: LazyMutex<Matcher> =
+    LazyMutex::new(|| Matcher::new(Config::DEFAULT.match_paths()));
@@ -1,11 +1,29 @@
+use config::{CaseSensitivity, MatchingMode};
+use filter::{ItemFilter, ResultOrder};
 use injector::Injector;
-use std::{sync::Arc, thread::available_parallelism};
+use nucleo::{Match, Utf32Str};
+use query::Query;
+use std::{
+    path::is_separator, sync::Arc, thread::available_parallelism,
+    time::Duration,
+};
 
+pub mod config;
+mod filter;
 pub mod injector;
 pub mod lazy;
 pub mod matched_item;
+pub mod query;
 
 const MATCHER_TICK_TIMEOUT: u64 = 2;
+/// Time spent testing items on each tick when filtering them (see
+/// `ItemFilter`), so that large sources don't freeze the UI.
+const FILTER_TICK_TIMEOUT: Duration = Duration::from_millis(8);
+/// Number of best matches reordered to favor file names in path mode (see
+/// `Matcher::rank_paths`).
+const FILE_NAME_RANKING_WINDOW: u32 = 256;
+/// Added to the score of path matches ending in the file name.
+const FILE_NAME_BONUS: u32 = 16;
 
 /// The status of the fuzzy matcher.
 ///
@@ -52,22 +70,48 @@ where
     /// A pre-allocated buffer used to collect match indices when fetching the results
     /// from the matcher. This avoids having to re-allocate on each pass.
     col_indices_buffer: Vec<u32>,
+    mode: MatchingMode,
+    case_sensitivity: CaseSensitivity,
+    /// Whether the items are reversed (see `reverse_items`)
+    reverse: bool,
+    /// Whether the next call to `find` must rematch every item, even if the
+    /// pattern didn't change
+    rescore: bool,
+    /// The pattern last handed over to `Nucleo`
+    nucleo_pattern: String,
+    /// Filters the items when the query is a regex or an alternation
+    filter: Option<ItemFilter>,
+    order: ResultOrder<I>,
+    /// Positions of the best matches of the current snapshot once reordered
+    /// to favor file names, see `rank_paths`
+    file_name_ranking: Option<Vec<u32>>,
 }
 
 /// Get the number of threads to use for the matcher.
 ///
 /// This uses the number of available threads on the system, minus 3, to avoid
-/// saturating the system.
+/// saturating the system, while always using at least one thread.
 ///
 /// The number is capped to 32 threads to avoid impacting startup time and memory usage.
 ///
 /// Defaults to 4 if the number of available threads cannot be determined.
 pub fn matcher_threads() -> usize {
     available_parallelism()
-        .map(|n| n.get().saturating_sub(3).min(32))
+        .map(|n| n.get().saturating_sub(3).clamp(1, 32))
         .unwrap_or(4)
 }
 
+/// The `Nucleo` configuration for the given matching mode.
+///
+/// Path mode favors matches at path separators, and `Matcher::rank_paths`
+/// those in the file name.
+fn nucleo_config(mode: MatchingMode) -> nucleo::Config {
+    match mode {
+        MatchingMode::Path => nucleo::Config::DEFAULT.match_paths(),
+        _ => nucleo::Config::DEFAULT,
+    }
+}
+
 impl<I> Matcher<I>
 where
     I: Sync + Send + Clone + 'static,
@@ -78,11 +122,12 @@ where
         n_threads: usize,
     ) -> Self {
         let mut inner = nucleo::Nucleo::new(
-            nucleo::Config::DEFAULT,
+            nucleo_config(MatchingMode::default()),
             Arc::new(|| {}),
             Some(n_threads),
             1,
         );
+        let (sort_strategy, order) = ResultOrder::split(sort_strategy);
         inner.set_sort_strategy(sort_strategy);
         Self {
             inner,
@@ -91,6 +136,14 @@ where
             status: Status::default(),
             last_pattern: String::new(),
             col_indices_buffer: Vec::with_capacity(128), // Pre-allocate for performance
+            mode: MatchingMode::default(),
+            case_sensitivity: CaseSensitivity::default(),
+            reverse: false,
+            rescore: false,
+            nucleo_pattern: String::new(),
+            filter: None,
+            order,
+            file_name_ranking: None,
         }
     }
 
@@ -98,7 +151,58 @@ where
     ///
     /// This should be called periodically to update the state of the matcher.
     pub fn tick(&mut self) {
-        self.status = self.inner.tick(MATCHER_TICK_TIMEOUT).into();
+        let status = self.inner.tick(MATCHER_TICK_TIMEOUT);
+        if status.changed {
+            self.file_name_ranking = None;
+        }
+        self.status = status.into();
+        if let Some(filter) = &mut self.filter {
+            let snapshot = self.inner.snapshot();
+            let scanned = filter.scan(
+                snapshot,
+                &self.order,
+                self.reverse,
+                FILTER_TICK_TIMEOUT,
+            );
+            // keep reporting activity until every item has been tested
+            self.status.running |= !scanned;
+            self.total_item_count = snapshot.item_count();
+            self.matched_item_count = filter.matched_item_count();
+        }
+    }
+
+    /// Set how patterns are matched against the items.
+    ///
+    /// This takes effect on the next call to `find`, which rematches every
+    /// item.
+    pub fn set_matching(
+        &mut self,
+        mode: MatchingMode,
+        case_sensitivity: CaseSensitivity,
+    ) {
+        if mode != self.mode || case_sensitivity != self.case_sensitivity {
+            if (mode == MatchingMode::Path)
+                != (self.mode == MatchingMode::Path)
+            {
+                self.inner.update_config(nucleo_config(mode));
+            }
+            self.mode = mode;
+            self.case_sensitivity = case_sensitivity;
+            self.rescore = true;
+        }
+    }
+
+    /// Set how the matched items are sorted.
+    ///
+    /// This takes effect on the next call to `find`.
+    pub fn set_sort_strategy(
+        &mut self,
+        sort_strategy: nucleo::SortStrategy<I>,
+    ) {
+        let (sort_strategy, order) = ResultOrder::split(sort_strategy);
+        self.inner.set_sort_strategy(sort_strategy);
+        self.order = order;
+        self.rescore = true;
     }
 
     /// Get an injector that can be used to push items into the fuzzy matcher.
@@ -129,17 +233,110 @@ where
     /// The `Matcher` will keep track of the last pattern and only reparse the
     /// pattern if it has changed, allowing for more efficient matching when
     /// `self.last_pattern` is a prefix of the new `pattern`.
+    ///
+    /// Outside of regex mode, the pattern may contain alternations (see
+    /// [`Query`]).
     pub fn find(&mut self, pattern: &str) {
-        if pattern != self.last_pattern {
+        if pattern == self.last_pattern && !self.rescore {
+            return;
+        }
+        let query =
+            (self.mode != MatchingMode::Regex).then(|| Query::parse(pattern));
+        self.filter = match &query {
+            None => Some(ItemFilter::regex(pattern, self.case_sensitivity)),
+            Some(query) if query.is_alternation() => {
+                Some(ItemFilter::alternatives(
+                    query,
+                    self.case_sensitivity,
+                    !matches!(self.order, ResultOrder::Index),
+                    self.mode == MatchingMode::Path,
+                ))
+            }
+            Some(_) => None,
+        };
+        // when filtering, let nucleo hand out every item to the filter
+        let nucleo_pattern =
+            query.and_then(|query| query.pattern()).unwrap_or_default();
+        if self.rescore || nucleo_pattern != self.nucleo_pattern {
             self.inner.pattern.reparse(
                 0,
-                pattern,
-                nucleo::pattern::CaseMatching::Smart,
+                &nucleo_pattern,
+                self.case_sensitivity.into(),
                 nucleo::pattern::Normalization::Smart,
-                pattern.starts_with(&self.last_pattern),
+                !self.rescore
+                    && nucleo_pattern.starts_with(&self.nucleo_pattern),
             );
-            self.last_pattern = pattern.to_string();
+            self.nucleo_pattern = nucleo_pattern;
         }
+        self.rescore = false;
+        self.last_pattern = pattern.to_string();
+        self.file_name_ranking = None;
+    }
+
+    /// Reorder the best matches so that, in path mode, the ones ending in the
+    /// file name come first, e.g. `crates/app/src/main.rs` before
+    /// `src/main_x/a.rs` for `main`.
+    ///
+    /// Only the first `FILE_NAME_RANKING_WINDOW` matches are reordered, which
+    /// is cheap enough to do whenever the snapshot changes.
+    fn rank_paths(&mut self) {
+        if self.file_name_ranking.is_some() {
+            return;
+        }
+        let snapshot = self.inner.snapshot();
+        if self.mode != MatchingMode::Path
+            || matches!(self.order, ResultOrder::Index)
+            || self.nucleo_pattern.is_empty()
+        {
+            self.file_name_ranking = Some(Vec::new());
+            return;
+        }
+
+        let mut matcher = lazy::PATH_MATCHER.lock();
+        let window =
+            snapshot.matched_item_count().min(FILE_NAME_RANKING_WINDOW);
+        let mut ranked: Vec<(u32, Match)> = snapshot.matches()
+            [..window as usize]
+            .iter()
+            .zip(0..)
+            .map(|(&m, position)| {
+                let Some(item) = snapshot.get_item(m.idx) else {
+                    return (position, m);
+                };
+                let haystack = item.matcher_columns[0].slice(..);
+                self.col_indices_buffer.clear();
+                snapshot.pattern().column_pattern(0).indices(
+                    haystack,
+                    &mut matcher,
+                    &mut self.col_indices_buffer,
+                );
+                let in_file_name =
+                    self.col_indices_buffer.iter().max().is_some_and(
+                        |&last| last >= file_name_start(haystack),
+                    );
+                let score = if in_file_name {
+                    m.score.saturating_add(FILE_NAME_BONUS)
+                } else {
+                    m.score
+                };
+                (position, Match { score, idx: m.idx })
+            })
+            .collect();
+        self.col_indices_buffer.clear();
+        ranked.sort_by(|(_, m1), (_, m2)| {
+            self.order.compare(snapshot, self.reverse, *m1, *m2)
+        });
+        self.file_name_ranking =
+            Some(ranked.into_iter().map(|(position, _)| position).collect());
+    }
+
+    /// The position among the matches of the snapshot of the `index`-th
+    /// result.
+    fn match_position(&self, index: u32) -> u32 {
+        self.file_name_ranking
+            .as_ref()
+            .and_then(|ranking| ranking.get(index as usize).copied())
+            .unwrap_or(index)
     }
 
     /// Get the matched items.
@@ -177,6 +374,12 @@ where
         num_entries: u32,
         offset: u32,
     ) -> Vec<matched_item::MatchedItem<I>> {
+        if self.filter.is_some() {
+            return (offset..offset.saturating_add(num_entries))
+                .map_while(|index| self.get_filtered_result(index))
+                .collect();
+        }
+
         let snapshot = self.inner.snapshot();
         self.total_item_count = snapshot.item_count();
         self.matched_item_count = snapshot.matched_item_count();
@@ -192,6 +395,8 @@ where
             num_entries
         };
 
+        self.rank_paths();
+        let snapshot = self.inner.snapshot();
         // Clear the pre-allocated match indices buffer for safety
         self.col_indices_buffer.clear();
         let mut matcher = lazy::MATCHER.lock();
@@ -199,9 +404,14 @@ where
         // PERF: Pre-allocate the results Vec so we avoid repeated reallocations
         let mut results = Vec::with_capacity(num_entries as usize);
 
-        for item in snapshot.matched_items(
-            offset..(num_entries + offset).min(self.matched_item_count),
-        ) {
+        for index in
+            offset..(num_entries + offset).min(self.matched_item_count)
+        {
+            let Some(item) =
+                snapshot.get_matched_item(self.match_position(index))
+            else {
+                continue;
+            };
             snapshot.pattern().column_pattern(0).indices(
                 item.matcher_columns[0].slice(..),
                 &mut matcher,
@@ -248,27 +458,53 @@ where
         &mut self,
         index: u32,
     ) -> Option<matched_item::MatchedItem<I>> {
+        if self.filter.is_some() {
+            return self.get_filtered_result(index);
+        }
+
+        self.rank_paths();
         let snapshot = self.inner.snapshot();
         let mut matcher = lazy::MATCHER.lock();
         self.col_indices_buffer.clear();
 
-        snapshot.get_matched_item(index).map(|item| {
-            snapshot.pattern().column_pattern(0).indices(
-                item.matcher_columns[0].slice(..),
-                &mut matcher,
-                &mut self.col_indices_buffer,
-            );
-            self.col_indices_buffer.sort_unstable();
-            self.col_indices_buffer.dedup();
+        snapshot
+            .get_matched_item(self.match_position(index))
+            .map(|item| {
+                snapshot.pattern().column_pattern(0).indices(
+                    item.matcher_columns[0].slice(..),
+                    &mut matcher,
+                    &mut self.col_indices_buffer,
+                );
+                self.col_indices_buffer.sort_unstable();
+                self.col_indices_buffer.dedup();
 
-            let indices = self.col_indices_buffer.drain(..);
-            let matched_string = item.matcher_columns[0].to_string();
+                let indices = self.col_indices_buffer.drain(..);
+                let matched_string = item.matcher_columns[0].to_string();
 
-            matched_item::MatchedItem {
-                inner: item.data.clone(),
-                matched_string,
-                match_indices: indices.collect(),
-            }
+                matched_item::MatchedItem {
+                    inner: item.data.clone(),
+                    matched_string,
+                    match_indices: indices.collect(),
+                }
+            })
+    }
+
+    /// Get the result at the given position when filtering the items.
+    fn get_filtered_result(
+        &self,
+        index: u32,
+    ) -> Option<matched_item::MatchedItem<I>> {
+        let filter = self.filter.as_ref()?;
+        let item = self
+            .inner
+            .snapshot()
+            .get_item(filter.get(index, self.reverse)?)?;
+        let match_indices =
+            filter.match_indices(item.matcher_columns[0].slice(..));
+        Some(matched_item::MatchedItem {
+            inner: item.data.clone(),
+            matched_string: item.matcher_columns[0].to_string(),
+            match_indices,
         })
     }
 
@@ -283,6 +519,10 @@ where
         self.status = Status::default();
         self.last_pattern.clear();
         self.col_indices_buffer.clear();
+        self.file_name_ranking = None;
+        if let Some(filter) = &mut self.filter {
+            filter.reset();
+        }
     }
 
     /// Set whether to reverse the input order.
@@ -293,6 +533,20 @@ where
     ///
     /// Defaults to `false`.
     pub fn reverse_items(&mut self, reverse: bool) {
+        self.reverse = reverse;
         self.inner.reverse_items(reverse);
     }
 }
+
+/// Index of the first character of the file name in `path`.
+fn file_name_start(path: Utf32Str<'_>) -> u32 {
+    let last_separator = match path {
+        Utf32Str::Ascii(bytes) => {
+            bytes.iter().rposition(|&b| is_separator(char::from(b)))
+        }
+        Utf32Str::Unicode(chars) => {
+            chars.iter().rposition(|&c| is_separator(c))
+        }
+    };
+    last_separator.map_or(0, |i| u32::try_from(i + 1).unwrap_or(u32::MAX))
+}
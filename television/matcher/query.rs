@@ -0,0 +1,267 @@
+use std::fmt::Display;
+
+/// Maximum number of alternatives a query is expanded into.
+///
+/// Groups are distributed over the surrounding terms (`(a | b) (c | d)` is
+/// `a c | a d | b c | b d`), so the number of alternatives grows quickly with
+/// nested alternations. Anything past this limit is dropped.
+const MAX_ALTERNATIVES: usize = 64;
+
+/// A query made of alternatives separated by `|`, with parenthesised groups.
+///
+/// Each alternative is a regular fuzzy pattern, i.e. space separated terms
+/// which must all match (see the `nucleo` pattern syntax), and an entry
+/// matches the query if it matches any of its alternatives:
+///
+/// ```text
+/// .rs$ | .toml$           -> ".rs$", ".toml$"
+/// src (error | warn) !test  -> "src error !test", "src warn !test"
+/// ```
+///
+/// `|` is always an operator, while parentheses only open a group at the
+/// start of a term and close one when a group is open, so that most queries
+/// containing them (e.g. `main()`) keep matching literally. Each of them can
+/// be escaped with a backslash to be matched literally anywhere.
+///
+/// Parsing never fails: unclosed groups are closed at the end of the query
+/// and empty alternatives (e.g. while typing `foo |`) are ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Query {
+    pub alternatives: Vec<Alternative>,
+}
+
+/// The terms of one of the alternatives of a `Query`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Alternative {
+    pub terms: Vec<String>,
+}
+
+impl Alternative {
+    /// The alternative as a pattern `nucleo` can parse.
+    pub fn pattern(&self) -> String {
+        self.terms.join(" ")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Term(String),
+    Or,
+    Open,
+    Close,
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Self {
+        let tokens = tokenize(query);
+        let mut parser = Parser {
+            tokens: tokens.into_iter().peekable(),
+        };
+        let mut alternatives: Vec<Alternative> = Vec::new();
+        for alternative in parser.parse_or() {
+            if !alternative.terms.is_empty()
+                && !alternatives.contains(&alternative)
+            {
+                alternatives.push(alternative);
+            }
+        }
+        Self { alternatives }
+    }
+
+    /// Whether the query has more than one alternative, i.e. can't be
+    /// expressed as a single fuzzy pattern.
+    pub fn is_alternation(&self) -> bool {
+        self.alternatives.len() > 1
+    }
+
+    /// The query as a single fuzzy pattern, if it has no more than one
+    /// alternative.
+    pub fn pattern(&self) -> Option<String> {
+        match self.alternatives.as_slice() {
+            [] => Some(String::new()),
+            [alternative] => Some(alternative.pattern()),
+            _ => None,
+        }
+    }
+
+    /// Apply `f` to every term of the query.
+    #[must_use]
+    pub fn map_terms(mut self, f: impl Fn(&str) -> String) -> Self {
+        for alternative in &mut self.alternatives {
+            for term in &mut alternative.terms {
+                *term = f(term);
+            }
+        }
+        self
+    }
+}
+
+/// Formats the query back into the query syntax, with its groups expanded.
+impl Display for Query {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, alternative) in self.alternatives.iter().enumerate() {
+            if i > 0 {
+                write!(f, " | ")?;
+            }
+            for (j, term) in alternative.terms.iter().enumerate() {
+                if j > 0 {
+                    write!(f, " ")?;
+                }
+                for c in term.chars() {
+                    if matches!(c, '|' | '(' | ')') {
+                        write!(f, "\\")?;
+                    }
+                    write!(f, "{c}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn tokenize(query: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut term = String::new();
+    let mut depth = 0usize;
+    let mut chars = query.chars();
+
+    let end_term = |term: &mut String, tokens: &mut Vec<Token>| {
+        if !term.is_empty() {
+            tokens.push(Token::Term(std::mem::take(term)));
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped @ ('|' | '(' | ')')) => term.push(escaped),
+                // other escapes (e.g. `\ `) are left for nucleo to handle
+                Some(escaped) => {
+                    term.push('\\');
+                    term.push(escaped);
+                }
+                None => term.push('\\'),
+            },
+            c if c.is_whitespace() => end_term(&mut term, &mut tokens),
+            '|' => {
+                end_term(&mut term, &mut tokens);
+                tokens.push(Token::Or);
+            }
+            '(' if term.is_empty() => {
+                depth += 1;
+                tokens.push(Token::Open);
+            }
+            ')' if depth > 0 => {
+                end_term(&mut term, &mut tokens);
+                depth -= 1;
+                tokens.push(Token::Close);
+            }
+            c => term.push(c),
+        }
+    }
+    end_term(&mut term, &mut tokens);
+    tokens
+}
+
+struct Parser {
+    tokens: std::iter::Peekable<std::vec::IntoIter<Token>>,
+}
+
+impl Parser {
+    /// `alternative ('|' alternative)*`
+    fn parse_or(&mut self) -> Vec<Alternative> {
+        let mut alternatives = self.parse_and();
+        while self.tokens.next_if_eq(&Token::Or).is_some() {
+            let rest = self.parse_and();
+            let room = MAX_ALTERNATIVES.saturating_sub(alternatives.len());
+            alternatives.extend(rest.into_iter().take(room));
+        }
+        alternatives
+    }
+
+    /// `(term | '(' or ')')*`
+    fn parse_and(&mut self) -> Vec<Alternative> {
+        let mut alternatives = vec![Alternative::default()];
+        loop {
+            let factor = match self.tokens.peek() {
+                Some(Token::Term(_)) => {
+                    let Some(Token::Term(term)) = self.tokens.next() else {
+                        unreachable!()
+                    };
+                    vec![Alternative { terms: vec![term] }]
+                }
+                Some(Token::Open) => {
+                    self.tokens.next();
+                    let group = self.parse_or();
+                    // an unclosed group ends with the query
+                    self.tokens.next_if_eq(&Token::Close);
+                    group
+                }
+                _ => return alternatives,
+            };
+            alternatives = alternatives
+                .iter()
+                .flat_map(|lhs| {
+                    factor.iter().map(move |rhs| Alternative {
+                        terms: lhs
+                            .terms
+                            .iter()
+                            .chain(&rhs.terms)
+                            .cloned()
+                            .collect(),
+                    })
+                })
+                .take(MAX_ALTERNATIVES)
+                .collect();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alternatives(query: &str) -> Vec<String> {
+        Query::parse(query)
+            .alternatives
+            .iter()
+            .map(Alternative::pattern)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_alternation() {
+        assert_eq!(alternatives("foo bar"), vec!["foo bar"]);
+        assert_eq!(alternatives(".rs$ | .toml$"), vec![".rs$", ".toml$"]);
+        assert_eq!(alternatives("error|warn"), vec!["error", "warn"]);
+        assert_eq!(
+            alternatives("src (error | warn) !test"),
+            vec!["src error !test", "src warn !test"]
+        );
+        assert_eq!(
+            alternatives("(a | b) (c | d)"),
+            vec!["a c", "a d", "b c", "b d"]
+        );
+        assert_eq!(alternatives("a | (b | (c d))"), vec!["a", "b", "c d"]);
+    }
+
+    #[test]
+    fn test_parse_incomplete_and_literal() {
+        assert_eq!(alternatives(""), Vec::<String>::new());
+        assert_eq!(alternatives("foo |"), vec!["foo"]);
+        assert_eq!(alternatives("| foo | foo"), vec!["foo"]);
+        assert_eq!(alternatives("(foo | bar"), vec!["foo", "bar"]);
+        assert_eq!(alternatives("main() foo)"), vec!["main() foo)"]);
+        assert_eq!(alternatives(r"a\|b \(c\)"), vec!["a|b (c)"]);
+        assert_eq!(alternatives(r"foo\ bar"), vec![r"foo\ bar"]);
+        assert!(!Query::parse("(foo)").is_alternation());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let query =
+            Query::parse(r"x (a\| | b) y").map_terms(|t| format!("'{t}"));
+        assert_eq!(query.to_string(), r"'x 'a\| 'y | 'x 'b 'y");
+        assert_eq!(Query::parse(&query.to_string()), query);
+    }
+}
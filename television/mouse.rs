@@ -1,10 +1,15 @@
-use crossterm::event::{MouseEvent, MouseEventKind};
-use ratatui::layout::Position;
+use crossterm::event::{
+    KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use ratatui::{
+    layout::{Position, Rect},
+    widgets::ListDirection,
+};
 
 use crate::{action::Action, screen::layout::Layout, television::Mode};
 
-/// Handles mouse events (scrolling) and returns the corresponding action
-/// based on the cursor position, the current UI layout and mode.
+/// Handles mouse events and returns the corresponding action based on the
+/// cursor position, the current UI layout and mode.
 pub fn get_action_for_mouse_event(
     event: MouseEvent,
     ui_layout: &Layout,
@@ -12,6 +17,60 @@ pub fn get_action_for_mouse_event(
 ) -> Action {
     let position = Position::new(event.column, event.row);
 
+    match event.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            get_action_for_click(event, position, ui_layout, mode)
+        }
+        MouseEventKind::Drag(MouseButton::Left) => {
+            Action::MouseDragTo(event.column, event.row)
+        }
+        MouseEventKind::Up(MouseButton::Left) => Action::MouseRelease,
+        MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+            get_action_for_scroll(event, position, ui_layout, mode)
+        }
+        _ => Action::NoOp,
+    }
+}
+
+fn get_action_for_click(
+    event: MouseEvent,
+    position: Position,
+    ui_layout: &Layout,
+    mode: Mode,
+) -> Action {
+    let in_list = match mode {
+        Mode::Channel => ui_layout.results.contains(position),
+        Mode::RemoteControl => ui_layout
+            .remote_control
+            .is_some_and(|rc| rc.contains(position)),
+        Mode::ActionPicker | Mode::CommandPalette | Mode::HistoryPicker => {
+            ui_layout
+                .action_picker
+                .is_some_and(|ap| ap.contains(position))
+        }
+    };
+    if !in_list {
+        return Action::MouseClickAt(event.column, event.row);
+    }
+
+    // multi-selection only makes sense for channel results
+    if mode == Mode::Channel {
+        if event.modifiers.contains(KeyModifiers::CONTROL) {
+            return Action::ToggleSelectionAtPosition(event.column, event.row);
+        }
+        if event.modifiers.contains(KeyModifiers::SHIFT) {
+            return Action::SelectRangeToPosition(event.column, event.row);
+        }
+    }
+    Action::SelectEntryAtPosition(event.column, event.row)
+}
+
+fn get_action_for_scroll(
+    event: MouseEvent,
+    position: Position,
+    ui_layout: &Layout,
+    mode: Mode,
+) -> Action {
     // if the mouse is over the results or remote control, scroll the selection
     if matches!(mode, Mode::Channel) && ui_layout.results.contains(position)
         || matches!(mode, Mode::RemoteControl)
@@ -38,3 +97,217 @@ pub fn get_action_for_mouse_event(
     }
     Action::NoOp
 }
+
+/// Returns the index of the row of a list drawn in `area` that contains the
+/// given position, counting from the list's first row.
+pub fn list_row_at(
+    area: Rect,
+    position: Position,
+    direction: ListDirection,
+) -> Option<usize> {
+    if !area.contains(position) {
+        return None;
+    }
+    let row = match direction {
+        ListDirection::TopToBottom => position.y - area.y,
+        ListDirection::BottomToTop => area.bottom() - 1 - position.y,
+    };
+    Some(row.into())
+}
+
+/// The border between the results and the preview panel, which can be
+/// dragged to resize the preview.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewBorder {
+    /// The preview is to the right of the results
+    Right,
+    /// The preview is below the results
+    Below,
+    /// The preview is above the results
+    Above,
+}
+
+impl PreviewBorder {
+    /// Returns the border separating `results` from `preview` if the given
+    /// position lies on it.
+    pub fn at(
+        results: Rect,
+        preview: Rect,
+        position: Position,
+    ) -> Option<PreviewBorder> {
+        let (border, lines) = if preview.x > results.x {
+            (
+                PreviewBorder::Right,
+                [results.right().saturating_sub(1), preview.x],
+            )
+        } else if preview.y > results.y {
+            (
+                PreviewBorder::Below,
+                [results.bottom().saturating_sub(1), preview.y],
+            )
+        } else {
+            (
+                PreviewBorder::Above,
+                [preview.bottom().saturating_sub(1), results.y],
+            )
+        };
+        let on_border = match border {
+            PreviewBorder::Right => {
+                lines.contains(&position.x)
+                    && (preview.top()..preview.bottom()).contains(&position.y)
+            }
+            PreviewBorder::Below | PreviewBorder::Above => {
+                lines.contains(&position.y)
+                    && (preview.left()..preview.right()).contains(&position.x)
+            }
+        };
+        on_border.then_some(border)
+    }
+
+    /// The preview size (in percent) that puts this border at the given
+    /// position.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn preview_size_at(
+        self,
+        results: Rect,
+        preview: Rect,
+        position: Position,
+    ) -> u16 {
+        let (preview_span, total) = match self {
+            PreviewBorder::Right => (
+                preview.right().saturating_sub(position.x),
+                preview.right().saturating_sub(results.x),
+            ),
+            PreviewBorder::Below => (
+                preview.bottom().saturating_sub(position.y),
+                preview.bottom().saturating_sub(results.y),
+            ),
+            PreviewBorder::Above => (
+                (position.y + 1).saturating_sub(preview.y),
+                results.bottom().saturating_sub(preview.y),
+            ),
+        };
+        if total == 0 {
+            return MAX_PREVIEW_SIZE;
+        }
+        ((u32::from(preview_span) * 100 / u32::from(total)) as u16)
+            .clamp(MIN_PREVIEW_SIZE, MAX_PREVIEW_SIZE)
+    }
+}
+
+const MIN_PREVIEW_SIZE: u16 = 10;
+const MAX_PREVIEW_SIZE: u16 = 90;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn click(column: u16, row: u16, modifiers: KeyModifiers) -> MouseEvent {
+        MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers,
+        }
+    }
+
+    #[test]
+    fn test_click_actions() {
+        let layout = Layout::new(
+            Rect::new(0, 0, 40, 20),
+            Rect::new(0, 20, 40, 3),
+            Some(Rect::new(40, 0, 40, 23)),
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(
+            get_action_for_mouse_event(
+                click(5, 5, KeyModifiers::NONE),
+                &layout,
+                Mode::Channel
+            ),
+            Action::SelectEntryAtPosition(5, 5)
+        );
+        assert_eq!(
+            get_action_for_mouse_event(
+                click(5, 5, KeyModifiers::CONTROL),
+                &layout,
+                Mode::Channel
+            ),
+            Action::ToggleSelectionAtPosition(5, 5)
+        );
+        assert_eq!(
+            get_action_for_mouse_event(
+                click(5, 5, KeyModifiers::SHIFT),
+                &layout,
+                Mode::Channel
+            ),
+            Action::SelectRangeToPosition(5, 5)
+        );
+        assert_eq!(
+            get_action_for_mouse_event(
+                click(50, 5, KeyModifiers::NONE),
+                &layout,
+                Mode::Channel
+            ),
+            Action::MouseClickAt(50, 5)
+        );
+    }
+
+    #[test]
+    fn test_list_row_at() {
+        let area = Rect::new(1, 1, 10, 5);
+        let top_down = ListDirection::TopToBottom;
+        let bottom_up = ListDirection::BottomToTop;
+        assert_eq!(list_row_at(area, Position::new(2, 1), top_down), Some(0));
+        assert_eq!(list_row_at(area, Position::new(2, 3), top_down), Some(2));
+        assert_eq!(list_row_at(area, Position::new(2, 5), bottom_up), Some(0));
+        assert_eq!(list_row_at(area, Position::new(2, 0), top_down), None);
+    }
+
+    #[test]
+    fn test_preview_border() {
+        let results = Rect::new(0, 0, 40, 20);
+        let preview = Rect::new(40, 0, 40, 20);
+        assert_eq!(
+            PreviewBorder::at(results, preview, Position::new(40, 3)),
+            Some(PreviewBorder::Right)
+        );
+        assert_eq!(
+            PreviewBorder::at(results, preview, Position::new(20, 3)),
+            None
+        );
+        assert_eq!(
+            PreviewBorder::Right.preview_size_at(
+                results,
+                preview,
+                Position::new(20, 3)
+            ),
+            75
+        );
+        assert_eq!(
+            PreviewBorder::Right.preview_size_at(
+                results,
+                preview,
+                Position::new(0, 3)
+            ),
+            MAX_PREVIEW_SIZE
+        );
+
+        let preview = Rect::new(0, 20, 40, 20);
+        assert_eq!(
+            PreviewBorder::at(results, preview, Position::new(5, 19)),
+            Some(PreviewBorder::Below)
+        );
+        assert_eq!(
+            PreviewBorder::Below.preview_size_at(
+                results,
+                preview,
+                Position::new(5, 30)
+            ),
+            25
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+/// Maximum number of notifications kept in the history.
+const HISTORY_SIZE: usize = 100;
+/// Maximum number of toasts displayed at once.
+pub const MAX_VISIBLE_NOTIFICATIONS: usize = 3;
+
+/// How important a notification is, which determines its color and how long
+/// it stays on screen.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// How long a toast of this severity stays on screen.
+    ///
+    /// Problems are left up longer than confirmations since they usually
+    /// require reading.
+    pub fn duration(self) -> Duration {
+        match self {
+            Severity::Info | Severity::Success => Duration::from_secs(3),
+            Severity::Warning => Duration::from_secs(5),
+            Severity::Error => Duration::from_secs(8),
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Success => write!(f, "success"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub severity: Severity,
+    pub message: String,
+    /// How many times in a row this notification was raised
+    pub count: usize,
+    /// When the notification was (last) raised
+    pub created: Instant,
+    dismissed: bool,
+}
+
+impl Notification {
+    /// Whether the notification should still be displayed as a toast.
+    pub fn is_active(&self, now: Instant) -> bool {
+        !self.dismissed
+            && now.saturating_duration_since(self.created)
+                < self.severity.duration()
+    }
+}
+
+/// Notifications raised during the session, newest last.
+///
+/// Each notification is displayed as a toast until its severity's timeout
+/// expires, and remains available in the history afterwards.
+#[derive(Debug, Clone, Default)]
+pub struct Notifications {
+    history: VecDeque<Notification>,
+    /// Number of active toasts the last time `tick` was called
+    last_active: usize,
+}
+
+impl Notifications {
+    /// Raise a new notification.
+    ///
+    /// Raising the same notification as the latest one while it is still
+    /// displayed only bumps its counter and restarts its timer, so that a
+    /// repeated failure doesn't flood the screen.
+    pub fn push(&mut self, severity: Severity, message: impl Into<String>) {
+        let message = message.into();
+        let now = Instant::now();
+        if let Some(last) = self.history.back_mut()
+            && last.severity == severity
+            && last.message == message
+            && last.is_active(now)
+        {
+            last.count += 1;
+            last.created = now;
+            return;
+        }
+        if self.history.len() == HISTORY_SIZE {
+            self.history.pop_front();
+        }
+        self.history.push_back(Notification {
+            severity,
+            message,
+            count: 1,
+            created: now,
+            dismissed: false,
+        });
+    }
+
+    /// The notifications to display as toasts, newest first.
+    pub fn active(&self, now: Instant) -> impl Iterator<Item = &Notification> {
+        self.history
+            .iter()
+            .rev()
+            .filter(move |n| n.is_active(now))
+            .take(MAX_VISIBLE_NOTIFICATIONS)
+    }
+
+    /// Every notification kept in the history, newest first.
+    pub fn history(&self) -> impl Iterator<Item = &Notification> {
+        self.history.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    /// Hide all toasts, keeping the notifications in the history.
+    pub fn dismiss_all(&mut self) {
+        for notification in &mut self.history {
+            notification.dismissed = true;
+        }
+    }
+
+    /// Returns whether the set of displayed toasts changed since the last
+    /// call, i.e. whether a redraw is needed.
+    pub fn tick(&mut self, now: Instant) -> bool {
+        let active = self.active(now).count();
+        let changed = active != self.last_active;
+        self.last_active = active;
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_notifications_are_merged() {
+        let mut notifications = Notifications::default();
+        notifications.push(Severity::Error, "boom");
+        notifications.push(Severity::Error, "boom");
+        notifications.push(Severity::Info, "boom");
+
+        let history: Vec<_> = notifications.history().collect();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].severity, Severity::Info);
+        assert_eq!(history[1].count, 2);
+    }
+
+    #[test]
+    fn test_notifications_expire() {
+        let mut notifications = Notifications::default();
+        notifications.push(Severity::Success, "copied");
+        notifications.push(Severity::Error, "failed");
+        let now = Instant::now();
+        assert!(notifications.tick(now));
+        assert_eq!(notifications.active(now).count(), 2);
+        assert!(!notifications.tick(now));
+
+        let later = now + Severity::Success.duration();
+        let active: Vec<_> = notifications.active(later).collect();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].message, "failed");
+        assert!(notifications.tick(later));
+
+        notifications.dismiss_all();
+        assert_eq!(notifications.active(now).count(), 0);
+        assert_eq!(notifications.history().count(), 2);
+    }
+}
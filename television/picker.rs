@@ -114,6 +114,16 @@ impl<T> Picker<T> {
             self.relative_select(Some(relative_selected.saturating_sub(1)));
         }
     }
+    fn inner_first(&mut self) {
+        self.select(Some(0));
+        self.relative_select(Some(0));
+    }
+
+    fn inner_last(&mut self, total_items: usize, height: usize) {
+        self.select(Some(total_items - 1));
+        self.relative_select(Some((height - 1).min(total_items - 1)));
+    }
+
     /// Generic cursor movement helper.
     pub fn move_cursor(
         &mut self,
@@ -134,6 +144,13 @@ impl<T> Picker<T> {
             Movement::Prev => {
                 self.select_prev(step, total_items, picker_ui_height);
             }
+            // first and last follow the on-screen order like next and prev
+            Movement::First if self.inverted => {
+                self.inner_last(total_items, picker_ui_height);
+            }
+            Movement::Last if self.inverted => self.inner_first(),
+            Movement::First => self.inner_first(),
+            Movement::Last => self.inner_last(total_items, picker_ui_height),
         }
     }
 }
@@ -157,10 +174,13 @@ impl<Entry> Picker<Entry> {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-/// Cursor movement: `Movement::Next` or `Movement::Prev`.
+/// Cursor movement: `Movement::Next`, `Movement::Prev`, or a jump to
+/// either end of the list with `Movement::First` and `Movement::Last`.
 pub enum Movement {
     Next,
     Prev,
+    First,
+    Last,
 }
 
 #[allow(clippy::doc_overindented_list_items)]
@@ -296,6 +316,27 @@ mod tests {
         assert_eq!(picker.relative_selected(), Some(1), "relative_selected");
     }
 
+    /// - item 0         *
+    /// - item 1 S     R *
+    /// - item 2         * height
+    /// - item 3 last
+    #[test]
+    fn test_picker_move_cursor_first_and_last() {
+        let mut picker = Picker::<Entry>::default();
+        picker.select(Some(1));
+        picker.relative_select(Some(1));
+        picker.move_cursor(Movement::Last, 1, 4, 3);
+        assert_eq!(picker.selected(), Some(3), "selected");
+        assert_eq!(picker.relative_selected(), Some(2), "relative_selected");
+        picker.move_cursor(Movement::First, 1, 4, 3);
+        assert_eq!(picker.selected(), Some(0), "selected");
+        assert_eq!(picker.relative_selected(), Some(0), "relative_selected");
+
+        picker = picker.inverted();
+        picker.move_cursor(Movement::First, 1, 4, 3);
+        assert_eq!(picker.selected(), Some(3), "selected");
+    }
+
     #[test]
     fn test_picker_offset_default() {
         let picker = Picker::<Entry>::default();
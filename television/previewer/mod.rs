@@ -17,6 +17,7 @@ use tokio::{
 use tracing::{debug, trace, warn};
 
 use crate::{
+    action::Action,
     channels::{
         entry::Entry,
         prototypes::{CommandSpec, Template},
@@ -183,6 +184,11 @@ pub struct Previewer {
     footer_template: Option<Template>,
     offset_expr: Option<Template>,
     results: UnboundedSender<Preview>,
+    /// Used to report preview failures to the user
+    action_tx: UnboundedSender<Action>,
+    /// Whether a failure was already reported, so that a broken preview
+    /// command doesn't raise a notification for every entry
+    reported_failure: bool,
     cache: Option<Arc<Mutex<Cache>>>,
 }
 
@@ -197,6 +203,7 @@ impl Previewer {
         requests_rx: UnboundedReceiver<Request>,
         requests_tx: UnboundedSender<Request>,
         results_tx: UnboundedSender<Preview>,
+        action_tx: UnboundedSender<Action>,
         cache: bool,
     ) -> Self {
         let cache = if cache {
@@ -215,6 +222,8 @@ impl Previewer {
             footer_template,
             offset_expr,
             results: results_tx,
+            action_tx,
+            reported_failure: false,
             cache,
         }
     }
@@ -254,11 +263,20 @@ impl Previewer {
                             Ok(Ok(Ok(()))) => {
                                 trace!("Preview job completed successfully");
                             }
-                            Ok(Ok(Err(e))) => warn!(
-                                "Failed to generate preview for entry '{}': {}",
-                                &self.last_job_entry.clone().unwrap().raw,
-                                e
-                            ),
+                            Ok(Ok(Err(e))) => {
+                                warn!(
+                                    "Failed to generate preview for entry '{}': {}",
+                                    &self.last_job_entry.clone().unwrap().raw,
+                                    e
+                                );
+                                if !self.reported_failure {
+                                    self.reported_failure = true;
+                                    let _ =
+                                        self.action_tx.send(Action::Error(
+                                            format!("Preview failed: {e}"),
+                                        ));
+                                }
+                            }
                             Ok(Err(join_err)) => {
                                 warn!(
                                     "Preview join error for '{}': {}",
@@ -0,0 +1,169 @@
+use crate::{screen::colors::Colorscheme, television::ActionInputPopup};
+use anyhow::Result;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph},
+};
+
+const POPUP_WIDTH: u16 = 60;
+const MIN_POPUP_WIDTH: u16 = 30;
+/// Height of the bordered text field.
+const INPUT_FIELD_HEIGHT: u16 = 3;
+
+/// Draws a centered modal prompting the user for the inputs of an external
+/// action.
+///
+/// Values that have already been submitted are listed above the prompt for
+/// the current input.
+pub fn draw_action_input_popup(
+    f: &mut Frame<'_>,
+    area: Rect,
+    popup: &ActionInputPopup,
+    colorscheme: &Colorscheme,
+) -> Result<()> {
+    let Some(current) = popup.current() else {
+        return Ok(());
+    };
+
+    let mut lines: Vec<Line> = popup
+        .inputs
+        .iter()
+        .zip(&popup.values)
+        .map(|(input, value)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{}: ", input.prompt()),
+                    Style::default()
+                        .fg(colorscheme.help.metadata_field_name_fg),
+                ),
+                Span::styled(
+                    value.clone(),
+                    Style::default()
+                        .fg(colorscheme.help.metadata_field_value_fg),
+                ),
+            ])
+        })
+        .collect();
+    let mut prompt = vec![Span::styled(
+        current.prompt().to_string(),
+        Style::default().fg(colorscheme.input.input_fg).bold(),
+    )];
+    if let Some(default) = &current.default {
+        prompt.push(Span::styled(
+            format!(" (default: {default})"),
+            Style::default().fg(colorscheme.general.border_fg).italic(),
+        ));
+    }
+    lines.push(Line::from(prompt));
+
+    // borders + prompt lines + text field
+    let height = u16::try_from(lines.len())? + INPUT_FIELD_HEIGHT + 2;
+    let popup_area = centered_rect(
+        POPUP_WIDTH.min(area.width),
+        height.min(area.height),
+        area,
+    );
+    if popup_area.width < MIN_POPUP_WIDTH || popup_area.height < height {
+        return Ok(());
+    }
+
+    f.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colorscheme.mode.action_picker))
+        .title_top(
+            Line::from(Span::styled(
+                format!(" {} ", popup.action_name),
+                Style::default().fg(colorscheme.mode.action_picker).bold(),
+            ))
+            .alignment(Alignment::Center),
+        )
+        .title_bottom(
+            Line::from(Span::styled(
+                " Enter: submit · Esc: cancel ",
+                Style::default().fg(colorscheme.general.border_fg).italic(),
+            ))
+            .alignment(Alignment::Left),
+        )
+        .title_bottom(
+            Line::from(Span::styled(
+                format!(" {}/{} ", popup.values.len() + 1, popup.inputs.len()),
+                Style::default().fg(colorscheme.general.border_fg),
+            ))
+            .alignment(Alignment::Right),
+        )
+        .style(
+            Style::default()
+                .bg(colorscheme.general.background.unwrap_or_default()),
+        )
+        .padding(Padding::horizontal(1));
+
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+
+    let [prompt_area, input_area] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(INPUT_FIELD_HEIGHT),
+    ])
+    .areas(inner);
+
+    f.render_widget(Paragraph::new(lines), prompt_area);
+
+    let input_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colorscheme.general.border_fg));
+    let input_inner = input_block.inner(input_area);
+    f.render_widget(input_block, input_area);
+
+    let [arrow_area, text_area] =
+        Layout::horizontal([Constraint::Length(2), Constraint::Fill(1)])
+            .areas(input_inner);
+    f.render_widget(
+        Paragraph::new(Span::styled(
+            "> ",
+            Style::default().fg(colorscheme.input.input_fg).bold(),
+        )),
+        arrow_area,
+    );
+
+    // keep 1 for the cursor
+    let width = text_area.width.saturating_sub(1);
+    let scroll = popup.input.visual_scroll(width as usize);
+    f.render_widget(
+        Paragraph::new(popup.input.value())
+            .scroll((0, u16::try_from(scroll)?))
+            .style(Style::default().fg(colorscheme.input.input_fg).bold()),
+        text_area,
+    );
+    f.set_cursor_position((
+        text_area.x
+            + u16::try_from(popup.input.visual_cursor().max(scroll) - scroll)?,
+        text_area.y,
+    ));
+
+    Ok(())
+}
+
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let vertical_layout = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(height),
+        Constraint::Fill(1),
+    ])
+    .split(area);
+
+    let horizontal_layout = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(width),
+        Constraint::Fill(1),
+    ])
+    .split(vertical_layout[1]);
+
+    horizontal_layout[1]
+}
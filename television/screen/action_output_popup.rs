@@ -0,0 +1,152 @@
+use crate::{screen::colors::Colorscheme, television::ActionOutputPopup};
+use ansi_to_tui::IntoText;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::Style,
+    text::{Line, Span, Text},
+    widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph},
+};
+
+const MIN_POPUP_WIDTH: u16 = 30;
+const MIN_POPUP_HEIGHT: u16 = 8;
+/// Percentage of the available area the popup spans in each direction.
+const POPUP_SIZE_PERCENT: u16 = 80;
+
+/// Draws a centered panel showing the output of an action that was run in
+/// `ExecutionMode::Capture`.
+///
+/// The formatted command is pinned at the top, followed by the scrollable
+/// stdout and stderr of the command. The exit status is displayed in the
+/// bottom border.
+pub fn draw_action_output_popup(
+    f: &mut Frame<'_>,
+    area: Rect,
+    popup: &ActionOutputPopup,
+    colorscheme: &Colorscheme,
+) {
+    let popup_area = centered_rect(
+        (area.width * POPUP_SIZE_PERCENT / 100).max(MIN_POPUP_WIDTH),
+        (area.height * POPUP_SIZE_PERCENT / 100).max(MIN_POPUP_HEIGHT),
+        area,
+    );
+
+    if popup_area.width < MIN_POPUP_WIDTH
+        || popup_area.height < MIN_POPUP_HEIGHT
+    {
+        return;
+    }
+
+    f.render_widget(Clear, popup_area);
+
+    let (status_text, status_style) = match popup.exit_code {
+        Some(0) => (
+            " exit 0 ".to_string(),
+            Style::default().fg(colorscheme.help.metadata_field_value_fg),
+        ),
+        Some(code) => (
+            format!(" exit {code} "),
+            Style::default().fg(colorscheme.input.results_count_fg),
+        ),
+        None => (
+            " failed ".to_string(),
+            Style::default().fg(colorscheme.input.results_count_fg),
+        ),
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colorscheme.general.border_fg))
+        .title_top(
+            Line::from(Span::styled(
+                format!(" {} ", popup.action_name),
+                Style::default().fg(colorscheme.mode.action_picker).bold(),
+            ))
+            .alignment(Alignment::Center),
+        )
+        .title_bottom(
+            Line::from(Span::styled(
+                " Enter/Esc: dismiss · ↑/↓: scroll ",
+                Style::default().fg(colorscheme.general.border_fg).italic(),
+            ))
+            .alignment(Alignment::Left),
+        )
+        .title_bottom(
+            Line::from(Span::styled(status_text, status_style.bold()))
+                .alignment(Alignment::Right),
+        )
+        .style(
+            Style::default()
+                .bg(colorscheme.general.background.unwrap_or_default()),
+        )
+        .padding(Padding::horizontal(1));
+
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+
+    let [command_area, output_area] =
+        Layout::vertical([Constraint::Length(2), Constraint::Fill(1)])
+            .areas(inner);
+
+    let command = Paragraph::new(Line::from(vec![
+        Span::styled(
+            "$ ",
+            Style::default().fg(colorscheme.help.metadata_field_name_fg),
+        ),
+        Span::styled(
+            popup.command.clone(),
+            Style::default()
+                .fg(colorscheme.help.metadata_field_name_fg)
+                .bold(),
+        ),
+    ]));
+    f.render_widget(command, command_area);
+
+    let output = Paragraph::new(generate_output_text(popup, colorscheme))
+        .scroll((popup.scroll, 0));
+    f.render_widget(output, output_area);
+}
+
+fn generate_output_text(
+    popup: &ActionOutputPopup,
+    colorscheme: &Colorscheme,
+) -> Text<'static> {
+    if popup.stdout.is_empty() && popup.stderr.is_empty() {
+        return Text::from(Line::from(Span::styled(
+            "(no output)",
+            Style::default().fg(colorscheme.general.border_fg).italic(),
+        )));
+    }
+
+    let mut text = popup
+        .stdout
+        .into_text()
+        .unwrap_or_else(|_| Text::raw(popup.stdout.clone()));
+    let stderr_style = Style::default().fg(colorscheme.input.results_count_fg);
+    text.lines.extend(
+        popup
+            .stderr
+            .lines()
+            .map(|line| Line::styled(line.to_string(), stderr_style)),
+    );
+    text
+}
+
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let vertical_layout = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(height),
+        Constraint::Fill(1),
+    ])
+    .split(area);
+
+    let horizontal_layout = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(width),
+        Constraint::Fill(1),
+    ])
+    .split(vertical_layout[1]);
+
+    horizontal_layout[1]
+}
@@ -1,19 +1,22 @@
 use crate::{
     channels::action_picker::ActionEntry,
-    screen::{colors::Colorscheme, result_item},
-    utils::input::Input,
+    frecency::current_timestamp,
+    history::HistoryEntry,
+    screen::{colors::Colorscheme, constants::POINTER_SYMBOL, result_item},
+    utils::{input::Input, strings::format_age},
 };
 use anyhow::Result;
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    prelude::{Line, Span, Style},
+    prelude::{Color, Line, Span, Style},
     style::Stylize,
     widgets::{
-        Block, BorderType, Borders, Clear, ListDirection, ListState, Padding,
-        Paragraph, Wrap,
+        Block, BorderType, Borders, Clear, List, ListDirection, ListState,
+        Padding, Paragraph, Wrap,
     },
 };
+use std::rc::Rc;
 
 /// Minimum width required to show the description panel alongside the action list.
 const MIN_WIDTH_FOR_DESCRIPTION_PANEL: u16 = 60;
@@ -22,20 +25,13 @@ const MIN_WIDTH_FOR_DESCRIPTION_PANEL: u16 = 60;
 pub fn draw_action_picker(
     f: &mut Frame,
     rect: Rect,
+    title: &str,
     entries: &[ActionEntry],
     picker_state: &mut ListState,
     input_state: &mut Input,
     colorscheme: &Colorscheme,
 ) -> Result<()> {
-    let mut constraints = vec![Constraint::Fill(1)];
-    if rect.width > MIN_WIDTH_FOR_DESCRIPTION_PANEL {
-        constraints.push(Constraint::Fill(1));
-    }
-
-    let layout = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints(constraints)
-        .split(rect);
+    let layout = columns(rect);
 
     f.render_widget(Clear, rect);
 
@@ -44,6 +40,7 @@ pub fn draw_action_picker(
     draw_search_panel(
         f,
         layout[0],
+        title,
         entries,
         picker_state,
         colorscheme,
@@ -57,6 +54,36 @@ pub fn draw_action_picker(
     Ok(())
 }
 
+/// Splits the picker into the search panel and, if there is enough room,
+/// the detail panel.
+fn columns(rect: Rect) -> Rc<[Rect]> {
+    let mut constraints = vec![Constraint::Fill(1)];
+    if rect.width > MIN_WIDTH_FOR_DESCRIPTION_PANEL {
+        constraints.push(Constraint::Fill(1));
+    }
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(constraints)
+        .split(rect)
+}
+
+fn search_panel_rows(area: Rect) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Fill(1), Constraint::Length(3)].as_ref())
+        .split(area)
+}
+
+/// The area in which the entries of an action picker drawn in `rect` are
+/// listed, one per row.
+pub fn action_list_area(rect: Rect) -> Rect {
+    Block::default()
+        .borders(Borders::ALL)
+        .padding(Padding::right(1))
+        .inner(search_panel_rows(columns(rect)[0])[0])
+}
+
 fn draw_detail_panel(
     f: &mut Frame,
     rect: Rect,
@@ -73,7 +100,11 @@ fn draw_detail_panel(
         )
         .padding(Padding::horizontal(1));
 
-    let content: Vec<Line> = if let Some(entry) = selected_entry {
+    let content: Vec<Line> = if let Some(history_entry) =
+        selected_entry.and_then(|entry| entry.history_entry.as_ref())
+    {
+        history_details(history_entry, colorscheme)
+    } else if let Some(entry) = selected_entry {
         let mut lines = Vec::new();
 
         // Description section
@@ -93,20 +124,61 @@ fn draw_detail_panel(
         // Blank line separator
         lines.push(Line::from(""));
 
-        // Command section
-        lines.push(Line::from(Span::styled(
-            "Command:",
-            Style::default().bold(),
-        )));
-        if entry.commands.is_empty() {
-            lines.push(Line::from("No command defined."));
-        } else {
-            for cmd in &entry.commands {
-                lines.push(Line::from(Span::styled(
-                    cmd.as_str(),
-                    Style::default().fg(colorscheme.preview.title_fg),
-                )));
+        // Command section (built-in actions and channels don't have one)
+        if entry.is_external() {
+            lines.push(Line::from(Span::styled(
+                "Command:",
+                Style::default().bold(),
+            )));
+            if entry.commands.is_empty() {
+                lines.push(Line::from("No command defined."));
+            } else {
+                for cmd in &entry.commands {
+                    lines.push(Line::from(Span::styled(
+                        cmd.as_str(),
+                        Style::default().fg(colorscheme.preview.title_fg),
+                    )));
+                }
             }
+        } else {
+            lines.push(Line::from(Span::styled(
+                "Keybinding:",
+                Style::default().bold(),
+            )));
+            lines.push(Line::from(Span::styled(
+                entry
+                    .keybinding
+                    .map_or_else(|| "unbound".to_string(), |k| k.to_string()),
+                Style::default().fg(colorscheme.mode.channel).bold(),
+            )));
+        }
+
+        // Requirements section
+        if !entry.requirements.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "Requirements:",
+                Style::default().bold(),
+            )));
+            lines.push(Line::from(
+                entry
+                    .requirements
+                    .iter()
+                    .map(|requirement| {
+                        Span::styled(
+                            format!("{} ", requirement.bin_name),
+                            Style::default()
+                                .fg(if requirement.is_met() {
+                                    Color::Green
+                                } else {
+                                    Color::Red
+                                })
+                                .bold()
+                                .italic(),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            ));
         }
 
         lines
@@ -122,26 +194,58 @@ fn draw_detail_panel(
     f.render_widget(paragraph, rect);
 }
 
+/// The channel a past query was used in, when, and what was picked with it.
+fn history_details<'a>(
+    entry: &'a HistoryEntry,
+    colorscheme: &Colorscheme,
+) -> Vec<Line<'a>> {
+    let mut lines = vec![
+        Line::from(Span::styled("Channel:", Style::default().bold())),
+        Line::from(Span::styled(
+            entry.channel.as_str(),
+            Style::default().fg(colorscheme.mode.channel).bold(),
+        )),
+        Line::from(""),
+        Line::from(Span::styled("Last used:", Style::default().bold())),
+        Line::from(Span::styled(
+            format_age(current_timestamp().saturating_sub(entry.timestamp)),
+            Style::default().italic(),
+        )),
+        Line::from(""),
+        Line::from(Span::styled("Picked:", Style::default().bold())),
+    ];
+    if entry.selected.is_empty() {
+        lines.push(Line::from("Nothing recorded."));
+    } else {
+        lines.extend(entry.selected.iter().map(|raw| {
+            Line::from(Span::styled(
+                raw.as_str(),
+                Style::default().fg(colorscheme.preview.title_fg),
+            ))
+        }));
+    }
+    lines
+}
+
 fn draw_search_panel(
     f: &mut Frame,
     area: Rect,
+    title: &str,
     entries: &[ActionEntry],
     picker_state: &mut ListState,
     colorscheme: &Colorscheme,
     input: &mut Input,
 ) -> Result<()> {
-    let layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Fill(1), Constraint::Length(3)].as_ref())
-        .split(area);
+    let layout = search_panel_rows(area);
 
-    draw_action_list(f, layout[0], entries, picker_state, colorscheme);
+    draw_action_list(f, layout[0], title, entries, picker_state, colorscheme);
     draw_input(f, layout[1], input, colorscheme)
 }
 
 fn draw_action_list(
     f: &mut Frame,
     area: Rect,
+    title: &str,
     entries: &[ActionEntry],
     picker_state: &mut ListState,
     colorscheme: &Colorscheme,
@@ -151,7 +255,7 @@ fn draw_action_list(
         .border_type(BorderType::Rounded)
         .border_style(Style::default().fg(colorscheme.general.border_fg))
         .title_top(
-            Line::from(" Actions ")
+            Line::from(title.to_string())
                 .alignment(Alignment::Center)
                 .italic(),
         )
@@ -161,15 +265,32 @@ fn draw_action_list(
         )
         .padding(Padding::right(1));
 
-    let action_list = result_item::build_results_list(
-        block,
-        entries,
-        picker_state,
-        ListDirection::TopToBottom,
-        &colorscheme.results,
-        area.width,
-        |_| None,
-    );
+    // actions with unmet requirements are greyed out
+    let action_list = List::new(entries.iter().enumerate().map(|(i, e)| {
+        let result_fg = if !e.is_runnable() {
+            colorscheme.general.border_fg
+        } else if picker_state.selected() == Some(i) {
+            colorscheme.results.result_selected_fg
+        } else {
+            colorscheme.results.result_fg
+        };
+        result_item::build_result_line(
+            e,
+            colorscheme.results.result_selected_fg,
+            result_fg,
+            colorscheme.results.match_foreground_color,
+            area.width,
+            None,
+        )
+    }))
+    .direction(ListDirection::TopToBottom)
+    .highlight_style(
+        Style::default()
+            .bg(colorscheme.results.result_selected_bg)
+            .bold(),
+    )
+    .highlight_symbol(POINTER_SYMBOL)
+    .block(block);
 
     f.render_stateful_widget(action_list, area, picker_state);
 }
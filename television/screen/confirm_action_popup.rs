@@ -0,0 +1,184 @@
+use crate::{screen::colors::Colorscheme, television::ConfirmActionPopup};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph, Wrap},
+};
+
+const MIN_POPUP_WIDTH: u16 = 30;
+const MIN_POPUP_HEIGHT: u16 = 8;
+const MAX_POPUP_WIDTH: u16 = 100;
+/// Maximum number of affected entries listed before collapsing the rest.
+const MAX_LISTED_ENTRIES: usize = 10;
+
+/// Draws a centered yes/no dialog asking the user to confirm an external
+/// action.
+///
+/// The dialog shows the formatted confirmation message, the command that
+/// will be run and the entries it applies to.
+pub fn draw_confirm_action_popup(
+    f: &mut Frame<'_>,
+    area: Rect,
+    popup: &ConfirmActionPopup,
+    colorscheme: &Colorscheme,
+) {
+    let content = generate_popup_content(popup, colorscheme);
+    let (popup_width, popup_height) =
+        calculate_popup_size(&content, area.width, area.height);
+    let popup_area = centered_rect(popup_width, popup_height, area);
+
+    if popup_area.width < MIN_POPUP_WIDTH
+        || popup_area.height < MIN_POPUP_HEIGHT
+    {
+        return;
+    }
+
+    f.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colorscheme.input.results_count_fg))
+        .title_top(
+            Line::from(Span::styled(
+                format!(" {} ", popup.action_name),
+                Style::default()
+                    .fg(colorscheme.input.results_count_fg)
+                    .bold(),
+            ))
+            .alignment(Alignment::Center),
+        )
+        .title_bottom(
+            Line::from(vec![
+                Span::styled(
+                    " [y]",
+                    Style::default().fg(colorscheme.mode.channel).bold(),
+                ),
+                Span::styled(
+                    "es / ",
+                    Style::default().fg(colorscheme.general.border_fg),
+                ),
+                Span::styled(
+                    "[n]",
+                    Style::default()
+                        .fg(colorscheme.input.results_count_fg)
+                        .bold(),
+                ),
+                Span::styled(
+                    "o ",
+                    Style::default().fg(colorscheme.general.border_fg),
+                ),
+            ])
+            .alignment(Alignment::Center),
+        )
+        .style(
+            Style::default()
+                .bg(colorscheme.general.background.unwrap_or_default()),
+        )
+        .padding(Padding::horizontal(1));
+
+    let paragraph = Paragraph::new(content)
+        .block(block)
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(paragraph, popup_area);
+}
+
+fn generate_popup_content(
+    popup: &ConfirmActionPopup,
+    colorscheme: &Colorscheme,
+) -> Vec<Line<'static>> {
+    let field_style =
+        Style::default().fg(colorscheme.help.metadata_field_name_fg);
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            popup.message.clone(),
+            Style::default().fg(colorscheme.input.input_fg).bold(),
+        )),
+        Line::from(""),
+        Line::from(Span::styled("Command:", field_style)),
+        Line::from(Span::styled(
+            format!("  {}", popup.command),
+            Style::default().fg(colorscheme.preview.title_fg),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("Entries ({}):", popup.entries.len()),
+            field_style,
+        )),
+    ];
+
+    for entry in popup.entries.iter().take(MAX_LISTED_ENTRIES) {
+        lines.push(Line::from(vec![
+            Span::styled(
+                "  - ",
+                Style::default().fg(colorscheme.general.border_fg),
+            ),
+            Span::styled(
+                entry.clone(),
+                Style::default().fg(colorscheme.help.metadata_field_value_fg),
+            ),
+        ]));
+    }
+    if popup.entries.len() > MAX_LISTED_ENTRIES {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  … and {} more",
+                popup.entries.len() - MAX_LISTED_ENTRIES
+            ),
+            Style::default().fg(colorscheme.general.border_fg).italic(),
+        )));
+    }
+    lines.push(Line::from(""));
+
+    lines
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn calculate_popup_size(
+    content: &[Line<'_>],
+    max_width: u16,
+    max_height: u16,
+) -> (u16, u16) {
+    let content_width =
+        content.iter().map(Line::width).max().unwrap_or_default();
+
+    let required_width = (content_width + 4)
+        .min(MAX_POPUP_WIDTH as usize)
+        .min(max_width as usize) as u16;
+    let required_width = required_width.max(MIN_POPUP_WIDTH);
+
+    // account for long lines (e.g. commands) wrapping inside the popup
+    let inner_width = required_width.saturating_sub(4).max(1) as usize;
+    let content_height: usize = content
+        .iter()
+        .map(|line| line.width().div_ceil(inner_width).max(1))
+        .sum();
+    let required_height = (content_height + 2).min(max_height as usize) as u16;
+    let required_height = required_height.max(MIN_POPUP_HEIGHT);
+
+    (required_width, required_height)
+}
+
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let vertical_layout = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(height),
+        Constraint::Fill(1),
+    ])
+    .split(area);
+
+    let horizontal_layout = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(width),
+        Constraint::Fill(1),
+    ])
+    .split(vertical_layout[1]);
+
+    horizontal_layout[1]
+}
@@ -60,7 +60,10 @@ pub fn draw_help_panel(
 /// Checks if an action is relevant for the given mode
 fn is_action_relevant_for_mode(action: &Action, mode: Mode) -> bool {
     match mode {
-        Mode::Channel | Mode::ActionPicker => {
+        Mode::Channel
+        | Mode::ActionPicker
+        | Mode::CommandPalette
+        | Mode::HistoryPicker => {
             // Channel mode - all actions except those specifically for remote mode switching
             match action {
                 // Input actions - available in both modes
@@ -73,14 +76,21 @@ fn is_action_relevant_for_mode(action: &Action, mode: Mode) -> bool {
                 | Action::GoToNextChar
                 | Action::GoToInputStart
                 | Action::GoToInputEnd
+                // Vim mode - channel specific
+                | Action::EnterInsertMode
+                | Action::EnterNormalMode
                 // Navigation actions - available in both modes
                 | Action::SelectNextEntry
                 | Action::SelectPrevEntry
                 | Action::SelectNextPage
                 | Action::SelectPrevPage
+                | Action::SelectFirstEntry
+                | Action::SelectLastEntry
                 // Selection actions - channel specific (multi-select)
                 | Action::ToggleSelectionDown
                 | Action::ToggleSelectionUp
+                | Action::DeselectEntry
+                | Action::ToggleVisualMode
                 | Action::ConfirmSelection
                 // Preview actions - channel specific
                 | Action::ScrollPreviewUp
@@ -93,13 +103,19 @@ fn is_action_relevant_for_mode(action: &Action, mode: Mode) -> bool {
                 | Action::ReloadSource
                 | Action::CycleSources
                 | Action::CyclePreviews
+                | Action::CycleMatchMode
+                | Action::ToggleCaseSensitivity
+                | Action::ToggleSort
                 | Action::SelectPrevHistory
                 | Action::SelectNextHistory
+                | Action::ToggleHistoryPicker
                 // UI toggles - global
                 | Action::ToggleRemoteControl
                 | Action::ToggleActionPicker
+                | Action::ToggleCommandPalette
                 | Action::ToggleHelp
                 | Action::ToggleStatusBar
+                | Action::ToggleNotificationHistory
                 // Channel-mode layout
                 | Action::ToggleOrientation
                 // Application actions - global
@@ -116,11 +132,22 @@ fn is_action_relevant_for_mode(action: &Action, mode: Mode) -> bool {
                 | Action::Suspend
                 | Action::Resume
                 | Action::Error(_)
+                | Action::Notify(_, _)
                 | Action::OpenEntry
                 | Action::SwitchToChannel(_)
                 | Action::WatchTimer
+                | Action::ReloadConfig
                 | Action::SelectEntryAtPosition(_, _)
                 | Action::MouseClickAt(_, _)
+                | Action::InsertText(_)
+                | Action::SetInput(_)
+                | Action::ToggleSelectionAtPosition(_, _)
+                | Action::SelectRangeToPosition(_, _)
+                | Action::MouseDragTo(_, _)
+                | Action::MouseRelease
+                | Action::SubmitActionInputs(_, _)
+                | Action::RunExternalAction(_, _)
+                | Action::ShowActionOutput(_)
                 | Action::Expect(_)
                 | Action::SelectAndExit => false,
             }
@@ -143,12 +170,15 @@ fn is_action_relevant_for_mode(action: &Action, mode: Mode) -> bool {
                 | Action::SelectPrevEntry
                 | Action::SelectNextPage
                 | Action::SelectPrevPage
+                | Action::SelectFirstEntry
+                | Action::SelectLastEntry
                 // Selection in remote mode - just confirm (no multi-select)
                 | Action::ConfirmSelection
                 // UI toggles - global
                 | Action::ToggleRemoteControl
                 | Action::ToggleHelp
                 | Action::ToggleStatusBar
+                | Action::ToggleNotificationHistory
                 // Application actions - global
                 | Action::Quit => true,
 
@@ -170,7 +200,16 @@ fn add_keybinding_lines_for_keys(
     // Collect all valid keybinding entries
     let mut entries: Vec<(String, String)> = Vec::new();
 
-    for (key, actions) in keybindings.iter() {
+    let bindings = keybindings
+        .iter()
+        .map(|(key, actions)| (key.to_string(), actions))
+        .chain(
+            keybindings
+                .sequences
+                .iter()
+                .map(|(sequence, actions)| (sequence.to_string(), actions)),
+        );
+    for (key_string, actions) in bindings {
         for action in actions.as_slice() {
             // Filter out NoOp actions (unbound keys)
             // Filter out actions not relevant for current mode
@@ -181,7 +220,6 @@ fn add_keybinding_lines_for_keys(
             }
 
             let description = action.description();
-            let key_string = key.to_string();
             entries.push((description.to_string(), key_string.clone()));
             trace!(
                 "Added keybinding: {} -> {} ({})",
@@ -271,6 +309,8 @@ fn generate_help_content(
         Mode::Channel => "Channel Mode",
         Mode::RemoteControl => "Remote Control Mode",
         Mode::ActionPicker => "Action Picker Mode",
+        Mode::CommandPalette => "Command Palette Mode",
+        Mode::HistoryPicker => "History Picker Mode",
     };
 
     lines.push(Line::from(vec![Span::styled(
@@ -281,15 +321,26 @@ fn generate_help_content(
             .underlined(),
     )]));
 
+    let mut help_keybindings: Keybindings = config
+        .input_map
+        .global_keybindings
+        .iter()
+        .map(|(key, actions)| (*key, actions.first().unwrap().clone()))
+        .collect::<Vec<_>>()
+        .into();
+    help_keybindings.sequences = config
+        .input_map
+        .global_keybindings
+        .sequences
+        .iter()
+        .map(|(sequence, actions)| {
+            (sequence.clone(), actions.first().unwrap().clone().into())
+        })
+        .collect();
+
     add_keybinding_lines_for_keys(
         &mut lines,
-        &config
-            .input_map
-            .global_keybindings
-            .iter()
-            .map(|(key, actions)| (*key, actions.first().unwrap().clone()))
-            .collect::<Vec<_>>()
-            .into(),
+        &help_keybindings,
         mode,
         colorscheme,
         mode_name,
@@ -339,7 +390,10 @@ fn create_compact_keybinding_line(
 ) -> Line<'static> {
     // Use the appropriate mode color
     let key_color = match mode {
-        Mode::Channel | Mode::ActionPicker => colorscheme.mode.channel,
+        Mode::Channel
+        | Mode::ActionPicker
+        | Mode::CommandPalette
+        | Mode::HistoryPicker => colorscheme.mode.channel,
         Mode::RemoteControl => colorscheme.mode.remote_control,
     };
 
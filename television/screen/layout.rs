@@ -13,6 +13,7 @@ use clap::ValueEnum;
 use ratatui::layout::{
     self, Constraint, Direction, Layout as RatatuiLayout, Rect,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
@@ -34,7 +35,16 @@ impl From<u16> for Dimensions {
 }
 
 #[derive(
-    Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Hash, Eq,
+    Debug,
+    Clone,
+    Copy,
+    Deserialize,
+    Serialize,
+    Default,
+    PartialEq,
+    Hash,
+    Eq,
+    JsonSchema,
 )]
 pub enum InputPosition {
     #[serde(rename = "top")]
@@ -73,6 +83,7 @@ impl From<crate::cli::args::InputPosition> for InputPosition {
     Eq,
     Hash,
     ValueEnum,
+    JsonSchema,
 )]
 pub enum Orientation {
     #[serde(rename = "landscape")]
@@ -406,8 +417,12 @@ impl Layout {
             None
         };
 
-        // the action picker is a centered popup (similar to remote control but simpler)
-        let action_picker = if mode == Mode::ActionPicker {
+        // the action picker (and command palette) is a centered popup
+        // (similar to remote control but simpler)
+        let action_picker = if matches!(
+            mode,
+            Mode::ActionPicker | Mode::CommandPalette | Mode::HistoryPicker
+        ) {
             let action_picker_rect = centered_rect_with_dimensions(
                 &Dimensions::new(
                     area.width * ACTION_PICKER_WIDTH_PERCENTAGE / 100,
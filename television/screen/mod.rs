@@ -1,5 +1,8 @@
+pub mod action_input_popup;
+pub mod action_output_popup;
 pub mod action_picker;
 pub mod colors;
+pub mod confirm_action_popup;
 pub mod constants;
 pub mod help_panel;
 pub mod input;
@@ -7,6 +10,7 @@ pub mod keybindings;
 pub mod layout;
 pub mod logo;
 pub mod missing_requirements_popup;
+pub mod notifications;
 pub mod preview;
 pub mod remote_control;
 pub mod result_item;
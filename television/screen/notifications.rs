@@ -0,0 +1,243 @@
+use crate::{
+    notifications::{Notification, Notifications, Severity},
+    screen::colors::Colorscheme,
+    television::NotificationHistoryPopup,
+};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph, Wrap},
+};
+use std::time::{Duration, Instant};
+
+const MIN_TOAST_WIDTH: u16 = 20;
+const MAX_TOAST_WIDTH: u16 = 50;
+/// Maximum number of message lines shown in a toast.
+const MAX_TOAST_LINES: u16 = 4;
+const MIN_POPUP_WIDTH: u16 = 30;
+const MIN_POPUP_HEIGHT: u16 = 6;
+/// Percentage of the available area the history panel spans in each
+/// direction.
+const POPUP_SIZE_PERCENT: u16 = 70;
+
+fn severity_color(severity: Severity, colorscheme: &Colorscheme) -> Color {
+    match severity {
+        Severity::Info => colorscheme.mode.channel,
+        Severity::Success => Color::Green,
+        Severity::Warning => Color::Yellow,
+        Severity::Error => Color::Red,
+    }
+}
+
+/// Draws the active notifications as toasts stacked in the top right corner
+/// of `area`, newest first.
+pub fn draw_notifications(
+    f: &mut Frame<'_>,
+    area: Rect,
+    notifications: &Notifications,
+    now: Instant,
+    colorscheme: &Colorscheme,
+) {
+    // leave the panel's border and title visible
+    let area = Rect {
+        x: area.x + 1,
+        y: area.y + 1,
+        width: area.width.saturating_sub(3),
+        height: area.height.saturating_sub(2),
+    };
+    let width = (area.width * 2 / 5).clamp(MIN_TOAST_WIDTH, MAX_TOAST_WIDTH);
+    if area.width < width {
+        return;
+    }
+
+    let mut y = area.y;
+    for notification in notifications.active(now) {
+        // borders and horizontal padding
+        let text_width = usize::from(width - 4);
+        let lines = u16::try_from(
+            notification.message.chars().count().div_ceil(text_width),
+        )
+        .unwrap_or(MAX_TOAST_LINES)
+        .clamp(1, MAX_TOAST_LINES);
+        let height = lines + 2;
+        if y + height > area.bottom() {
+            break;
+        }
+        let toast_area = Rect::new(area.right() - width, y, width, height);
+        draw_toast(f, toast_area, notification, colorscheme);
+        y += height;
+    }
+}
+
+fn draw_toast(
+    f: &mut Frame<'_>,
+    area: Rect,
+    notification: &Notification,
+    colorscheme: &Colorscheme,
+) {
+    let color = severity_color(notification.severity, colorscheme);
+    let mut title = vec![Span::styled(
+        format!(" {} ", notification.severity),
+        Style::default().fg(color).bold(),
+    )];
+    if notification.count > 1 {
+        title.push(Span::styled(
+            format!("×{} ", notification.count),
+            Style::default().fg(colorscheme.general.border_fg),
+        ));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(color))
+        .title_top(Line::from(title).alignment(Alignment::Left))
+        .style(
+            Style::default()
+                .bg(colorscheme.general.background.unwrap_or_default()),
+        )
+        .padding(Padding::horizontal(1));
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(notification.message.as_str())
+            .style(Style::default().fg(colorscheme.results.result_fg))
+            .wrap(Wrap { trim: true })
+            .block(block),
+        area,
+    );
+}
+
+/// Draws a centered panel listing every notification raised during the
+/// session, newest first.
+pub fn draw_notification_history_popup(
+    f: &mut Frame<'_>,
+    area: Rect,
+    popup: &NotificationHistoryPopup,
+    notifications: &Notifications,
+    now: Instant,
+    colorscheme: &Colorscheme,
+) {
+    let popup_area = centered_rect(
+        (area.width * POPUP_SIZE_PERCENT / 100).max(MIN_POPUP_WIDTH),
+        (area.height * POPUP_SIZE_PERCENT / 100).max(MIN_POPUP_HEIGHT),
+        area,
+    );
+
+    if popup_area.width < MIN_POPUP_WIDTH
+        || popup_area.height < MIN_POPUP_HEIGHT
+    {
+        return;
+    }
+
+    f.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colorscheme.general.border_fg))
+        .title_top(
+            Line::from(Span::styled(
+                " Notifications ",
+                Style::default().fg(colorscheme.mode.channel).bold(),
+            ))
+            .alignment(Alignment::Center),
+        )
+        .title_bottom(
+            Line::from(Span::styled(
+                " Enter/Esc: dismiss · ↑/↓: scroll ",
+                Style::default().fg(colorscheme.general.border_fg).italic(),
+            ))
+            .alignment(Alignment::Left),
+        )
+        .style(
+            Style::default()
+                .bg(colorscheme.general.background.unwrap_or_default()),
+        )
+        .padding(Padding::horizontal(1));
+
+    let lines: Vec<Line<'_>> = if notifications.is_empty() {
+        vec![Line::from(Span::styled(
+            "(no notifications)",
+            Style::default().fg(colorscheme.general.border_fg).italic(),
+        ))]
+    } else {
+        notifications
+            .history()
+            .skip(popup.scroll.into())
+            .map(|notification| history_line(notification, now, colorscheme))
+            .collect()
+    };
+
+    f.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(block),
+        popup_area,
+    );
+}
+
+fn history_line<'a>(
+    notification: &'a Notification,
+    now: Instant,
+    colorscheme: &Colorscheme,
+) -> Line<'a> {
+    let mut spans = vec![
+        Span::styled(
+            format!(
+                "{:>4} ",
+                format_elapsed(
+                    now.saturating_duration_since(notification.created)
+                )
+            ),
+            Style::default().fg(colorscheme.general.border_fg),
+        ),
+        Span::styled(
+            format!("{:<8}", notification.severity),
+            Style::default()
+                .fg(severity_color(notification.severity, colorscheme))
+                .bold(),
+        ),
+        Span::styled(
+            notification.message.as_str(),
+            Style::default().fg(colorscheme.results.result_fg),
+        ),
+    ];
+    if notification.count > 1 {
+        spans.push(Span::styled(
+            format!(" ×{}", notification.count),
+            Style::default().fg(colorscheme.general.border_fg),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// Formats the time elapsed since a notification was raised, e.g. `12s`.
+fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m", secs / 60),
+        _ => format!("{}h", secs / 3600),
+    }
+}
+
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let vertical_layout = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(height),
+        Constraint::Fill(1),
+    ])
+    .split(area);
+
+    let horizontal_layout = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(width),
+        Constraint::Fill(1),
+    ])
+    .split(vertical_layout[1]);
+
+    horizontal_layout[1]
+}
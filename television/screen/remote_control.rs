@@ -21,6 +21,7 @@ use ratatui::{
         Paragraph, Wrap,
     },
 };
+use std::rc::Rc;
 
 #[allow(clippy::too_many_arguments)]
 pub fn draw_remote_control(
@@ -33,18 +34,7 @@ pub fn draw_remote_control(
     show_channel_descriptions: bool,
 ) -> Result<()> {
     let show_logo = rect.height >= REMOTE_LOGO_HEIGHT_U16;
-    let mut constraints = vec![Constraint::Fill(1)];
-    if show_channel_descriptions {
-        constraints.push(Constraint::Fill(1));
-    }
-    if show_logo {
-        constraints.push(Constraint::Length(REMOTE_LOGO_WIDTH_U16 + 2));
-    }
-
-    let layout = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints(constraints)
-        .split(rect);
+    let layout = columns(rect, show_channel_descriptions);
 
     // Clear the popup area
     f.render_widget(Clear, rect);
@@ -70,6 +60,41 @@ pub fn draw_remote_control(
     Ok(())
 }
 
+/// Splits the remote control into the search panel, the optional
+/// information panel and the logo.
+fn columns(rect: Rect, show_channel_descriptions: bool) -> Rc<[Rect]> {
+    let mut constraints = vec![Constraint::Fill(1)];
+    if show_channel_descriptions {
+        constraints.push(Constraint::Fill(1));
+    }
+    if rect.height >= REMOTE_LOGO_HEIGHT_U16 {
+        constraints.push(Constraint::Length(REMOTE_LOGO_WIDTH_U16 + 2));
+    }
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(constraints)
+        .split(rect)
+}
+
+fn search_panel_rows(area: Rect) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Fill(1), Constraint::Length(3)].as_ref())
+        .split(area)
+}
+
+/// The area in which the channels of a remote control drawn in `rect` are
+/// listed, one per row.
+pub fn channel_list_area(rect: Rect, show_channel_descriptions: bool) -> Rect {
+    Block::default()
+        .borders(Borders::ALL)
+        .padding(Padding::right(1))
+        .inner(
+            search_panel_rows(columns(rect, show_channel_descriptions)[0])[0],
+        )
+}
+
 fn draw_information_panel(
     f: &mut Frame,
     rect: Rect,
@@ -203,10 +228,7 @@ fn draw_search_panel(
     colorscheme: &Colorscheme,
     input: &mut Input,
 ) -> Result<()> {
-    let layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Fill(1), Constraint::Length(3)].as_ref())
-        .split(area);
+    let layout = search_panel_rows(area);
 
     draw_rc_channels(f, layout[0], entries, picker_state, colorscheme);
     draw_rc_input(f, layout[1], input, colorscheme)
@@ -14,6 +14,36 @@ use ratatui::{
 };
 use rustc_hash::FxHashSet;
 
+fn results_block(
+    title: Line<'_>,
+    padding: Padding,
+    border_type: BorderType,
+) -> Block<'_> {
+    let block = Block::default()
+        .title_top(title)
+        .padding(RatatuiPadding::from(padding));
+    match border_type.to_ratatui_border_type() {
+        Some(border_type) => {
+            block.borders(Borders::ALL).border_type(border_type)
+        }
+        None => block,
+    }
+}
+
+/// The area in which the results drawn in `rect` are listed, one per row.
+pub fn results_list_area(
+    rect: Rect,
+    results_panel_padding: &Padding,
+    results_panel_border_type: &BorderType,
+) -> Rect {
+    results_block(
+        Line::from(" Results "),
+        *results_panel_padding,
+        *results_panel_border_type,
+    )
+    .inner(rect)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn draw_results_list(
     f: &mut Frame,
@@ -57,21 +87,16 @@ pub fn draw_results_list(
         Line::from(" Results ").alignment(Alignment::Center)
     };
 
-    let mut results_block = Block::default()
-        .title_top(title)
-        .style(
-            Style::default()
-                .bg(colorscheme.general.background.unwrap_or_default()),
-        )
-        .padding(RatatuiPadding::from(*results_panel_padding));
-    if let Some(border_type) =
-        results_panel_border_type.to_ratatui_border_type()
-    {
-        results_block = results_block
-            .borders(Borders::ALL)
-            .border_type(border_type)
-            .border_style(Style::default().fg(colorscheme.general.border_fg));
-    }
+    let results_block = results_block(
+        title,
+        *results_panel_padding,
+        *results_panel_border_type,
+    )
+    .style(
+        Style::default()
+            .bg(colorscheme.general.background.unwrap_or_default()),
+    )
+    .border_style(Style::default().fg(colorscheme.general.border_fg));
 
     let list_direction = match input_bar_position {
         InputPosition::Bottom => ratatui::widgets::ListDirection::BottomToTop,
@@ -1,5 +1,8 @@
 use crate::{
-    action::Action, draw::Ctx, television::Mode, utils::strings::SPACE,
+    action::Action,
+    draw::Ctx,
+    television::{Mode, VimMode},
+    utils::strings::SPACE,
 };
 use ratatui::{
     Frame,
@@ -13,16 +16,6 @@ use ratatui::{
 
 /// Draw the status bar at the bottom of the screen
 pub fn draw_status_bar(f: &mut Frame<'_>, area: Rect, ctx: &Ctx) {
-    // Split status bar into three sections
-    let chunks = RatatuiLayout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Fill(1), // Left: mode + channel info
-            Constraint::Fill(3), // Middle: hints
-            Constraint::Fill(1), // Right: version
-        ])
-        .split(area);
-
     // === LEFT SECTION: Mode bubble and channel info ===
     let mut left_spans = vec![Span::raw(SPACE)]; // Initial spacing
 
@@ -43,6 +36,16 @@ pub fn draw_status_bar(f: &mut Frame<'_>, area: Rect, ctx: &Ctx) {
             ctx.colorscheme.mode.action_picker_fg,
             ctx.colorscheme.mode.action_picker,
         ),
+        Mode::CommandPalette => (
+            "COMMANDS",
+            ctx.colorscheme.mode.action_picker_fg,
+            ctx.colorscheme.mode.action_picker,
+        ),
+        Mode::HistoryPicker => (
+            "HISTORY",
+            ctx.colorscheme.mode.action_picker_fg,
+            ctx.colorscheme.mode.action_picker,
+        ),
     };
 
     // Create mode bubble with separators
@@ -71,6 +74,20 @@ pub fn draw_status_bar(f: &mut Frame<'_>, area: Rect, ctx: &Ctx) {
         ));
     }
 
+    // Add the vim editing mode next to the mode bubble
+    if ctx.tv_state.mode == Mode::Channel
+        && let Some(vim_mode) = ctx.tv_state.vim_mode
+    {
+        let vim_mode_style = match vim_mode {
+            VimMode::Insert => {
+                Style::default().fg(mode_bg).add_modifier(Modifier::BOLD)
+            }
+            VimMode::Normal | VimMode::Visual => mode_style,
+        };
+        left_spans.push(Span::raw(SPACE));
+        left_spans.push(Span::styled(format!(" {vim_mode} "), vim_mode_style));
+    }
+
     // Add channel-specific info in Channel mode
     if ctx.tv_state.mode == Mode::Channel {
         let name_style = Style::default()
@@ -101,6 +118,26 @@ pub fn draw_status_bar(f: &mut Frame<'_>, area: Rect, ctx: &Ctx) {
         }
     }
 
+    // Keys typed so far of a multi-key binding
+    if !ctx.tv_state.pending_keys.is_empty() {
+        let keys: Vec<String> = ctx
+            .tv_state
+            .pending_keys
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        left_spans.extend([
+            Span::styled(
+                " • ",
+                Style::default().fg(ctx.colorscheme.general.border_fg),
+            ),
+            Span::styled(
+                format!("{} …", keys.join(SPACE)),
+                Style::default().fg(mode_bg).add_modifier(Modifier::BOLD),
+            ),
+        ]);
+    }
+
     // === MIDDLE SECTION: Hints ===
     let mut middle_spans = Vec::new();
     let mut hint_spans = Vec::new();
@@ -108,7 +145,9 @@ pub fn draw_status_bar(f: &mut Frame<'_>, area: Rect, ctx: &Ctx) {
     // Use mode color for keybinding hints
     let key_color = match ctx.tv_state.mode {
         Mode::Channel => ctx.colorscheme.mode.channel,
-        Mode::ActionPicker => ctx.colorscheme.mode.action_picker,
+        Mode::ActionPicker | Mode::CommandPalette | Mode::HistoryPicker => {
+            ctx.colorscheme.mode.action_picker
+        }
         Mode::RemoteControl => ctx.colorscheme.mode.remote_control,
     };
 
@@ -139,7 +178,10 @@ pub fn draw_status_bar(f: &mut Frame<'_>, area: Rect, ctx: &Ctx) {
             .get_key_for_action(&Action::ToggleRemoteControl);
         if let Some(k) = key {
             let hint_text = match ctx.tv_state.mode {
-                Mode::Channel | Mode::ActionPicker => "Remote Control",
+                Mode::Channel
+                | Mode::ActionPicker
+                | Mode::CommandPalette
+                | Mode::HistoryPicker => "Remote Control",
                 Mode::RemoteControl => "Back to Channel",
             };
             add_hint(hint_text, &k.to_string());
@@ -159,6 +201,17 @@ pub fn draw_status_bar(f: &mut Frame<'_>, area: Rect, ctx: &Ctx) {
         }
     }
 
+    // Add command palette hint (Channel mode only)
+    if ctx.tv_state.mode == Mode::Channel {
+        let key = &ctx
+            .config
+            .input_map
+            .get_key_for_action(&Action::ToggleCommandPalette);
+        if let Some(k) = key {
+            add_hint("Commands", &k.to_string());
+        }
+    }
+
     // Add keybinding help hint (available in both modes)
     let key = &ctx.config.input_map.get_key_for_action(&Action::ToggleHelp);
     if let Some(k) = key {
@@ -170,13 +223,53 @@ pub fn draw_status_bar(f: &mut Frame<'_>, area: Rect, ctx: &Ctx) {
         middle_spans.extend(hint_spans);
     }
 
-    // === RIGHT SECTION: Version ===
-    let right_spans = vec![Span::styled(
+    // === RIGHT SECTION: Matching settings and version ===
+    let mut right_spans = Vec::new();
+    if ctx.tv_state.mode == Mode::Channel {
+        let setting_style =
+            Style::default().fg(ctx.colorscheme.help.metadata_field_name_fg);
+        let separator_style =
+            Style::default().fg(ctx.colorscheme.general.border_fg);
+        right_spans.extend([
+            Span::styled(
+                ctx.tv_state.matching_mode.to_string(),
+                setting_style,
+            ),
+            Span::styled(" • ", separator_style),
+            Span::styled(
+                ctx.tv_state.case_sensitivity.to_string(),
+                setting_style,
+            ),
+            Span::styled(" • ", separator_style),
+            Span::styled(
+                if ctx.tv_state.no_sort {
+                    "unsorted"
+                } else {
+                    "sorted"
+                },
+                setting_style,
+            ),
+            Span::raw("   "),
+        ]);
+    }
+    right_spans.push(Span::styled(
         format!("v{} ", ctx.app_metadata.version),
         Style::default()
             .fg(ctx.colorscheme.results.result_fg)
             .add_modifier(Modifier::ITALIC),
-    )];
+    ));
+
+    // Split status bar into three sections
+    let right_line = Line::from(right_spans);
+    let right_width = u16::try_from(right_line.width()).unwrap_or(u16::MAX);
+    let chunks = RatatuiLayout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Fill(1),          // Left: mode + channel info
+            Constraint::Fill(3),          // Middle: hints
+            Constraint::Min(right_width), // Right: matching settings + version
+        ])
+        .split(area);
 
     // Render all sections
     f.render_widget(
@@ -188,7 +281,7 @@ pub fn draw_status_bar(f: &mut Frame<'_>, area: Rect, ctx: &Ctx) {
         chunks[1],
     );
     f.render_widget(
-        Paragraph::new(Line::from(right_spans)).alignment(Alignment::Right),
+        Paragraph::new(right_line).alignment(Alignment::Right),
         chunks[2],
     );
 }
@@ -0,0 +1,164 @@
+//! Persistence of the JSON files tv keeps in its data directory (history,
+//! frecency), which may be shared by several tv instances running at once.
+//!
+//! Updates are read-modify-write cycles performed while holding an exclusive
+//! lock on a sibling `.lock` file: the latest content of the file is loaded,
+//! merged with the instance's changes and written back atomically through a
+//! temporary file. Concurrent instances therefore never overwrite each
+//! other's changes, and a crash mid-write can't leave a truncated file.
+
+use anyhow::{Context, Result};
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+use tracing::{debug, warn};
+
+/// A JSON file in the data directory.
+#[derive(Debug, Clone)]
+pub struct JsonStore {
+    path: PathBuf,
+}
+
+impl JsonStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Load the content of the file.
+    ///
+    /// A missing or empty file yields the default value. A file that can't
+    /// be parsed is moved aside (with a `.corrupt` suffix) so that it can be
+    /// inspected, and the default value is returned as well.
+    pub fn load<T>(&self) -> Result<T>
+    where
+        T: DeserializeOwned + Default,
+    {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                debug!("{} not found, starting fresh", self.path.display());
+                return Ok(T::default());
+            }
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("Failed to read {}", self.path.display())
+                });
+            }
+        };
+        if contents.trim().is_empty() {
+            debug!("{} is empty", self.path.display());
+            return Ok(T::default());
+        }
+        match serde_json::from_str(&contents) {
+            Ok(value) => Ok(value),
+            Err(e) => {
+                let backup = self.sibling("corrupt");
+                warn!(
+                    "Failed to parse {} ({e}), moving it to {} and starting fresh",
+                    self.path.display(),
+                    backup.display()
+                );
+                fs::rename(&self.path, &backup).with_context(|| {
+                    format!("Failed to move {} aside", self.path.display())
+                })?;
+                Ok(T::default())
+            }
+        }
+    }
+
+    /// Apply `f` to the latest content of the file and write the result back,
+    /// while holding the file's lock.
+    ///
+    /// Returns the value that was written.
+    pub fn update<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned + Default,
+        F: FnOnce(&mut T),
+    {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create {}", parent.display())
+            })?;
+        }
+        let lock_path = self.sibling("lock");
+        let lock = File::create(&lock_path).with_context(|| {
+            format!("Failed to open {}", lock_path.display())
+        })?;
+        lock.lock().with_context(|| {
+            format!("Failed to lock {}", lock_path.display())
+        })?;
+
+        let mut value = self.load()?;
+        f(&mut value);
+        self.write(&value)?;
+        // the lock is released when `lock` is dropped
+        Ok(value)
+    }
+
+    fn write<T: Serialize>(&self, value: &T) -> Result<()> {
+        let contents = serde_json::to_string_pretty(value)
+            .context("Failed to serialize data")?;
+        let tmp_path = self.sibling("tmp");
+        let mut tmp = File::create(&tmp_path).with_context(|| {
+            format!("Failed to create {}", tmp_path.display())
+        })?;
+        tmp.write_all(contents.as_bytes())
+            .and_then(|()| tmp.sync_all())
+            .with_context(|| {
+                format!("Failed to write {}", tmp_path.display())
+            })?;
+        fs::rename(&tmp_path, &self.path).with_context(|| {
+            format!("Failed to write {}", self.path.display())
+        })?;
+        debug!("Saved {}", self.path.display());
+        Ok(())
+    }
+
+    /// `<file>.<extension>`, next to the file.
+    fn sibling(&self, extension: &str) -> PathBuf {
+        let mut name = self
+            .path
+            .file_name()
+            .map(ToOwned::to_owned)
+            .unwrap_or_default();
+        name.push(".");
+        name.push(extension);
+        self.path.with_file_name(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_update_merges_with_latest_content() {
+        let dir = tempdir().unwrap();
+        let store = JsonStore::new(dir.path().join("data.json"));
+        let other = JsonStore::new(dir.path().join("data.json"));
+
+        store.update(|v: &mut Vec<u32>| v.push(1)).unwrap();
+        other.update(|v: &mut Vec<u32>| v.push(2)).unwrap();
+        assert_eq!(store.load::<Vec<u32>>().unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_corrupt_file_is_moved_aside() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("data.json");
+        fs::write(&path, "[1, 2").unwrap();
+
+        let store = JsonStore::new(path);
+        assert_eq!(store.load::<Vec<u32>>().unwrap(), Vec::<u32>::new());
+        assert!(dir.path().join("data.json.corrupt").exists());
+        assert_eq!(store.update(|v: &mut Vec<u32>| v.push(3)).unwrap(), [3]);
+    }
+}
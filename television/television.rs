@@ -1,21 +1,33 @@
 use crate::{
-    action::Action,
-    cable::Cable,
+    action::{Action, command_palette_actions},
+    cable::{Cable, load_cable},
     channels::{
         action_picker::{ActionEntry, ActionPicker},
         channel::ChannelKind as CableChannel,
         entry::Entry,
-        prototypes::{ChannelPrototype, CommandSpec, Template},
+        prototypes::{
+            ActionInput, ActionSpec, ChannelPrototype, CommandSpec,
+            PostAction, Template,
+        },
         remote_control::{CableEntry, RemoteControl},
     },
     config::{
-        Theme,
+        Config, Theme,
         layers::{ConfigLayers, MergedConfig},
+        ui::ThemeOverrides,
     },
     draw::{ChannelState, Ctx, TvState},
     errors::os_error_exit,
-    frecency::FrecencyHandle,
+    frecency::{ChannelFrecency, FrecencyHandle},
+    history::HistoryEntry,
     input::convert_action_to_input_request,
+    keymap::PendingKeys,
+    matcher::{
+        config::{CaseSensitivity, MatchingMode},
+        query::Query,
+    },
+    mouse::{PreviewBorder, list_row_at},
+    notifications::{Notifications, Severity},
     picker::{Movement, Picker},
     previewer::{
         Config as PreviewerConfig, Preview, Previewer,
@@ -23,30 +35,44 @@ use crate::{
     },
     render::UiState,
     screen::{
+        action_picker::action_list_area,
         colors::Colorscheme,
         layout::{InputPosition, Orientation},
+        remote_control::channel_list_area,
+        results::results_list_area,
     },
     utils::{
         clipboard::CLIPBOARD,
+        command::{format_action_command, format_command},
+        input::Input,
         metadata::AppMetadata,
         strings::{EMPTY_STRING, SPACE},
     },
 };
 use anyhow::Result;
-use ratatui::layout::Rect;
+use ratatui::{
+    layout::{Position, Rect},
+    widgets::ListDirection,
+};
 use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
-use std::{fmt::Display, sync::Arc};
+use std::{
+    fmt::Display,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::sync::mpsc::{
     UnboundedReceiver, UnboundedSender, unbounded_channel,
 };
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 #[derive(PartialEq, Copy, Clone, Hash, Eq, Debug, Serialize, Deserialize)]
 pub enum Mode {
     Channel,
     RemoteControl,
     ActionPicker,
+    CommandPalette,
+    HistoryPicker,
 }
 
 impl Display for Mode {
@@ -55,10 +81,45 @@ impl Display for Mode {
             Mode::Channel => write!(f, "Channel"),
             Mode::RemoteControl => write!(f, "Remote Control"),
             Mode::ActionPicker => write!(f, "Action Picker"),
+            Mode::CommandPalette => write!(f, "Command Palette"),
+            Mode::HistoryPicker => write!(f, "History Picker"),
+        }
+    }
+}
+
+/// Editing mode of the input when vim mode is enabled.
+#[derive(PartialEq, Copy, Clone, Hash, Eq, Debug, Serialize, Deserialize)]
+pub enum VimMode {
+    /// Printable keys are typed into the input.
+    Insert,
+    /// Printable keys are looked up in the normal mode keybindings.
+    Normal,
+    /// Like normal mode, but moving the cursor selects every entry between
+    /// the anchor and the cursor.
+    Visual,
+}
+
+impl Display for VimMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VimMode::Insert => write!(f, "INSERT"),
+            VimMode::Normal => write!(f, "NORMAL"),
+            VimMode::Visual => write!(f, "VISUAL"),
         }
     }
 }
 
+/// Range selection started with `Action::ToggleVisualMode`.
+#[derive(Debug, Clone)]
+struct VisualSelection {
+    /// Index of the entry the selection started from.
+    anchor: usize,
+    /// Index of the cursor the range was last computed for.
+    cursor: usize,
+    /// Entries that were selected before entering visual mode.
+    initial: FxHashSet<Entry>,
+}
+
 /// State for the missing requirements popup dialog.
 ///
 /// This popup is shown when a user attempts to switch to a channel
@@ -69,10 +130,143 @@ pub struct MissingRequirementsPopup {
     pub missing_requirements: Vec<String>,
 }
 
-#[derive(PartialEq, Copy, Clone, Hash, Eq, Debug, Serialize, Deserialize)]
-pub enum MatchingMode {
-    Substring,
-    Fuzzy,
+/// State for the panel showing the output of an action run in
+/// `ExecutionMode::Capture`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ActionOutputPopup {
+    pub action_name: String,
+    /// The fully formatted command that was run
+    pub command: String,
+    pub stdout: String,
+    pub stderr: String,
+    /// The command's exit code (`None` if it was terminated by a signal)
+    pub exit_code: Option<i32>,
+    /// Vertical scroll offset of the output, in lines
+    pub scroll: u16,
+    /// Post-actions to apply once the panel is dismissed
+    pub after: Vec<PostAction>,
+}
+
+impl ActionOutputPopup {
+    /// The number of output lines (stdout followed by stderr).
+    pub fn line_count(&self) -> usize {
+        self.stdout.lines().count() + self.stderr.lines().count()
+    }
+
+    fn scroll_by(&mut self, delta: i16) {
+        let max_scroll = u16::try_from(self.line_count().saturating_sub(1))
+            .unwrap_or(u16::MAX);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max_scroll);
+    }
+}
+
+/// State for the panel listing the notifications raised during the session.
+#[derive(Debug, Clone, Default)]
+pub struct NotificationHistoryPopup {
+    /// Vertical scroll offset of the list, in notifications
+    pub scroll: u16,
+}
+
+impl NotificationHistoryPopup {
+    fn scroll_by(&mut self, delta: i16, len: usize) {
+        let max_scroll =
+            u16::try_from(len.saturating_sub(1)).unwrap_or(u16::MAX);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max_scroll);
+    }
+}
+
+/// State for the yes/no dialog shown before running an external action that
+/// defines a `confirm` message.
+#[derive(Debug, Clone)]
+pub struct ConfirmActionPopup {
+    pub action_name: String,
+    /// The formatted confirmation message
+    pub message: String,
+    /// The formatted command that will run if confirmed
+    pub command: String,
+    /// The raw values of the entries the action applies to, sorted
+    pub entries: Vec<String>,
+    /// Values collected for the action's inputs, if any
+    pub inputs: Vec<(String, String)>,
+}
+
+impl ConfirmActionPopup {
+    pub fn new(
+        action_name: &str,
+        confirm: &Template,
+        action_spec: &ActionSpec,
+        entries: &FxHashSet<Entry>,
+        inputs: Vec<(String, String)>,
+    ) -> Result<Self> {
+        let message = action_spec.fill_inputs(
+            &format_command(entries, confirm, &action_spec.separator)?,
+            |value| Ok(value.to_string()),
+        )?;
+        let command = format_action_command(action_spec, entries)?;
+        let mut entries: Vec<String> =
+            entries.iter().map(|e| e.raw.clone()).collect();
+        entries.sort();
+        Ok(Self {
+            action_name: action_name.to_string(),
+            message,
+            command,
+            entries,
+            inputs,
+        })
+    }
+}
+
+/// State for the modal prompting the user for an action's inputs, one at a
+/// time.
+#[derive(Debug, Clone)]
+pub struct ActionInputPopup {
+    pub action_name: String,
+    pub inputs: Vec<ActionInput>,
+    /// Values submitted so far, in the same order as `inputs`
+    pub values: Vec<String>,
+    /// The text field for the input currently being prompted for
+    pub input: Input,
+}
+
+impl ActionInputPopup {
+    pub fn new(action_name: &str, inputs: &[ActionInput]) -> Self {
+        Self {
+            action_name: action_name.to_string(),
+            inputs: inputs.to_vec(),
+            values: Vec::with_capacity(inputs.len()),
+            input: Input::default(),
+        }
+    }
+
+    /// The input currently being prompted for.
+    pub fn current(&self) -> Option<&ActionInput> {
+        self.inputs.get(self.values.len())
+    }
+
+    /// Submit the value of the current input and move on to the next one.
+    ///
+    /// Returns the named values once every input has been submitted.
+    pub fn submit(&mut self) -> Option<Vec<(String, String)>> {
+        let value = match (self.input.value(), self.current()) {
+            (
+                "",
+                Some(ActionInput {
+                    default: Some(d), ..
+                }),
+            ) => d.clone(),
+            (value, _) => value.to_string(),
+        };
+        self.values.push(value);
+        self.input.reset();
+
+        (self.values.len() >= self.inputs.len()).then(|| {
+            self.inputs
+                .iter()
+                .map(|i| i.name.clone())
+                .zip(self.values.iter().cloned())
+                .collect()
+        })
+    }
 }
 
 pub struct Television {
@@ -86,9 +280,17 @@ pub struct Television {
     pub currently_selected: Option<Entry>,
     pub current_pattern: String,
     pub matching_mode: MatchingMode,
+    pub case_sensitivity: CaseSensitivity,
+    /// Whether results are kept in source order instead of being sorted
+    pub no_sort: bool,
     pub results_picker: Picker<Entry>,
     pub rc_picker: Picker<CableEntry>,
     pub ap_picker: Picker<ActionEntry>,
+    /// Past queries listed by the history picker, oldest first
+    pub history: Vec<HistoryEntry>,
+    /// The channel whose queries the history picker lists, all channels if
+    /// `None`
+    history_picker_channel: Option<String>,
     pub preview_state: PreviewState,
     pub preview_handles:
         Option<(UnboundedSender<PreviewRequest>, UnboundedReceiver<Preview>)>,
@@ -102,6 +304,28 @@ pub struct Television {
     was_running: bool,
     /// Popup shown when attempting to switch to a channel with missing requirements
     pub missing_requirements_popup: Option<MissingRequirementsPopup>,
+    /// Panel showing the output of the last captured external action
+    pub action_output_popup: Option<ActionOutputPopup>,
+    /// Dialog asking for confirmation before running an external action
+    pub confirm_action_popup: Option<ConfirmActionPopup>,
+    /// Modal collecting the inputs of an external action
+    pub action_input_popup: Option<ActionInputPopup>,
+    /// Errors and action results reported to the user
+    pub notifications: Notifications,
+    /// Panel listing past notifications
+    pub notification_history_popup: Option<NotificationHistoryPopup>,
+    /// Whether the user asked for a reload that hasn't completed yet
+    pending_reload: bool,
+    /// Keys typed so far of a multi-key binding
+    pub pending_keys: PendingKeys,
+    /// Current editing mode, `None` unless vim mode is enabled
+    pub vim_mode: Option<VimMode>,
+    visual_selection: Option<VisualSelection>,
+    /// When, in which mode and on which entry the last click happened, to
+    /// detect double-clicks
+    last_click: Option<(Instant, Mode, usize)>,
+    /// The preview border currently being dragged with the mouse
+    preview_resize: Option<PreviewBorder>,
 }
 
 impl Television {
@@ -117,7 +341,7 @@ impl Television {
         let merged_config = {
             // this is to keep the outer merged config immutable
             let mut m = layered_config.merge();
-            m.input_map.merge_globals_with(
+            m.merge_global_shortcuts(
                 &cable_channels.get_channels_shortcut_keybindings(),
             );
             m
@@ -128,11 +352,9 @@ impl Television {
             results_picker = results_picker.inverted();
         }
 
-        let matching_mode = if merged_config.exact_match {
-            MatchingMode::Substring
-        } else {
-            MatchingMode::Fuzzy
-        };
+        let matching_mode = merged_config.match_mode;
+        let case_sensitivity = merged_config.case_sensitivity;
+        let no_sort = merged_config.no_sort;
 
         // previewer
         let preview_handles = merged_config
@@ -141,6 +363,7 @@ impl Television {
             .map(|command| {
                 Self::setup_previewer(
                     command,
+                    action_tx.clone(),
                     merged_config.channel_preview_cached,
                     merged_config.channel_preview_offset.clone(),
                     merged_config.preview_panel_header.clone(),
@@ -148,12 +371,10 @@ impl Television {
                 )
             });
 
-        let frecency_config =
-            if merged_config.channel_frecency && !merged_config.no_sort {
-                Some((frecency.clone(), merged_config.channel_name.clone()))
-            } else {
-                None
-            };
+        // frecency is only used while sorting is enabled (see `toggle_sort`)
+        let frecency_config = merged_config
+            .channel_frecency
+            .then(|| channel_frecency(&frecency, &merged_config));
 
         let mut channel = CableChannel::new(
             merged_config.channel_source_command.clone(),
@@ -163,6 +384,7 @@ impl Television {
             merged_config.channel_source_output.clone(),
             merged_config.channel_preview_command.is_some(),
             merged_config.no_sort,
+            merged_config.sort.clone(),
             frecency_config,
             merged_config.is_stdin,
         );
@@ -174,14 +396,10 @@ impl Television {
                 .to_string_lossy()
                 .to_string(),
         );
-        let base_theme = Theme::from_name(&merged_config.theme);
-        let theme = base_theme
-            .merge_with_overrides(&merged_config.theme_overrides)
-            .unwrap_or_else(|e| {
-                error!("Failed to apply theme overrides: {}", e);
-                base_theme
-            });
-        let colorscheme = (&theme).into();
+        let colorscheme = Self::colorscheme(
+            Theme::from_name(&merged_config.theme),
+            &merged_config.theme_overrides,
+        );
 
         let pattern = Television::preprocess_pattern(
             matching_mode,
@@ -191,6 +409,7 @@ impl Television {
                 .unwrap_or(EMPTY_STRING.to_string()),
         );
 
+        channel.set_matching(matching_mode, case_sensitivity);
         channel.find(&pattern);
 
         let preview_state = PreviewState::new(
@@ -211,6 +430,11 @@ impl Television {
         // Action picker is lazily initialized when toggled
         let action_picker = None;
 
+        let vim_mode = merged_config
+            .vim_input_maps
+            .is_some()
+            .then_some(VimMode::Insert);
+
         Self {
             action_tx,
             merged_config: Arc::new(layered_config.merge()),
@@ -223,8 +447,12 @@ impl Television {
             current_pattern: EMPTY_STRING.to_string(),
             results_picker,
             matching_mode,
+            case_sensitivity,
+            no_sort,
             rc_picker: Picker::default(),
             ap_picker: Picker::default(),
+            history: Vec::new(),
+            history_picker_channel: None,
             preview_state,
             preview_handles,
             app_metadata: Arc::new(app_metadata),
@@ -234,11 +462,23 @@ impl Television {
             ui_state: UiState::default(),
             frecency,
             missing_requirements_popup: None,
+            action_output_popup: None,
+            confirm_action_popup: None,
+            action_input_popup: None,
+            notifications: Notifications::default(),
+            notification_history_popup: None,
+            pending_reload: false,
+            pending_keys: PendingKeys::default(),
+            vim_mode,
+            visual_selection: None,
+            last_click: None,
+            preview_resize: None,
         }
     }
 
     fn setup_previewer(
         command: &CommandSpec,
+        action_tx: UnboundedSender<Action>,
         cached: bool,
         offset_expr: Option<Template>,
         title_template: Option<Template>,
@@ -256,6 +496,7 @@ impl Television {
             preview_requests_rx,
             preview_requests_tx.clone(),
             preview_results_tx,
+            action_tx,
             cached,
         );
         tokio::spawn(async move { previewer.run().await });
@@ -292,6 +533,16 @@ impl Television {
                     .map_or(0, |r| r.height as usize),
             ),
             self.missing_requirements_popup.clone(),
+            self.action_output_popup.clone(),
+            self.confirm_action_popup.clone(),
+            self.action_input_popup.clone(),
+            self.notifications.clone(),
+            self.notification_history_popup.clone(),
+            self.pending_keys.keys().to_vec(),
+            self.vim_mode,
+            self.matching_mode,
+            self.case_sensitivity,
+            self.no_sort,
         );
 
         Ctx::new(
@@ -308,30 +559,124 @@ impl Television {
         self.merged_config.channel_name.clone()
     }
 
+    /// The frecency table the current channel ranks its entries with and
+    /// records selections into.
+    pub fn channel_frecency(&self) -> ChannelFrecency {
+        channel_frecency(&self.frecency, &self.merged_config)
+    }
+
     pub fn change_channel(&mut self, channel_prototype: &ChannelPrototype) {
         // shutdown the current channel and reset state
         self.preview_state.reset();
         self.reset_picker_selection();
         self.reset_picker_input();
         self.current_pattern = EMPTY_STRING.to_string();
-        self.channel.shutdown();
-        if let Some((sender, _)) = &self.preview_handles {
-            sender
-                .send(PreviewRequest::Shutdown)
-                .expect("Failed to send shutdown signal to previewer");
-        }
+        self.exit_visual_mode();
         // setup the new channel
         debug!("Changing channel to {:?}", channel_prototype);
         self.layered_config
             .update_channel(channel_prototype.clone());
         self.merged_config = Arc::new(self.layered_config.merge());
-        // merge channel shortcuts if remote control is enabled
-        if let Some(rc) = &mut self.remote_control {
-            Arc::make_mut(&mut self.merged_config)
-                .input_map
-                .merge_globals_with(
-                    &rc.cable_channels.get_channels_shortcut_keybindings(),
-                );
+        self.merge_channel_shortcuts();
+        // Set preview state enabled based on both channel capability and UI configuration
+        self.preview_state.enabled = channel_prototype.preview.is_some()
+            && !self.merged_config.preview_panel_hidden;
+        self.matching_mode = self.merged_config.match_mode;
+        self.case_sensitivity = self.merged_config.case_sensitivity;
+        self.no_sort = self.merged_config.no_sort;
+        self.restart_channel();
+    }
+
+    /// Apply a configuration reloaded from disk and reload the cable
+    /// channels and the theme.
+    ///
+    /// The current channel is restarted with its new definition, keeping the
+    /// query, unless it isn't part of the cable (e.g. stdin or ad-hoc
+    /// channels). Channels and themes that can't be parsed are reported as
+    /// notifications.
+    pub fn reload_config(&mut self, config: Config) {
+        let had_preview = self.merged_config.channel_preview_command.is_some();
+        self.layered_config.update_base_config(config);
+        let (cable, errors) =
+            load_cable(&self.layered_config.merge().cable_dir);
+        let mut failed = !errors.is_empty();
+        for error in errors {
+            self.notify(Severity::Error, error);
+        }
+        let prototype = cable.get(&self.current_channel()).cloned();
+        if let Some(prototype) = &prototype {
+            self.layered_config.reload_channel(prototype.clone());
+        }
+        self.merged_config = Arc::new(self.layered_config.merge());
+        self.remote_control =
+            (!self.merged_config.remote_disabled).then(|| {
+                RemoteControl::new(
+                    cable,
+                    self.merged_config.remote_sort_alphabetically,
+                )
+            });
+        if self.remote_control.is_none() && self.mode == Mode::RemoteControl {
+            self.mode = Mode::Channel;
+        }
+        self.merge_channel_shortcuts();
+
+        let theme = Theme::try_from_name(&self.merged_config.theme)
+            .unwrap_or_else(|e| {
+                failed = true;
+                self.notify(Severity::Error, e);
+                Theme::from_name(&self.merged_config.theme)
+            });
+        self.colorscheme = Arc::new(Self::colorscheme(
+            theme,
+            &self.merged_config.theme_overrides,
+        ));
+
+        if prototype.is_some() {
+            let has_preview =
+                self.merged_config.channel_preview_command.is_some();
+            if has_preview != had_preview {
+                self.preview_state.reset();
+                self.preview_state.enabled =
+                    has_preview && !self.merged_config.preview_panel_hidden;
+            }
+            self.restart_channel();
+            let pattern = self.results_picker.input.value().to_string();
+            self.channel
+                .find(&Self::preprocess_pattern(self.matching_mode, &pattern));
+            self.results_picker.reset_selection();
+        }
+        if !failed {
+            self.notify(Severity::Info, "Configuration reloaded");
+        }
+    }
+
+    /// Merge the channel shortcuts into the input maps if the remote control
+    /// is enabled.
+    fn merge_channel_shortcuts(&mut self) {
+        if let Some(rc) = &self.remote_control {
+            Arc::make_mut(&mut self.merged_config).merge_global_shortcuts(
+                &rc.cable_channels.get_channels_shortcut_keybindings(),
+            );
+        }
+    }
+
+    fn colorscheme(theme: Theme, overrides: &ThemeOverrides) -> Colorscheme {
+        let theme =
+            theme.merge_with_overrides(overrides).unwrap_or_else(|e| {
+                error!("Failed to apply theme overrides: {}", e);
+                theme
+            });
+        (&theme).into()
+    }
+
+    /// Shut down the current channel and its previewer and start them again
+    /// from the merged configuration.
+    fn restart_channel(&mut self) {
+        self.channel.shutdown();
+        if let Some((sender, _)) = &self.preview_handles {
+            sender
+                .send(PreviewRequest::Shutdown)
+                .expect("Failed to send shutdown signal to previewer");
         }
 
         self.preview_handles =
@@ -339,6 +684,7 @@ impl Television {
                 |command| {
                     Self::setup_previewer(
                         command,
+                        self.action_tx.clone(),
                         self.merged_config.channel_preview_cached,
                         self.merged_config.channel_preview_offset.clone(),
                         self.merged_config.preview_panel_header.clone(),
@@ -346,21 +692,12 @@ impl Television {
                     )
                 },
             );
-        // Set preview state enabled based on both channel capability and UI configuration
-        self.preview_state.enabled = channel_prototype.preview.is_some()
-            && !self.merged_config.preview_panel_hidden;
 
-        // Build frecency config if enabled for this channel and sorting is enabled
-        let frecency_config = if self.merged_config.channel_frecency
-            && !self.merged_config.no_sort
-        {
-            Some((
-                self.frecency.clone(),
-                self.merged_config.channel_name.clone(),
-            ))
-        } else {
-            None
-        };
+        // Build frecency config if enabled for this channel
+        let frecency_config = self
+            .merged_config
+            .channel_frecency
+            .then(|| self.channel_frecency());
 
         self.channel = CableChannel::new(
             self.merged_config.channel_source_command.clone(),
@@ -369,10 +706,13 @@ impl Television {
             self.merged_config.channel_source_display.clone(),
             self.merged_config.channel_source_output.clone(),
             self.merged_config.channel_preview_command.is_some(),
-            self.merged_config.no_sort,
+            self.no_sort,
+            self.merged_config.sort.clone(),
             frecency_config,
             false, // stdin only applies to the initial channel
         );
+        self.channel
+            .set_matching(self.matching_mode, self.case_sensitivity);
         self.was_running = true;
         self.channel.load();
     }
@@ -389,7 +729,9 @@ impl Television {
                     rc.find(pattern);
                 }
             }
-            Mode::ActionPicker => {
+            Mode::ActionPicker
+            | Mode::CommandPalette
+            | Mode::HistoryPicker => {
                 if let Some(ap) = self.action_picker.as_mut() {
                     ap.find(pattern);
                 }
@@ -399,24 +741,9 @@ impl Television {
 
     fn preprocess_pattern(mode: MatchingMode, pattern: &str) -> String {
         if mode == MatchingMode::Substring {
-            let parts: Vec<&str> = pattern.split_ascii_whitespace().collect();
-            if parts.is_empty() {
-                return pattern.to_string();
-            }
-
-            let capacity = parts.iter().map(|s| s.len() + 2).sum::<usize>()
-                + parts.len()
-                - 1;
-            let mut result = String::with_capacity(capacity);
-
-            for (i, part) in parts.iter().enumerate() {
-                if i > 0 {
-                    result.push(' ');
-                }
-                result.push('\'');
-                result.push_str(part);
-            }
-            result
+            Query::parse(pattern)
+                .map_terms(|term| format!("'{term}"))
+                .to_string()
         } else {
             pattern.to_string()
         }
@@ -453,7 +780,11 @@ impl Television {
         match self.mode {
             Mode::Channel => self.results_picker.selected().map(|i| i as u32),
             Mode::RemoteControl => self.rc_picker.selected().map(|i| i as u32),
-            Mode::ActionPicker => self.ap_picker.selected().map(|i| i as u32),
+            Mode::ActionPicker
+            | Mode::CommandPalette
+            | Mode::HistoryPicker => {
+                self.ap_picker.selected().map(|i| i as u32)
+            }
         }
     }
 
@@ -497,7 +828,9 @@ impl Television {
                         as usize,
                 );
             }
-            Mode::ActionPicker => {
+            Mode::ActionPicker
+            | Mode::CommandPalette
+            | Mode::HistoryPicker => {
                 let total_results =
                     self.action_picker
                         .as_ref()
@@ -522,7 +855,9 @@ impl Television {
             Mode::RemoteControl => {
                 self.rc_picker.reset_selection();
             }
-            Mode::ActionPicker => {
+            Mode::ActionPicker
+            | Mode::CommandPalette
+            | Mode::HistoryPicker => {
                 self.ap_picker.reset_selection();
             }
         }
@@ -534,7 +869,9 @@ impl Television {
             Mode::RemoteControl => {
                 self.rc_picker.reset_input();
             }
-            Mode::ActionPicker => {
+            Mode::ActionPicker
+            | Mode::CommandPalette
+            | Mode::HistoryPicker => {
                 self.ap_picker.reset_input();
             }
         }
@@ -553,8 +890,220 @@ impl Television {
             self.reset_picker_selection();
         }
     }
+
+    /// The vim mode key presses should currently be interpreted in.
+    ///
+    /// Vim mode only applies to the results list: pickers and popups that
+    /// capture input always behave like regular text fields.
+    pub fn effective_vim_mode(&self) -> Option<VimMode> {
+        if self.mode == Mode::Channel && !self.has_blocking_popup() {
+            self.vim_mode
+        } else {
+            None
+        }
+    }
+
+    /// Whether unbound printable keys should be typed into the input.
+    pub fn accepts_text_input(&self) -> bool {
+        !matches!(
+            self.effective_vim_mode(),
+            Some(VimMode::Normal | VimMode::Visual)
+        )
+    }
+
+    fn set_vim_mode(&mut self, vim_mode: VimMode) {
+        if self.vim_mode.is_none() || self.mode != Mode::Channel {
+            return;
+        }
+        self.exit_visual_mode();
+        if vim_mode == VimMode::Visual {
+            let Some(cursor) = self.results_picker.selected() else {
+                return;
+            };
+            self.visual_selection = Some(VisualSelection {
+                anchor: cursor,
+                // make sure the first range is computed right away
+                cursor: usize::MAX,
+                initial: self.channel.selected_entries().clone(),
+            });
+        }
+        self.vim_mode = Some(vim_mode);
+    }
+
+    fn exit_visual_mode(&mut self) {
+        if self.visual_selection.take().is_some() {
+            self.vim_mode = Some(VimMode::Normal);
+        }
+    }
+
+    /// Select every entry between the visual mode anchor and the cursor, on
+    /// top of the entries that were selected before entering visual mode.
+    fn update_visual_selection(&mut self) {
+        let Some(cursor) = self.results_picker.selected() else {
+            return;
+        };
+        let result_count = self.channel.result_count() as usize;
+        let Some(visual) = self.visual_selection.as_mut() else {
+            return;
+        };
+        if visual.cursor == cursor || result_count == 0 {
+            return;
+        }
+        visual.cursor = cursor;
+        let start = visual.anchor.min(cursor);
+        let end = visual.anchor.max(cursor).min(result_count - 1);
+        let initial = visual.initial.clone();
+
+        self.channel.clear_selection();
+        for entry in &initial {
+            self.channel.select(entry);
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        for index in start..=end {
+            if let Some(entry) = self.channel.get_result(index as u32) {
+                self.channel.select(&entry);
+            }
+        }
+    }
+
+    /// Returns the row and index of the entry displayed at the given
+    /// position in the list of the current mode.
+    fn entry_at_position(&self, x: u16, y: u16) -> Option<(usize, usize)> {
+        let layout = &self.ui_state.layout;
+        let (area, direction, offset, visible) = match self.mode {
+            Mode::Channel => (
+                results_list_area(
+                    layout.results,
+                    &self.merged_config.results_panel_padding,
+                    &self.merged_config.results_panel_border_type,
+                ),
+                match self.merged_config.input_bar_position {
+                    InputPosition::Top => ListDirection::TopToBottom,
+                    InputPosition::Bottom => ListDirection::BottomToTop,
+                },
+                self.results_picker.offset(),
+                self.results_picker.entries.len(),
+            ),
+            Mode::RemoteControl => (
+                channel_list_area(
+                    layout.remote_control?,
+                    self.merged_config.remote_show_channel_descriptions,
+                ),
+                ListDirection::TopToBottom,
+                self.rc_picker.offset(),
+                self.rc_picker.entries.len(),
+            ),
+            Mode::ActionPicker
+            | Mode::CommandPalette
+            | Mode::HistoryPicker => (
+                action_list_area(layout.action_picker?),
+                ListDirection::TopToBottom,
+                self.ap_picker.offset(),
+                self.ap_picker.entries.len(),
+            ),
+        };
+        let row = list_row_at(area, Position::new(x, y), direction)?;
+        (row < visible).then_some((row, offset + row))
+    }
+
+    /// Moves the cursor of the current picker to the entry displayed at the
+    /// given row.
+    fn select_row(&mut self, row: usize, index: usize) {
+        match self.mode {
+            Mode::Channel => {
+                self.results_picker.select(Some(index));
+                self.results_picker.relative_select(Some(row));
+            }
+            Mode::RemoteControl => {
+                self.rc_picker.select(Some(index));
+                self.rc_picker.relative_select(Some(row));
+            }
+            Mode::ActionPicker
+            | Mode::CommandPalette
+            | Mode::HistoryPicker => {
+                self.ap_picker.select(Some(index));
+                self.ap_picker.relative_select(Some(row));
+            }
+        }
+    }
+
+    /// Selects the clicked entry, or confirms it if it was already clicked
+    /// right before.
+    fn handle_click_on_entry(&mut self, x: u16, y: u16) -> Result<()> {
+        let Some((row, index)) = self.entry_at_position(x, y) else {
+            return Ok(());
+        };
+        self.select_row(row, index);
+
+        let now = Instant::now();
+        let is_double_click =
+            self.last_click.is_some_and(|(at, mode, clicked)| {
+                mode == self.mode
+                    && clicked == index
+                    && now.duration_since(at) <= DOUBLE_CLICK_INTERVAL
+            });
+        if is_double_click {
+            self.last_click = None;
+            self.action_tx.send(Action::ConfirmSelection)?;
+        } else {
+            self.last_click = Some((now, self.mode, index));
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn handle_toggle_selection_at(&mut self, x: u16, y: u16) {
+        if self.mode != Mode::Channel {
+            return;
+        }
+        let Some((row, index)) = self.entry_at_position(x, y) else {
+            return;
+        };
+        if let Some(entry) = self.channel.get_result(index as u32) {
+            self.channel.toggle_selection(&entry);
+        }
+        self.select_row(row, index);
+    }
+
+    /// Selects every entry between the cursor and the clicked entry.
+    #[allow(clippy::cast_possible_truncation)]
+    fn handle_select_range_to(&mut self, x: u16, y: u16) {
+        if self.mode != Mode::Channel {
+            return;
+        }
+        let Some((row, index)) = self.entry_at_position(x, y) else {
+            return;
+        };
+        let cursor = self.results_picker.selected().unwrap_or(index);
+        for i in cursor.min(index)..=cursor.max(index) {
+            if let Some(entry) = self.channel.get_result(i as u32) {
+                self.channel.select(&entry);
+            }
+        }
+        self.select_row(row, index);
+    }
+
+    fn handle_mouse_drag(&mut self, x: u16, y: u16) {
+        let (Some(border), Some(preview)) =
+            (self.preview_resize, self.ui_state.layout.preview_window)
+        else {
+            return;
+        };
+        let size = border.preview_size_at(
+            self.ui_state.layout.results,
+            preview,
+            Position::new(x, y),
+        );
+        if size != self.merged_config.preview_panel_size {
+            Arc::make_mut(&mut self.merged_config).preview_panel_size = size;
+        }
+    }
 }
 
+/// Maximum delay between two clicks on the same entry for them to count as
+/// a double-click.
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
 /// Always render the first N ticks.
 ///
 /// This is to ensure there are no startup artefacts and the UI
@@ -586,6 +1135,9 @@ impl Television {
             || matches!(
                 action,
                 Action::AddInputChar(_)
+                    | Action::InsertText(_)
+                    | Action::SetInput(_)
+                    | Action::ReloadConfig
                     | Action::DeletePrevChar
                     | Action::DeletePrevWord
                     | Action::DeleteNextChar
@@ -600,6 +1152,16 @@ impl Television {
                     | Action::SelectPrevEntry
                     | Action::SelectNextPage
                     | Action::SelectPrevPage
+                    | Action::SelectFirstEntry
+                    | Action::SelectLastEntry
+                    | Action::DeselectEntry
+                    | Action::EnterInsertMode
+                    | Action::EnterNormalMode
+                    | Action::ToggleVisualMode
+                    | Action::SelectEntryAtPosition(_, _)
+                    | Action::ToggleSelectionAtPosition(_, _)
+                    | Action::SelectRangeToPosition(_, _)
+                    | Action::MouseDragTo(_, _)
                     | Action::ScrollPreviewDown
                     | Action::ScrollPreviewUp
                     | Action::ScrollPreviewHalfPageDown
@@ -609,11 +1171,20 @@ impl Television {
                     | Action::ToggleStatusBar
                     | Action::ToggleRemoteControl
                     | Action::ToggleActionPicker
+                    | Action::ToggleCommandPalette
+                    | Action::ToggleHistoryPicker
                     | Action::ToggleOrientation
                     | Action::CopyEntryToClipboard
                     | Action::CycleSources
                     | Action::CyclePreviews
                     | Action::ReloadSource
+                    | Action::CycleMatchMode
+                    | Action::ToggleCaseSensitivity
+                    | Action::ToggleSort
+                    | Action::ToggleNotificationHistory
+                    | Action::ExternalAction(_)
+                    | Action::Error(_)
+                    | Action::Notify(_, _)
             ))
             // We want to avoid too much rendering while the channel is reloading
             // to prevent UI flickering.
@@ -781,15 +1352,94 @@ impl Television {
         ));
     }
 
+    /// Initialize the command palette with tv's built-in actions, the
+    /// current channel's actions and the available channels.
+    fn init_command_palette(&mut self) {
+        let input_map = &self.merged_config.input_map;
+        let builtin_actions = command_palette_actions()
+            .filter(|action| {
+                *action != Action::ToggleRemoteControl
+                    || !self.merged_config.remote_disabled
+            })
+            .map(|action| {
+                let keybinding = input_map.get_key_for_action(&action);
+                ActionEntry::builtin(action, keybinding)
+            });
+
+        let mut action_keybindings = rustc_hash::FxHashMap::default();
+        for (key, actions) in input_map
+            .channel_keybindings
+            .iter()
+            .chain(input_map.global_keybindings.iter())
+        {
+            for action in actions.as_slice() {
+                if let Action::ExternalAction(action_str) = action {
+                    action_keybindings
+                        .entry(action_str.clone())
+                        .or_insert(*key);
+                }
+            }
+        }
+
+        let mut channels = Vec::new();
+        if let Some(rc) = self.remote_control.as_ref()
+            && !self.merged_config.remote_disabled
+        {
+            let mut prototypes: Vec<_> = rc.cable_channels.values().collect();
+            prototypes.sort_by(|a, b| a.metadata.name.cmp(&b.metadata.name));
+            channels.extend(prototypes.into_iter().map(|prototype| {
+                ActionEntry::channel(
+                    prototype,
+                    rc.cable_channels
+                        .get_channel_shortcut(&prototype.metadata.name),
+                )
+            }));
+        }
+
+        self.action_picker = Some(ActionPicker::command_palette(
+            builtin_actions,
+            &self.merged_config.channel_actions,
+            &action_keybindings,
+            channels,
+        ));
+    }
+
+    /// Initialize the history picker with the past queries of the channel
+    /// it's scoped to, most recent first.
+    fn init_history_picker(&mut self) {
+        let mut seen = FxHashSet::default();
+        let entries = self
+            .history
+            .iter()
+            .rev()
+            .filter(|entry| {
+                self.history_picker_channel
+                    .as_ref()
+                    .is_none_or(|channel| *channel == entry.channel)
+            })
+            // only keep the latest use of each query
+            .filter(|entry| seen.insert((&entry.query, &entry.channel)))
+            .map(ActionEntry::history)
+            .collect::<Vec<_>>();
+        self.action_picker = Some(ActionPicker::history(entries));
+    }
+
     pub fn handle_input_action(&mut self, action: &Action) {
         let input = match self.mode {
             Mode::Channel => &mut self.results_picker.input,
             Mode::RemoteControl => &mut self.rc_picker.input,
-            Mode::ActionPicker => &mut self.ap_picker.input,
+            Mode::ActionPicker
+            | Mode::CommandPalette
+            | Mode::HistoryPicker => &mut self.ap_picker.input,
         };
-        input.handle(convert_action_to_input_request(action).unwrap());
+        if let Action::InsertText(text) = action {
+            input.insert_str(text);
+        } else {
+            input.handle(convert_action_to_input_request(action).unwrap());
+        }
         match action {
             Action::AddInputChar(_)
+            | Action::InsertText(_)
             | Action::DeletePrevChar
             | Action::DeletePrevWord
             | Action::DeleteLine
@@ -856,8 +1506,15 @@ impl Television {
                     self.change_channel(&new_channel);
                 }
             }
-            Mode::ActionPicker => {
+            Mode::ActionPicker
+            | Mode::CommandPalette
+            | Mode::HistoryPicker => {
                 if let Some(entry) = self.get_selected_action_entry() {
+                    // Actions with unmet requirements are greyed out and
+                    // can't be run
+                    if !entry.is_runnable() {
+                        return Ok(());
+                    }
                     // Close the action picker and dispatch the action
                     self.reset_picker_selection();
                     self.reset_picker_input();
@@ -865,8 +1522,7 @@ impl Television {
                         ap.find(EMPTY_STRING);
                     }
                     self.mode = Mode::Channel;
-                    self.action_tx
-                        .send(Action::ExternalAction(entry.action_string))?;
+                    self.action_tx.send(entry.action)?;
                 }
             }
         }
@@ -897,6 +1553,17 @@ impl Television {
                 .join(SPACE);
 
             tokio::spawn(CLIPBOARD.set(copied_string));
+            self.notify(
+                Severity::Success,
+                if entries.len() == 1 {
+                    "Copied entry to the clipboard".to_string()
+                } else {
+                    format!(
+                        "Copied {} entries to the clipboard",
+                        entries.len()
+                    )
+                },
+            );
         }
     }
 
@@ -917,33 +1584,231 @@ impl Television {
         }
     }
 
-    pub fn handle_reload_source(&mut self) {
-        if self.mode == Mode::Channel {
-            let current_pattern = self.current_pattern.clone();
-            self.channel.reload();
-            // Preserve the current pattern and re-run the search
-            self.find(&current_pattern);
+    /// Reload the channel's source, returns whether a reload was started.
+    pub fn handle_reload_source(&mut self) -> bool {
+        if self.mode != Mode::Channel || !self.channel.reload() {
+            return false;
         }
+        let current_pattern = self.current_pattern.clone();
+        // Preserve the current pattern and re-run the search
+        self.find(&current_pattern);
+        true
     }
 
-    pub fn handle_action(&mut self, action: &Action) -> Result<()> {
-        // If popup is showing, only allow certain actions to dismiss it
-        if self.missing_requirements_popup.is_some() {
-            match action {
-                // These actions dismiss the popup and stay in RemoteControl mode
-                Action::ConfirmSelection
-                | Action::Quit
-                | Action::ToggleRemoteControl => {
-                    self.missing_requirements_popup = None;
-                    return Ok(());
+    /// Apply the current matching settings to the channel and search again.
+    fn refresh_matching(&mut self) {
+        self.channel
+            .set_matching(self.matching_mode, self.case_sensitivity);
+        let current_pattern = self.current_pattern.clone();
+        self.find(&current_pattern);
+        self.reset_picker_selection();
+    }
+
+    /// Apply the post-actions of an external action once it has completed.
+    pub fn apply_post_actions(
+        &mut self,
+        post_actions: &[PostAction],
+    ) -> Result<()> {
+        for post_action in post_actions {
+            match post_action {
+                PostAction::Reload => {
+                    self.action_tx.send(Action::ReloadSource)?;
                 }
-                _ => return Ok(()),
+                PostAction::Quit => self.action_tx.send(Action::Quit)?,
+                PostAction::Switch(channel_name) => {
+                    if self.remote_control.as_ref().is_some_and(|rc| {
+                        rc.cable_channels.has_channel(channel_name)
+                    }) {
+                        self.action_tx.send(Action::SwitchToChannel(
+                            channel_name.clone(),
+                        ))?;
+                    } else {
+                        self.notify(
+                            Severity::Error,
+                            format!(
+                                "Cannot switch to unknown channel '{channel_name}'"
+                            ),
+                        );
+                    }
+                }
+                PostAction::ClearSelection => self.channel.clear_selection(),
             }
         }
+        Ok(())
+    }
 
-        // handle actions
+    /// Handle an action while the action output panel is open.
+    ///
+    /// Movement actions scroll the output, while confirming or quitting
+    /// dismisses the panel and applies any pending post-actions.
+    fn handle_action_output_popup(&mut self, action: &Action) -> Result<()> {
+        let Some(popup) = self.action_output_popup.as_mut() else {
+            return Ok(());
+        };
         match action {
-            Action::AddInputChar(_)
+            Action::ConfirmSelection | Action::Quit => {
+                let after = std::mem::take(&mut popup.after);
+                self.action_output_popup = None;
+                self.apply_post_actions(&after)?;
+            }
+            Action::SelectNextEntry | Action::ScrollPreviewDown => {
+                popup.scroll_by(1);
+            }
+            Action::SelectPrevEntry | Action::ScrollPreviewUp => {
+                popup.scroll_by(-1);
+            }
+            Action::SelectNextPage | Action::ScrollPreviewHalfPageDown => {
+                popup.scroll_by(20);
+            }
+            Action::SelectPrevPage | Action::ScrollPreviewHalfPageUp => {
+                popup.scroll_by(-20);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Handle an action while the confirmation dialog is open.
+    fn handle_confirm_action_popup(&mut self, action: &Action) -> Result<()> {
+        match action {
+            Action::ConfirmSelection | Action::AddInputChar('y' | 'Y') => {
+                if let Some(popup) = self.confirm_action_popup.take() {
+                    self.action_tx.send(Action::RunExternalAction(
+                        popup.action_name,
+                        popup.inputs,
+                    ))?;
+                }
+            }
+            Action::Quit | Action::AddInputChar('n' | 'N') => {
+                self.confirm_action_popup = None;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Handle an action while the action inputs modal is open.
+    fn handle_action_input_popup(&mut self, action: &Action) -> Result<()> {
+        let Some(popup) = self.action_input_popup.as_mut() else {
+            return Ok(());
+        };
+        match action {
+            Action::ConfirmSelection => {
+                if let Some(values) = popup.submit() {
+                    let action_name = popup.action_name.clone();
+                    self.action_input_popup = None;
+                    self.action_tx.send(Action::SubmitActionInputs(
+                        action_name,
+                        values,
+                    ))?;
+                }
+            }
+            Action::Quit => self.action_input_popup = None,
+            Action::InsertText(text) => {
+                popup.input.insert_str(text);
+            }
+            _ => {
+                if let Some(request) = convert_action_to_input_request(action)
+                {
+                    popup.input.handle(request);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle an action while the notification history is open.
+    fn handle_notification_history_popup(&mut self, action: &Action) {
+        let len = self.notifications.len();
+        let Some(popup) = self.notification_history_popup.as_mut() else {
+            return;
+        };
+        match action {
+            Action::ConfirmSelection
+            | Action::Quit
+            | Action::ToggleNotificationHistory => {
+                self.notification_history_popup = None;
+            }
+            Action::SelectNextEntry | Action::ScrollPreviewDown => {
+                popup.scroll_by(1, len);
+            }
+            Action::SelectPrevEntry | Action::ScrollPreviewUp => {
+                popup.scroll_by(-1, len);
+            }
+            Action::SelectNextPage | Action::ScrollPreviewHalfPageDown => {
+                popup.scroll_by(10, len);
+            }
+            Action::SelectPrevPage | Action::ScrollPreviewHalfPageUp => {
+                popup.scroll_by(-10, len);
+            }
+            _ => {}
+        }
+    }
+
+    /// Display a notification to the user.
+    pub fn notify(&mut self, severity: Severity, message: impl Into<String>) {
+        let message = message.into();
+        match severity {
+            Severity::Error => error!("{message}"),
+            Severity::Warning => warn!("{message}"),
+            Severity::Info | Severity::Success => debug!("{message}"),
+        }
+        self.notifications.push(severity, message);
+    }
+
+    /// Whether a popup that captures all input is currently shown.
+    pub fn has_blocking_popup(&self) -> bool {
+        self.notification_history_popup.is_some()
+            || self.action_output_popup.is_some()
+            || self.confirm_action_popup.is_some()
+            || self.action_input_popup.is_some()
+    }
+
+    pub fn handle_action(&mut self, action: &Action) -> Result<()> {
+        // notifications are raised whatever is currently displayed
+        match action {
+            Action::Error(message) => {
+                self.notify(Severity::Error, message.clone());
+                return Ok(());
+            }
+            Action::Notify(severity, message) => {
+                self.notify(*severity, message.clone());
+                return Ok(());
+            }
+            _ => {}
+        }
+        if self.notification_history_popup.is_some() {
+            self.handle_notification_history_popup(action);
+            return Ok(());
+        }
+        if self.action_input_popup.is_some() {
+            return self.handle_action_input_popup(action);
+        }
+        if self.confirm_action_popup.is_some() {
+            return self.handle_confirm_action_popup(action);
+        }
+        if self.action_output_popup.is_some() {
+            return self.handle_action_output_popup(action);
+        }
+
+        // If popup is showing, only allow certain actions to dismiss it
+        if self.missing_requirements_popup.is_some() {
+            match action {
+                // These actions dismiss the popup and stay in RemoteControl mode
+                Action::ConfirmSelection
+                | Action::Quit
+                | Action::ToggleRemoteControl => {
+                    self.missing_requirements_popup = None;
+                    return Ok(());
+                }
+                _ => return Ok(()),
+            }
+        }
+
+        // handle actions
+        match action {
+            Action::AddInputChar(_)
+            | Action::InsertText(_)
             | Action::DeletePrevChar
             | Action::DeletePrevWord
             | Action::DeleteNextChar
@@ -954,6 +1819,27 @@ impl Television {
             | Action::GoToPrevChar => {
                 self.handle_input_action(action);
             }
+            Action::SetInput(text) => self.set_pattern(text),
+            Action::EnterInsertMode => self.set_vim_mode(VimMode::Insert),
+            Action::EnterNormalMode => self.set_vim_mode(VimMode::Normal),
+            Action::ToggleVisualMode => match self.vim_mode {
+                Some(VimMode::Visual) => self.exit_visual_mode(),
+                Some(VimMode::Normal) => self.set_vim_mode(VimMode::Visual),
+                Some(VimMode::Insert) | None => {}
+            },
+            Action::SelectFirstEntry => {
+                self.move_cursor(Movement::First, 1);
+            }
+            Action::SelectLastEntry => {
+                self.move_cursor(Movement::Last, 1);
+            }
+            Action::DeselectEntry => {
+                if self.mode == Mode::Channel
+                    && let Some(entry) = &self.currently_selected
+                {
+                    self.channel.deselect(entry);
+                }
+            }
             Action::SelectNextEntry => {
                 self.move_cursor(Movement::Next, 1);
             }
@@ -998,21 +1884,89 @@ impl Television {
             Action::ToggleSelectionDown | Action::ToggleSelectionUp => {
                 self.handle_toggle_selection(action);
             }
+            Action::SelectEntryAtPosition(x, y) => {
+                self.handle_click_on_entry(*x, *y)?;
+            }
+            Action::ToggleSelectionAtPosition(x, y) => {
+                self.handle_toggle_selection_at(*x, *y);
+            }
+            Action::SelectRangeToPosition(x, y) => {
+                self.handle_select_range_to(*x, *y);
+            }
+            Action::MouseClickAt(x, y) => {
+                self.preview_resize = match self.ui_state.layout.preview_window
+                {
+                    Some(preview) if self.mode == Mode::Channel => {
+                        PreviewBorder::at(
+                            self.ui_state.layout.results,
+                            preview,
+                            Position::new(*x, *y),
+                        )
+                    }
+                    _ => None,
+                };
+            }
+            Action::MouseDragTo(x, y) => self.handle_mouse_drag(*x, *y),
+            Action::MouseRelease => self.preview_resize = None,
             Action::ConfirmSelection => {
                 self.handle_confirm_selection()?;
             }
             Action::CopyEntryToClipboard => {
                 self.handle_copy_entry_to_clipboard();
             }
+            Action::CycleSources if self.mode == Mode::HistoryPicker => {
+                self.history_picker_channel = match self.history_picker_channel
+                {
+                    Some(_) => None,
+                    None => Some(self.current_channel()),
+                };
+                self.init_history_picker();
+                self.reset_picker_selection();
+                if let Some(ap) = self.action_picker.as_mut() {
+                    ap.find(self.ap_picker.input.value());
+                }
+            }
             Action::CycleSources => {
                 self.cycle_sources();
             }
             Action::CyclePreviews => {
                 self.cycle_previews();
             }
-            Action::ReloadSource | Action::WatchTimer => {
+            Action::ReloadSource if self.mode == Mode::Channel => {
+                if self.channel.is_stdin() {
+                    self.notify(
+                        Severity::Warning,
+                        "Input read from stdin cannot be reloaded",
+                    );
+                } else if self.handle_reload_source()
+                    || self.channel.reloading()
+                {
+                    // report the reload in progress once it completes
+                    self.pending_reload = true;
+                }
+            }
+            Action::WatchTimer => {
                 self.handle_reload_source();
             }
+            Action::CycleMatchMode if self.mode == Mode::Channel => {
+                self.matching_mode = self.matching_mode.next();
+                self.refresh_matching();
+            }
+            Action::ToggleCaseSensitivity if self.mode == Mode::Channel => {
+                self.case_sensitivity = self.case_sensitivity.next();
+                self.refresh_matching();
+            }
+            Action::ToggleSort if self.mode == Mode::Channel => {
+                self.no_sort = !self.no_sort;
+                self.channel.set_no_sort(self.no_sort);
+                self.refresh_matching();
+            }
+            Action::ToggleNotificationHistory => {
+                // toasts would only repeat what the history shows
+                self.notifications.dismiss_all();
+                self.notification_history_popup =
+                    Some(NotificationHistoryPopup::default());
+            }
             Action::SwitchToChannel(channel_name) => {
                 if let Some(rc) = &self.remote_control {
                     let prototype = rc.zap(channel_name);
@@ -1046,7 +2000,9 @@ impl Television {
                         self.reset_picker_selection();
                         self.mode = Mode::Channel;
                     }
-                    Mode::ActionPicker => {
+                    Mode::ActionPicker
+                    | Mode::CommandPalette
+                    | Mode::HistoryPicker => {
                         // Close action picker and open remote control
                         self.reset_picker_input();
                         if let Some(ap) = self.action_picker.as_mut() {
@@ -1099,8 +2055,93 @@ impl Television {
                         }
                         self.ticks = 0;
                     }
+                    Mode::CommandPalette | Mode::HistoryPicker => {
+                        // Swap the current picker for the action picker
+                        self.reset_picker_input();
+                        self.reset_picker_selection();
+                        self.init_action_picker();
+                        self.mode = Mode::ActionPicker;
+                        if let Some(ap) = self.action_picker.as_mut() {
+                            ap.find(EMPTY_STRING);
+                        }
+                        self.ticks = 0;
+                    }
                 }
             }
+            Action::ToggleCommandPalette => match self.mode {
+                Mode::Channel => {
+                    self.init_command_palette();
+                    self.mode = Mode::CommandPalette;
+                    if let Some(ap) = self.action_picker.as_mut() {
+                        ap.find(EMPTY_STRING);
+                    }
+                    self.ticks = 0;
+                }
+                Mode::CommandPalette => {
+                    self.reset_picker_input();
+                    if let Some(ap) = self.action_picker.as_mut() {
+                        ap.find(EMPTY_STRING);
+                    }
+                    self.reset_picker_selection();
+                    self.mode = Mode::Channel;
+                }
+                Mode::RemoteControl
+                | Mode::ActionPicker
+                | Mode::HistoryPicker => {
+                    // Close the current overlay and open the command palette
+                    self.reset_picker_input();
+                    if let Some(rc) = self.remote_control.as_mut() {
+                        rc.find(EMPTY_STRING);
+                    }
+                    self.reset_picker_selection();
+                    self.init_command_palette();
+                    self.mode = Mode::CommandPalette;
+                    if let Some(ap) = self.action_picker.as_mut() {
+                        ap.find(EMPTY_STRING);
+                    }
+                    self.ticks = 0;
+                }
+            },
+            Action::ToggleHistoryPicker => match self.mode {
+                Mode::Channel => {
+                    self.history_picker_channel =
+                        (!self.merged_config.global_history)
+                            .then(|| self.current_channel());
+                    self.init_history_picker();
+                    self.mode = Mode::HistoryPicker;
+                    if let Some(ap) = self.action_picker.as_mut() {
+                        ap.find(EMPTY_STRING);
+                    }
+                    self.ticks = 0;
+                }
+                Mode::HistoryPicker => {
+                    self.reset_picker_input();
+                    if let Some(ap) = self.action_picker.as_mut() {
+                        ap.find(EMPTY_STRING);
+                    }
+                    self.reset_picker_selection();
+                    self.mode = Mode::Channel;
+                }
+                Mode::RemoteControl
+                | Mode::ActionPicker
+                | Mode::CommandPalette => {
+                    // Close the current overlay and open the history picker
+                    self.reset_picker_input();
+                    if let Some(rc) = self.remote_control.as_mut() {
+                        rc.find(EMPTY_STRING);
+                    }
+                    self.reset_picker_selection();
+                    self.history_picker_channel =
+                        (!self.merged_config.global_history)
+                            .then(|| self.current_channel());
+                    self.init_history_picker();
+                    self.mode = Mode::HistoryPicker;
+                    if let Some(ap) = self.action_picker.as_mut() {
+                        ap.find(EMPTY_STRING);
+                    }
+                    self.ticks = 0;
+                }
+            },
             Action::ToggleHelp => {
                 // Only allow toggling if the help panel is not disabled
                 if !self.merged_config.help_panel_disabled {
@@ -1147,6 +2188,10 @@ impl Television {
     pub fn update(&mut self, action: &Action) -> Result<Option<Action>> {
         self.handle_action(action)?;
 
+        if self.mode == Mode::Channel && self.visual_selection.is_some() {
+            self.update_visual_selection();
+        }
+
         // Always let the background matcher make progress
         self.channel.tick();
 
@@ -1160,6 +2205,15 @@ impl Television {
         }
         self.was_running = running;
 
+        if self.pending_reload && !running && !self.channel.reloading() {
+            self.pending_reload = false;
+            self.notify(
+                Severity::Info,
+                format!("Reloaded {} entries", self.channel.total_count()),
+            );
+        }
+        let notifications_changed = self.notifications.tick(Instant::now());
+
         // Only run the full results pipeline when the action could
         // have changed the results or the visible viewport
         if action.affects_results() {
@@ -1170,7 +2224,14 @@ impl Television {
             self.update_rc_picker_state();
         }
 
-        if self.action_picker.is_some() && self.mode == Mode::ActionPicker {
+        if self.action_picker.is_some()
+            && matches!(
+                self.mode,
+                Mode::ActionPicker
+                    | Mode::CommandPalette
+                    | Mode::HistoryPicker
+            )
+        {
             self.update_ap_picker_state();
         }
 
@@ -1181,7 +2242,7 @@ impl Television {
         }
         self.ticks += 1;
 
-        Ok(if self.should_render(action) {
+        Ok(if notifications_changed || self.should_render(action) {
             Some(Action::Render)
         } else {
             None
@@ -1189,6 +2250,18 @@ impl Television {
     }
 }
 
+/// The frecency table used by the channel `merged_config` describes.
+fn channel_frecency(
+    frecency: &FrecencyHandle,
+    merged_config: &MergedConfig,
+) -> ChannelFrecency {
+    ChannelFrecency::new(
+        frecency.clone(),
+        merged_config.channel_frecency_namespace.clone(),
+    )
+    .with_key(merged_config.channel_frecency_key.clone())
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
@@ -1198,7 +2271,9 @@ mod test {
         config::layers::ConfigLayers,
         event::Key,
         frecency::Frecency,
-        television::{MatchingMode, Mode, Television},
+        history::HistoryEntry,
+        matcher::config::MatchingMode,
+        television::{ActionInputPopup, ConfirmActionPopup, Mode, Television},
     };
     use std::sync::Arc;
     use tempfile::tempdir;
@@ -1252,6 +2327,70 @@ mod test {
         assert!(tv.remote_control.is_none());
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_reload_config() {
+        use crate::cli::PostProcessedCli;
+        use crate::notifications::Severity;
+
+        let cable_dir = tempdir().unwrap();
+        std::fs::write(
+            cable_dir.path().join("test.toml"),
+            "[metadata]\nname = \"test\"\n[source]\ncommand = \"echo 2\"\n",
+        )
+        .unwrap();
+        std::fs::write(cable_dir.path().join("broken.toml"), "[metadata")
+            .unwrap();
+        let mut config = crate::config::Config::default();
+        config.application.cable_dir = cable_dir.path().to_path_buf();
+
+        let prototype = crate::channels::prototypes::ChannelPrototype::new(
+            "test", "echo 1",
+        );
+        let cli_args = PostProcessedCli {
+            channel: ChannelCli {
+                exact: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let layered_config =
+            ConfigLayers::new(config.clone(), prototype, cli_args);
+        let dir = tempdir().unwrap();
+        let frecency = Arc::new(Frecency::new(100, dir.path()));
+        let mut tv = Television::new(
+            tokio::sync::mpsc::unbounded_channel().0,
+            layered_config,
+            Cable::from_prototypes(vec![]),
+            frecency,
+        );
+        tv.set_pattern("abc");
+
+        tv.reload_config(config);
+
+        // the channel was restarted with its new definition, keeping the
+        // query and the CLI overrides
+        assert_eq!(
+            tv.merged_config.channel_source_command.to_string(),
+            "[echo 2]"
+        );
+        assert_eq!(tv.results_picker.input.value(), "abc");
+        assert_eq!(tv.matching_mode, MatchingMode::Substring);
+        let rc = tv.remote_control.as_ref().unwrap();
+        assert!(rc.cable_channels.has_channel("test"));
+        assert!(rc.cable_channels.has_channel("files"));
+        // the broken channel is reported
+        let errors: Vec<_> = tv
+            .notifications
+            .history()
+            .filter(|n| n.severity == Severity::Error)
+            .map(|n| n.message.clone())
+            .collect();
+        assert_eq!(
+            errors,
+            vec!["Failed to parse cable channel broken.toml:1: unclosed table, expected `]`".to_string()]
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn test_channel_keybindings_take_precedence() {
         use crate::cli::PostProcessedCli;
@@ -1298,4 +2437,464 @@ mod test {
             Some(&Actions::single(Action::SelectNextEntry)),
         );
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_command_palette_lists_builtin_actions_and_channels() {
+        use crate::channels::prototypes::ChannelPrototype;
+        use crate::cli::PostProcessedCli;
+
+        let mut config = crate::config::Config::default();
+        config
+            .keybindings
+            .insert(Key::Ctrl('o'), Action::TogglePreview.into());
+        let prototype = toml::from_str::<ChannelPrototype>(
+            r#"
+            [metadata]
+            name = "test"
+
+            [source]
+            command = "echo 1"
+
+            [actions.edit]
+            command = "vim {}"
+            "#,
+        )
+        .unwrap();
+        let cable = Cable::from_prototypes(vec![
+            prototype.clone(),
+            ChannelPrototype::new("files", "fd -t f"),
+        ]);
+
+        let layered_config =
+            ConfigLayers::new(config, prototype, PostProcessedCli::default());
+        let dir = tempdir().unwrap();
+        let frecency = Arc::new(Frecency::new(100, dir.path()));
+        let mut tv = Television::new(
+            tokio::sync::mpsc::unbounded_channel().0,
+            layered_config,
+            cable,
+            frecency,
+        );
+
+        tv.handle_action(&Action::ToggleCommandPalette).unwrap();
+        assert_eq!(tv.mode, Mode::CommandPalette);
+
+        let palette = tv.action_picker.as_mut().unwrap();
+        let mut entries = Vec::new();
+        for _ in 0..100 {
+            entries = palette.results(100, 0);
+            if !palette.running() && !entries.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        let toggle_preview = entries
+            .iter()
+            .find(|e| e.action == Action::TogglePreview)
+            .unwrap();
+        assert_eq!(toggle_preview.action_name, "toggle_preview");
+        assert_eq!(toggle_preview.keybinding, Some(Key::Ctrl('o')));
+        assert!(
+            entries.iter().any(|e| e.action
+                == Action::ExternalAction("actions:edit".to_string()))
+        );
+        assert!(
+            entries
+                .iter()
+                .any(|e| e.action
+                    == Action::SwitchToChannel("files".to_string()))
+        );
+
+        tv.handle_action(&Action::ToggleCommandPalette).unwrap();
+        assert_eq!(tv.mode, Mode::Channel);
+    }
+
+    async fn picker_queries(tv: &mut Television) -> Vec<String> {
+        let picker = tv.action_picker.as_mut().unwrap();
+        for _ in 0..100 {
+            if !picker.running() && picker.result_count() > 0 {
+                break;
+            }
+            picker.results(0, 0);
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        picker
+            .results(100, 0)
+            .into_iter()
+            .map(|e| e.action_name)
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn test_history_picker_sets_input() {
+        use crate::channels::prototypes::ChannelPrototype;
+        use crate::cli::PostProcessedCli;
+
+        let prototype = ChannelPrototype::new("files", "echo 1");
+        let layered_config = ConfigLayers::new(
+            crate::config::Config::default(),
+            prototype.clone(),
+            PostProcessedCli::default(),
+        );
+        let dir = tempdir().unwrap();
+        let frecency = Arc::new(Frecency::new(100, dir.path()));
+        let (action_tx, mut action_rx) =
+            tokio::sync::mpsc::unbounded_channel();
+        let mut tv = Television::new(
+            action_tx,
+            layered_config,
+            Cable::from_prototypes(vec![prototype]),
+            frecency,
+        );
+        tv.history = [
+            ("old", "files"),
+            ("other", "dirs"),
+            ("recent", "files"),
+            ("old", "files"),
+        ]
+        .into_iter()
+        .map(|(query, channel)| {
+            HistoryEntry::new(query.into(), channel.into())
+        })
+        .collect();
+
+        tv.handle_action(&Action::ToggleHistoryPicker).unwrap();
+        assert_eq!(tv.mode, Mode::HistoryPicker);
+        assert_eq!(picker_queries(&mut tv).await, ["old", "recent"]);
+
+        tv.handle_action(&Action::CycleSources).unwrap();
+        assert_eq!(picker_queries(&mut tv).await, ["old", "recent", "other"]);
+
+        tv.update_ap_picker_state();
+        tv.handle_action(&Action::ConfirmSelection).unwrap();
+        assert_eq!(tv.mode, Mode::Channel);
+        let action = action_rx.try_recv().unwrap();
+        assert_eq!(action, Action::SetInput("old".to_string()));
+        tv.handle_action(&action).unwrap();
+        assert_eq!(tv.current_pattern, "old");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn test_vim_mode_visual_selection() {
+        use crate::channels::prototypes::ChannelPrototype;
+        use crate::cli::PostProcessedCli;
+        use crate::television::VimMode;
+
+        let mut config = crate::config::Config::default();
+        config.vim.enabled = true;
+        let prototype =
+            ChannelPrototype::new("test", "printf 'a\\nb\\nc\\nd\\n'");
+        let cable = Cable::from_prototypes(vec![prototype.clone()]);
+        let layered_config =
+            ConfigLayers::new(config, prototype, PostProcessedCli::default());
+        let dir = tempdir().unwrap();
+        let frecency = Arc::new(Frecency::new(100, dir.path()));
+        let mut tv = Television::new(
+            tokio::sync::mpsc::unbounded_channel().0,
+            layered_config,
+            cable,
+            frecency,
+        );
+        assert_eq!(tv.vim_mode, Some(VimMode::Insert));
+        assert!(tv.accepts_text_input());
+
+        tv.channel.load();
+        for _ in 0..100 {
+            tv.update(&Action::Tick).unwrap();
+            if !tv.channel.running() && tv.channel.result_count() == 4 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(tv.channel.result_count(), 4);
+
+        // visual mode can only be entered from normal mode
+        tv.update(&Action::ToggleVisualMode).unwrap();
+        assert_eq!(tv.vim_mode, Some(VimMode::Insert));
+
+        tv.update(&Action::EnterNormalMode).unwrap();
+        assert!(!tv.accepts_text_input());
+        tv.update(&Action::SelectNextEntry).unwrap();
+        tv.update(&Action::ToggleVisualMode).unwrap();
+        assert_eq!(tv.vim_mode, Some(VimMode::Visual));
+        assert_eq!(tv.channel.selected_entries().len(), 1);
+
+        tv.update(&Action::SelectLastEntry).unwrap();
+        assert_eq!(tv.channel.selected_entries().len(), 3);
+        tv.update(&Action::SelectPrevEntry).unwrap();
+        assert_eq!(tv.channel.selected_entries().len(), 2);
+
+        tv.update(&Action::ToggleVisualMode).unwrap();
+        assert_eq!(tv.vim_mode, Some(VimMode::Normal));
+        tv.update(&Action::SelectFirstEntry).unwrap();
+        // leaving visual mode keeps the selection
+        assert_eq!(tv.channel.selected_entries().len(), 2);
+
+        tv.update(&Action::SelectNextEntry).unwrap();
+        tv.update(&Action::DeselectEntry).unwrap();
+        assert_eq!(tv.channel.selected_entries().len(), 1);
+
+        tv.update(&Action::EnterInsertMode).unwrap();
+        assert!(tv.accepts_text_input());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn test_mouse_selection_and_preview_resize() {
+        use crate::channels::prototypes::ChannelPrototype;
+        use crate::cli::PostProcessedCli;
+        use crate::render::UiState;
+        use crate::screen::layout::Layout;
+        use ratatui::layout::Rect;
+
+        let prototype =
+            ChannelPrototype::new("test", "printf 'a\\nb\\nc\\nd\\n'");
+        let cable = Cable::from_prototypes(vec![prototype.clone()]);
+        let layered_config = ConfigLayers::new(
+            crate::config::Config::default(),
+            prototype,
+            PostProcessedCli::default(),
+        );
+        let dir = tempdir().unwrap();
+        let frecency = Arc::new(Frecency::new(100, dir.path()));
+        let (action_tx, mut action_rx) =
+            tokio::sync::mpsc::unbounded_channel();
+        let mut tv =
+            Television::new(action_tx, layered_config, cable, frecency);
+        // results listed from row 4 (below the input bar and the border)
+        tv.update_ui_state(UiState::new(Layout::new(
+            Rect::new(0, 3, 40, 20),
+            Rect::new(0, 0, 40, 3),
+            Some(Rect::new(40, 0, 40, 23)),
+            None,
+            None,
+            None,
+            None,
+        )));
+
+        tv.channel.load();
+        for _ in 0..100 {
+            tv.update(&Action::Tick).unwrap();
+            if !tv.channel.running() && tv.channel.result_count() == 4 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(tv.channel.result_count(), 4);
+
+        tv.update(&Action::SelectEntryAtPosition(5, 5)).unwrap();
+        assert_eq!(tv.results_picker.selected(), Some(1));
+        assert!(action_rx.try_recv().is_err());
+        // clicking the same entry again confirms it
+        tv.update(&Action::SelectEntryAtPosition(5, 5)).unwrap();
+        assert_eq!(action_rx.try_recv().unwrap(), Action::ConfirmSelection);
+
+        tv.update(&Action::ToggleSelectionAtPosition(5, 6)).unwrap();
+        assert_eq!(tv.results_picker.selected(), Some(2));
+        assert_eq!(tv.channel.selected_entries().len(), 1);
+        tv.update(&Action::SelectRangeToPosition(5, 4)).unwrap();
+        assert_eq!(tv.results_picker.selected(), Some(0));
+        assert_eq!(tv.channel.selected_entries().len(), 3);
+
+        // clicks below the last entry are ignored
+        tv.update(&Action::SelectEntryAtPosition(5, 15)).unwrap();
+        assert_eq!(tv.results_picker.selected(), Some(0));
+
+        // dragging the border between the results and the preview
+        tv.update(&Action::MouseClickAt(40, 10)).unwrap();
+        tv.update(&Action::MouseDragTo(20, 10)).unwrap();
+        assert_eq!(tv.merged_config.preview_panel_size, 75);
+        tv.update(&Action::MouseRelease).unwrap();
+        tv.update(&Action::MouseDragTo(60, 10)).unwrap();
+        assert_eq!(tv.merged_config.preview_panel_size, 75);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn test_notifications() {
+        use crate::channels::prototypes::ChannelPrototype;
+        use crate::cli::PostProcessedCli;
+        use crate::notifications::Severity;
+        use std::time::Instant;
+
+        let prototype = ChannelPrototype::new("test", "printf 'a\\nb\\n'");
+        let cable = Cable::from_prototypes(vec![prototype.clone()]);
+        let layered_config = ConfigLayers::new(
+            crate::config::Config::default(),
+            prototype,
+            PostProcessedCli::default(),
+        );
+        let dir = tempdir().unwrap();
+        let frecency = Arc::new(Frecency::new(100, dir.path()));
+        let mut tv = Television::new(
+            tokio::sync::mpsc::unbounded_channel().0,
+            layered_config,
+            cable,
+            frecency,
+        );
+
+        // raising a notification triggers a render
+        assert_eq!(
+            tv.update(&Action::Error("boom".to_string())).unwrap(),
+            Some(Action::Render)
+        );
+        let active: Vec<_> = tv.notifications.active(Instant::now()).collect();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].severity, Severity::Error);
+
+        // a completed reload is reported
+        tv.update(&Action::ReloadSource).unwrap();
+        assert!(tv.pending_reload && tv.channel.reloading());
+        for _ in 0..100 {
+            tv.update(&Action::Tick).unwrap();
+            if tv.notifications.len() == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let latest = tv.notifications.history().next().unwrap();
+        assert_eq!(latest.severity, Severity::Info);
+        assert_eq!(latest.message, "Reloaded 2 entries");
+
+        // the history hides the toasts and captures input until closed
+        tv.update(&Action::ToggleNotificationHistory).unwrap();
+        assert!(tv.has_blocking_popup());
+        assert_eq!(tv.notifications.active(Instant::now()).count(), 0);
+        tv.update(&Action::SelectNextEntry).unwrap();
+        assert_eq!(tv.notification_history_popup.as_ref().unwrap().scroll, 1);
+        tv.update(&Action::SelectNextEntry).unwrap();
+        assert_eq!(tv.notification_history_popup.as_ref().unwrap().scroll, 1);
+        tv.update(&Action::Quit).unwrap();
+        assert!(tv.notification_history_popup.is_none());
+        assert_eq!(tv.notifications.len(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn test_matching_toggles() {
+        use crate::channels::prototypes::ChannelPrototype;
+        use crate::cli::PostProcessedCli;
+        use crate::matcher::config::CaseSensitivity;
+
+        let prototype = ChannelPrototype::new(
+            "test",
+            "printf 'Foo.rs\\nfoo.rs\\nfxoxo\\n'",
+        );
+        let cable = Cable::from_prototypes(vec![prototype.clone()]);
+        let layered_config = ConfigLayers::new(
+            crate::config::Config::default(),
+            prototype,
+            PostProcessedCli::default(),
+        );
+        let dir = tempdir().unwrap();
+        let frecency = Arc::new(Frecency::new(100, dir.path()));
+        let mut tv = Television::new(
+            tokio::sync::mpsc::unbounded_channel().0,
+            layered_config,
+            cable,
+            frecency,
+        );
+        tv.channel.load();
+
+        let result_count = async |tv: &mut Television| {
+            for _ in 0..100 {
+                tv.update(&Action::Tick).unwrap();
+                if !tv.channel.running() {
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+            tv.channel.result_count()
+        };
+
+        tv.set_pattern("foo");
+        assert_eq!(tv.matching_mode, MatchingMode::Fuzzy);
+        assert_eq!(result_count(&mut tv).await, 3);
+
+        tv.update(&Action::CycleMatchMode).unwrap();
+        assert_eq!(tv.matching_mode, MatchingMode::Substring);
+        assert_eq!(result_count(&mut tv).await, 2);
+
+        tv.update(&Action::ToggleCaseSensitivity).unwrap();
+        assert_eq!(tv.case_sensitivity, CaseSensitivity::Sensitive);
+        assert_eq!(result_count(&mut tv).await, 1);
+
+        tv.update(&Action::CycleMatchMode).unwrap();
+        assert_eq!(tv.matching_mode, MatchingMode::Regex);
+        tv.set_pattern("^[fF]o+\\.");
+        assert_eq!(result_count(&mut tv).await, 2);
+
+        assert!(!tv.no_sort);
+        tv.update(&Action::ToggleSort).unwrap();
+        assert!(tv.no_sort);
+        assert!(tv.dump_context().tv_state.no_sort);
+    }
+
+    #[test]
+    fn test_confirm_action_popup() {
+        use crate::channels::{entry::Entry, prototypes::ActionSpec};
+        use rustc_hash::FxHashSet;
+
+        let action_spec: ActionSpec = toml::from_str(
+            r#"
+            command = "rm {}"
+            confirm = "Delete {} ?"
+            "#,
+        )
+        .unwrap();
+        let entries = FxHashSet::from_iter([
+            Entry::new("b.txt".to_string()),
+            Entry::new("a.txt".to_string()),
+        ]);
+
+        let popup = ConfirmActionPopup::new(
+            "delete",
+            action_spec.confirm.as_ref().unwrap(),
+            &action_spec,
+            &entries,
+            Vec::new(),
+        )
+        .unwrap();
+
+        assert_eq!(popup.action_name, "delete");
+        assert!(popup.message.starts_with("Delete '"));
+        assert!(popup.message.contains("'a.txt'"));
+        assert!(popup.command.starts_with("rm '"));
+        assert_eq!(popup.entries, vec!["a.txt", "b.txt"]);
+    }
+
+    #[test]
+    fn test_action_input_popup_collects_values() {
+        use crate::channels::prototypes::ActionInput;
+        use crate::utils::input::InputRequest;
+
+        let inputs = vec![
+            ActionInput {
+                name: "branch".to_string(),
+                prompt: Some("New branch name".to_string()),
+                default: None,
+            },
+            ActionInput {
+                name: "remote".to_string(),
+                prompt: None,
+                default: Some("origin".to_string()),
+            },
+        ];
+        let mut popup = ActionInputPopup::new("branch", &inputs);
+        assert_eq!(popup.current().unwrap().name, "branch");
+
+        for c in "dev".chars() {
+            popup.input.handle(InputRequest::InsertChar(c));
+        }
+        assert_eq!(popup.submit(), None);
+        assert_eq!(popup.current().unwrap().name, "remote");
+        assert_eq!(popup.input.value(), "");
+
+        // empty values fall back to the input's default
+        assert_eq!(
+            popup.submit(),
+            Some(vec![
+                ("branch".to_string(), "dev".to_string()),
+                ("remote".to_string(), "origin".to_string()),
+            ])
+        );
+    }
 }
@@ -7,11 +7,16 @@ use std::{
 use anyhow::Result;
 use crossterm::{
     cursor,
-    event::{DisableMouseCapture, EnableMouseCapture},
+    event::{
+        DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste,
+        EnableMouseCapture, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
     execute,
     terminal::{
         ClearType, EnterAlternateScreen, LeaveAlternateScreen, ScrollUp,
         disable_raw_mode, enable_raw_mode, is_raw_mode_enabled,
+        supports_keyboard_enhancement,
     },
 };
 use ratatui::{
@@ -51,6 +56,12 @@ where
 {
     pub terminal: ratatui::Terminal<CrosstermBackend<W>>,
     pub viewport: Viewport,
+    /// Whether to ask the terminal for the kitty keyboard protocol when
+    /// entering the TUI
+    pub keyboard_enhancements: bool,
+    /// Whether the enhancement flags were pushed and need to be popped on
+    /// exit
+    keyboard_enhancements_active: bool,
 }
 
 pub const TESTING_ENV_VAR: &str = "TV_TEST";
@@ -124,7 +135,12 @@ where
 
         options.viewport = viewport.clone();
         let terminal = Terminal::with_options(backend, options)?;
-        Ok(Self { terminal, viewport })
+        Ok(Self {
+            terminal,
+            viewport,
+            keyboard_enhancements: false,
+            keyboard_enhancements_active: false,
+        })
     }
 
     /// Handles scrolling logic when there's insufficient space for the requested height.
@@ -243,6 +259,22 @@ where
         let backend = self.terminal.backend_mut();
 
         execute!(backend, EnableMouseCapture)?;
+        execute!(backend, EnableBracketedPaste)?;
+
+        if self.keyboard_enhancements
+            && !self.keyboard_enhancements_active
+            && supports_keyboard_enhancement().unwrap_or(false)
+        {
+            debug!("Enabling keyboard enhancements");
+            execute!(
+                backend,
+                PushKeyboardEnhancementFlags(
+                    KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                        | KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS
+                )
+            )?;
+            self.keyboard_enhancements_active = true;
+        }
 
         if self.viewport == Viewport::Fullscreen {
             execute!(backend, EnterAlternateScreen)?;
@@ -270,6 +302,12 @@ where
 
             execute!(backend, cursor::Show)?;
             execute!(backend, DisableMouseCapture)?;
+            execute!(backend, DisableBracketedPaste)?;
+
+            if self.keyboard_enhancements_active {
+                execute!(backend, PopKeyboardEnhancementFlags)?;
+                self.keyboard_enhancements_active = false;
+            }
 
             if self.viewport == Viewport::Fullscreen {
                 execute!(backend, LeaveAlternateScreen)?;
@@ -154,6 +154,76 @@ pub fn format_command(
     }
 }
 
+/// The result of running an action in `ExecutionMode::Capture`.
+#[derive(Debug, Clone)]
+pub struct CapturedOutput {
+    /// The fully formatted command that was run
+    pub command: String,
+    pub stdout: String,
+    pub stderr: String,
+    pub status: ExitStatus,
+}
+
+/// Format the command of an action for the given entries.
+///
+/// Input values are quoted for the action's shell, like entries are for
+/// `{}`.
+pub fn format_action_command(
+    action_spec: &ActionSpec,
+    entries: &FxHashSet<Entry>,
+) -> Result<String> {
+    let template: &Template = action_spec.command.get_nth(0).template();
+    let formatted_command =
+        format_command(entries, template, &action_spec.separator)?;
+    let shell = action_spec
+        .command
+        .shell
+        .unwrap_or_else(|| Shell::from_env().unwrap_or_default());
+    action_spec.fill_inputs(&formatted_command, |value| quote(value, shell))
+}
+
+/// Quote `value` so that it's passed as is in a command run by `shell`.
+fn quote(value: &str, shell: Shell) -> Result<String> {
+    match shell {
+        Shell::Bash | Shell::Zsh => {
+            Ok(format!("'{}'", value.replace('\'', r"'\''")))
+        }
+        // backslashes escape quotes and themselves in fish
+        Shell::Fish => Ok(format!(
+            "'{}'",
+            value.replace('\\', r"\\").replace('\'', r"\'")
+        )),
+        Shell::Psh => Ok(format!("'{}'", value.replace('\'', "''"))),
+        // single quoted strings can't contain quotes in nushell, and double
+        // quoted ones neither in cmd, which still expands variables in them
+        Shell::Nu if !value.contains('\'') => Ok(format!("'{value}'")),
+        Shell::Cmd if !value.contains(['"', '%']) => {
+            Ok(format!("\"{value}\""))
+        }
+        Shell::Nu | Shell::Cmd => {
+            anyhow::bail!("'{value}' can't be passed safely to {shell}")
+        }
+    }
+}
+
+/// Build the shell command for an action from the given entries.
+///
+/// Returns the formatted command string alongside the ready-to-run `Command`.
+fn build_action_command(
+    action_spec: &ActionSpec,
+    entries: &FxHashSet<Entry>,
+) -> Result<(String, Command)> {
+    let formatted_command = format_action_command(action_spec, entries)?;
+
+    let cmd = shell_command(
+        &formatted_command,
+        action_spec.command.interactive,
+        &action_spec.command.env,
+        action_spec.command.shell,
+    );
+    Ok((formatted_command, cmd))
+}
+
 /// Execute an external action with the appropriate execution mode and output handling
 ///
 /// Takes an `ActionSpec` and a set of entries, creates a command using the action's template,
@@ -169,36 +239,30 @@ pub fn format_command(
 /// # Behavior
 /// - `ExecutionMode::Execute` - make the current process become what the command does
 /// - `ExecutionMode::Fork` - spawns the command as a child process
+/// - `ExecutionMode::Capture` - runs the command with its output captured
+///   (see [`capture_action`] to retrieve that output)
 pub fn execute_action(
     action_spec: &ActionSpec,
     entries: &FxHashSet<Entry>,
 ) -> Result<ExitStatus> {
     debug!("Executing external action with {} entries", entries.len());
 
-    let template: &Template = action_spec.command.get_nth(0).template();
-    let formatted_command =
-        format_command(entries, template, &action_spec.separator)?;
+    if action_spec.mode == ExecutionMode::Capture {
+        return Ok(capture_action(action_spec, entries)?.status);
+    }
 
-    let mut cmd = shell_command(
-        &formatted_command,
-        action_spec.command.interactive,
-        &action_spec.command.env,
-        action_spec.command.shell,
-    );
+    let (_, mut cmd) = build_action_command(action_spec, entries)?;
 
     #[cfg(unix)]
-    match action_spec.mode {
-        ExecutionMode::Execute => {
-            attach_to_tty(&mut cmd)?;
+    {
+        attach_to_tty(&mut cmd)?;
+        if action_spec.mode == ExecutionMode::Execute {
             let err = cmd.exec();
             eprintln!("Failed to execute command: {}", err);
-            Err(err.into())
-        }
-        ExecutionMode::Fork => {
-            attach_to_tty(&mut cmd)?;
-            let mut child = cmd.spawn()?;
-            Ok(child.wait()?)
+            return Err(err.into());
         }
+        let mut child = cmd.spawn()?;
+        Ok(child.wait()?)
     }
 
     // On windows we can't replace the current process, so we always fork
@@ -218,6 +282,31 @@ pub fn execute_action(
     }
 }
 
+/// Run an external action without handing it the terminal and collect its output.
+///
+/// The command's stdin is closed so that it cannot interfere with the TUI, and both
+/// stdout and stderr are captured (lossily decoded as UTF-8).
+pub fn capture_action(
+    action_spec: &ActionSpec,
+    entries: &FxHashSet<Entry>,
+) -> Result<CapturedOutput> {
+    debug!("Capturing external action with {} entries", entries.len());
+
+    let (command, mut cmd) = build_action_command(action_spec, entries)?;
+    let output = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()?;
+
+    Ok(CapturedOutput {
+        command,
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        status: output.status,
+    })
+}
+
 #[cfg(unix)]
 fn attach_to_tty(cmd: &mut Command) -> Result<()> {
     use std::io::{IsTerminal, stderr, stdin, stdout};
@@ -331,6 +420,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_action_inputs_are_quoted() {
+        let mut entries = FxHashSet::default();
+        entries.insert(Entry::new("main".to_string()));
+        let mut action_spec: ActionSpec = toml::from_str(
+            r#"
+            command = "git branch {input.branch} {split: :0}"
+            shell = "bash"
+            inputs = [{ name = "branch" }]
+            "#,
+        )
+        .unwrap();
+
+        let command = |action_spec: &ActionSpec, value: &str| {
+            let spec = action_spec
+                .with_inputs(&[("branch".to_string(), value.to_string())])
+                .unwrap();
+            format_action_command(&spec, &entries)
+        };
+        assert_eq!(
+            command(&action_spec, "feature/x").unwrap(),
+            "git branch 'feature/x' main"
+        );
+        // quotes can't end the quoted string
+        assert_eq!(
+            command(&action_spec, "x'; rm -rf ~; echo '").unwrap(),
+            r"git branch 'x'\''; rm -rf ~; echo '\''' main"
+        );
+        // braces are not template syntax
+        assert_eq!(
+            command(&action_spec, "{} {split: :0}").unwrap(),
+            "git branch '{} {split: :0}' main"
+        );
+
+        action_spec.command.shell = Some(Shell::Psh);
+        assert_eq!(
+            command(&action_spec, "it's").unwrap(),
+            "git branch 'it''s' main"
+        );
+
+        // a trailing backslash can't escape the closing quote in fish
+        action_spec.command.shell = Some(Shell::Fish);
+        assert_eq!(
+            command(&action_spec, "x\\").unwrap(),
+            r"git branch 'x\\' main"
+        );
+        assert_eq!(
+            command(&action_spec, r"a\'; rm -rf ~ #").unwrap(),
+            r"git branch 'a\\\'; rm -rf ~ #' main"
+        );
+
+        action_spec.command.shell = Some(Shell::Cmd);
+        assert_eq!(
+            command(&action_spec, "a & b").unwrap(),
+            r#"git branch "a & b" main"#
+        );
+        assert!(command(&action_spec, "%PATH%").is_err());
+    }
+
     #[test]
     fn test_shell_command_powershell_interactive_args() {
         let envs = HashMap::new();
@@ -367,4 +515,26 @@ mod tests {
 
         assert_eq!(args, vec!["-i", "-c", "echo hi"]);
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_capture_action_collects_output_and_status() {
+        let mut entries = FxHashSet::default();
+        entries.insert(Entry::new("hello".to_string()));
+
+        let action_spec: ActionSpec = toml::from_str(
+            r#"
+            command = "echo {} && echo oops >&2 && exit 3"
+            mode = "capture"
+            shell = "bash"
+            "#,
+        )
+        .unwrap();
+
+        let output = capture_action(&action_spec, &entries).unwrap();
+        assert_eq!(output.command, "echo 'hello' && echo oops >&2 && exit 3");
+        assert_eq!(output.stdout, "hello\n");
+        assert_eq!(output.stderr, "oops\n");
+        assert_eq!(output.status.code(), Some(3));
+    }
 }
@@ -337,6 +337,37 @@ impl Input {
         }
     }
 
+    /// Insert a whole piece of text at the cursor in one go (e.g. pasted
+    /// text).
+    ///
+    /// Since the input holds a single line, trailing line breaks are dropped
+    /// and any other line break or control character is replaced with a
+    /// space.
+    pub fn insert_str(&mut self, text: &str) -> InputResponse {
+        let text: String = text
+            .replace("\r\n", "\n")
+            .trim_end_matches(['\r', '\n'])
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+        if text.is_empty() {
+            return None;
+        }
+        let inserted = text.chars().count();
+        self.value = self
+            .value
+            .chars()
+            .take(self.cursor)
+            .chain(text.chars())
+            .chain(self.value.chars().skip(self.cursor))
+            .collect();
+        self.cursor += inserted;
+        Some(StateChanged {
+            value: true,
+            cursor: true,
+        })
+    }
+
     /// Get a reference to the current value.
     pub fn value(&self) -> &str {
         self.value.as_str()
@@ -581,6 +612,31 @@ mod tests {
         assert_eq!(input.cursor(), 2);
     }
 
+    #[test]
+    fn insert_str() {
+        let mut input = Input::from(TEXT).with_cursor(5);
+
+        let resp = input.insert_str(" pasted\r\ntext\twith tabs\n");
+        assert_eq!(
+            resp,
+            Some(StateChanged {
+                value: true,
+                cursor: true,
+            })
+        );
+        assert_eq!(
+            input.value(),
+            "first pasted text with tabs second, third."
+        );
+        assert_eq!(input.cursor(), 27);
+
+        assert_eq!(input.insert_str("\n"), None);
+        assert_eq!(
+            input.value(),
+            "first pasted text with tabs second, third."
+        );
+    }
+
     #[test]
     fn multispace_characters() {
         let input: Input = "Ｈｅｌｌｏ, ｗｏｒｌｄ!".into();
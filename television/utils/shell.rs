@@ -4,11 +4,19 @@ use crate::{
 };
 use anyhow::Result;
 use clap::CommandFactory;
+use schemars::JsonSchema;
 use std::fmt::Display;
 use tracing::{debug, warn};
 
 #[derive(
-    Debug, Clone, Copy, PartialEq, Hash, serde::Serialize, serde::Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    JsonSchema,
 )]
 #[serde(rename_all = "lowercase")]
 pub enum Shell {
@@ -753,11 +753,29 @@ pub fn to_title_case(s: &str) -> String {
         .join(" ")
 }
 
+/// Formats a duration in seconds as a short relative time, e.g. `3d ago`.
+pub fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", seconds / 60),
+        3600..86400 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::channels::entry::Entry;
 
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(5), "just now");
+        assert_eq!(format_age(125), "2m ago");
+        assert_eq!(format_age(3 * 3600), "3h ago");
+        assert_eq!(format_age(40 * 86400), "40d ago");
+    }
+
     fn test_next_char_boundary(input: &str, start: usize, expected: usize) {
         let actual = next_char_boundary(input, start);
         assert_eq!(actual, expected);